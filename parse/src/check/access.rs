@@ -0,0 +1,172 @@
+use crate::{
+    check::{
+        check, expect_position,
+        infer::{is_fresh, stands_for},
+        infer_shape,
+        map::lookup_key,
+        named::unfold,
+        some_group, Context, TypeError,
+    },
+    expression::{
+        access::{FieldAccess, Index, Projection, Slice},
+        literal::Literal,
+        Expression, ExpressionKind,
+    },
+    types::interner::{Node, TypeId},
+};
+
+/// `complex.field` has the type of `field`. Imported modules are complexes of everything they
+/// `export`. `union.variant` has the type `variant` carries, if the union has been narrowed to
+/// just that variant.
+///
+/// A parameter without a type that has a field accessed is a complex with just that field.
+pub(crate) fn field_access(access: &FieldAccess, scope: &mut Context) -> Result<TypeId, TypeError> {
+    let found = check(&access.expr, scope)?;
+    let found = infer_shape(found, scope, |scope| {
+        let field = scope.fresh(&access.field);
+        scope.insert(Node::Complex(
+            vec![(access.field.clone(), field)].into_iter().collect(),
+        ))
+    })?;
+    match scope.node(found) {
+        Node::Complex(fields) if fields.contains_key(&access.field) => Ok(fields[&access.field]),
+        // what a variant carries can only be accessed once it's known to be that variant
+        Node::Union(variants) if variants.contains_key(&access.field) => {
+            if variants.len() == 1 {
+                Ok(variants[&access.field])
+            } else {
+                Err(TypeError::NotNarrowed {
+                    variant: access.field.clone(),
+                    found: scope.boxed(found),
+                })
+            }
+        }
+        _ => match scope.not_exported(found, &access.field) {
+            Some(module) => Err(TypeError::NotExported {
+                module: module.clone(),
+                name: access.field.clone(),
+            }),
+            None => Err(TypeError::NoField {
+                field: access.field.clone(),
+                found: scope.boxed(found),
+            }),
+        },
+    }
+}
+
+/// `tuple.n` has the type of the `n`th element, which has to exist. How many elements a
+/// parameter without a type has can't be worked out from this, so it's type can't be inferred.
+pub(crate) fn projection(
+    projection: &Projection,
+    scope: &mut Context,
+) -> Result<TypeId, TypeError> {
+    let found = check(&projection.expr, scope)?;
+    match scope.node(found) {
+        Node::Variable(name) if is_fresh(name) => Err(TypeError::CannotInfer(stands_for(name))),
+        Node::Tuple(element_types) => {
+            element_types
+                .get(projection.index)
+                .copied()
+                .ok_or(TypeError::IndexOutOfBounds {
+                    index: projection.index,
+                    length: element_types.len(),
+                })
+        }
+        _ => Err(TypeError::NotIndexable(scope.boxed(found))),
+    }
+}
+
+/// `group[index]` has the type of the elements of the group. Maps can be indexed too, see
+/// [`lookup_key`].
+///
+/// The index is only checked against the length if they're both literals, otherwise it's
+/// checked when it's evaluated. A parameter without a type that's indexed is a group.
+pub(crate) fn index(index: &Index, scope: &mut Context) -> Result<TypeId, TypeError> {
+    let found = check(&index.expr, scope)?;
+    let found = infer_shape(found, scope, some_group)?;
+    let element_type = match scope.node(found) {
+        Node::Group(element_type) => *element_type,
+        _ => {
+            let unfolded = unfold(found, scope);
+            return match scope.node(unfolded) {
+                Node::Map(key_type, value_type) => {
+                    lookup_key(*key_type, *value_type, &index.index, scope)
+                }
+                _ => Err(TypeError::NotIndexable(scope.boxed(found))),
+            };
+        }
+    };
+    let found = check(&index.index, scope)?;
+    expect_position(found, scope)?;
+    if let Some(found) = static_index(&index.index)? {
+        check_bounds(found, static_length(&index.expr), false)?;
+    }
+    Ok(element_type)
+}
+
+/// `group[start:end]` is another group of the same type. The end can be the length of the
+/// group, since it isn't included in the slice.
+pub(crate) fn slice(slice: &Slice, scope: &mut Context) -> Result<TypeId, TypeError> {
+    let element_type = group_element(&slice.expr, scope)?;
+    let length = static_length(&slice.expr);
+    let mut bound = |expr: &Option<Box<Expression>>| -> Result<Option<usize>, TypeError> {
+        match expr {
+            Some(expr) => {
+                let found = check(expr, scope)?;
+                expect_position(found, scope)?;
+                let found = static_index(expr)?;
+                if let Some(found) = found {
+                    check_bounds(found, length, true)?;
+                }
+                Ok(found)
+            }
+            None => Ok(None),
+        }
+    };
+    let start = bound(&slice.start)?;
+    let end = bound(&slice.end)?;
+    match (start, end) {
+        (Some(start), Some(end)) if start > end => Err(TypeError::InvalidSlice { start, end }),
+        _ => Ok(scope.insert(Node::Group(element_type))),
+    }
+}
+
+fn group_element(expr: &Expression, scope: &mut Context) -> Result<TypeId, TypeError> {
+    let found = check(expr, scope)?;
+    let found = infer_shape(found, scope, some_group)?;
+    match scope.node(found) {
+        Node::Group(element_type) => Ok(*element_type),
+        _ => Err(TypeError::NotIndexable(scope.boxed(found))),
+    }
+}
+
+/// The value of the index, if it's a number literal.
+fn static_index(expr: &Expression) -> Result<Option<usize>, TypeError> {
+    match &expr.kind {
+        ExpressionKind::Literal(Literal::Num(num)) => {
+            let value = num.value();
+            value
+                .to_usize()
+                .map(Some)
+                .ok_or(TypeError::InvalidIndex(value))
+        }
+        _ => Ok(None),
+    }
+}
+
+/// The length of the group, if it's a group literal.
+fn static_length(expr: &Expression) -> Option<usize> {
+    match &expr.kind {
+        ExpressionKind::Group(group) => Some(group.0.len()),
+        _ => None,
+    }
+}
+
+fn check_bounds(index: usize, length: Option<usize>, inclusive: bool) -> Result<(), TypeError> {
+    match length {
+        Some(length) if index > length || (index == length && !inclusive) => {
+            Err(TypeError::IndexOutOfBounds { index, length })
+        }
+        _ => Ok(()),
+    }
+}
@@ -0,0 +1,292 @@
+//! Exhaustiveness and reachability checking for patterns, based on the usefulness algorithm from
+//! "Warnings for pattern matching" (Maranget, 2007).
+//!
+//! A pattern is *useful* with respect to some earlier patterns if there's a value it matches that
+//! none of them do. An arm is unreachable if it's pattern isn't useful with respect to the arms
+//! before it, and the arms are exhaustive if `_` isn't useful with respect to all of them.
+
+use types::Number;
+
+use crate::{
+    check::{named::unfold, Context, TypeError},
+    eval::literal_value,
+    eval::Value,
+    pattern::Pattern,
+    types::{
+        interner::{Node, TypeId},
+        primitive::PrimitiveType,
+    },
+    Identifier,
+};
+
+/// Checks that every arm can match something the arms before it don't, and that between them
+/// they match every value of type `found`.
+pub(crate) fn check_arms(
+    patterns: &[&Pattern],
+    found: TypeId,
+    scope: &mut Context,
+) -> Result<(), TypeError> {
+    let found = unfold(found, scope);
+    if is_never(found, scope) {
+        return Ok(());
+    }
+    let mut rows = vec![];
+    for (arm, pattern) in patterns.iter().enumerate() {
+        let row = vec![lower(pattern, found, scope)];
+        if !useful(&rows, &row, &[found], scope) {
+            return Err(TypeError::UnreachableArm(arm));
+        }
+        rows.push(row);
+    }
+    if useful(&rows, &[Pat::Wild], &[found], scope) {
+        return Err(TypeError::NonExhaustive);
+    }
+    Ok(())
+}
+
+/// Whether `pattern` matches every value of type `found`, which it has to for it to be used in a
+/// binding.
+pub(crate) fn is_irrefutable(pattern: &Pattern, found: TypeId, scope: &mut Context) -> bool {
+    let found = unfold(found, scope);
+    if is_never(found, scope) {
+        return true;
+    }
+    let rows = [vec![lower(pattern, found, scope)]];
+    !useful(&rows, &[Pat::Wild], &[found], scope)
+}
+
+fn is_never(found: TypeId, scope: &Context) -> bool {
+    *scope.node(found) == Node::Primitive(PrimitiveType::Never)
+}
+
+/// A pattern with the names taken out, and the fields of complexes put in the same order as
+/// they are in the type, which is all that matters for working out which values it matches.
+#[derive(Debug, Clone)]
+enum Pat {
+    Wild,
+    Constructor(Constructor, Vec<Pat>),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Constructor {
+    Bln(bool),
+    Emp,
+    Num(Number),
+    /// A value of one of the integer types or a `byte`.
+    Int(i128),
+    Str(String),
+    /// Tuples and complexes only have one way to make them, so this is the only constructor for
+    /// both of them.
+    Record,
+    /// A group with exactly `length` elements, or at least `length` if `rest` is set.
+    Group {
+        length: usize,
+        rest: bool,
+    },
+    Variant(Identifier),
+}
+
+impl Constructor {
+    /// Whether every value made with `other` can also be made with `self`.
+    fn covers(&self, other: &Constructor) -> bool {
+        match (self, other) {
+            (
+                Constructor::Group { length, rest: true },
+                Constructor::Group {
+                    length: other_length,
+                    ..
+                },
+            ) => length <= other_length,
+            _ => self == other,
+        }
+    }
+}
+
+fn lower(pattern: &Pattern, found: TypeId, scope: &mut Context) -> Pat {
+    let found = unfold(found, scope);
+    match (pattern, scope.node(found).clone()) {
+        (Pattern::Wildcard, _) | (Pattern::Binding(..), _) => Pat::Wild,
+        (Pattern::Literal(literal), _) => Pat::Constructor(
+            match literal_value(literal) {
+                Value::Bln(bln) => Constructor::Bln(bln),
+                Value::Num(num) => Constructor::Num(num),
+                Value::Int(_, int) => Constructor::Int(int),
+                Value::Byte(byte) => Constructor::Int(byte as i128),
+                Value::Str(str) => Constructor::Str(str),
+                _ => Constructor::Emp,
+            },
+            vec![],
+        ),
+        (Pattern::Tuple(elements), Node::Tuple(element_types)) => Pat::Constructor(
+            Constructor::Record,
+            elements
+                .iter()
+                .zip(element_types)
+                .map(|(element, element_type)| lower(element, element_type, scope))
+                .collect(),
+        ),
+        (Pattern::Complex(fields), Node::Complex(field_types)) => Pat::Constructor(
+            Constructor::Record,
+            field_types
+                .into_iter()
+                .map(|(name, field_type)| {
+                    fields
+                        .iter()
+                        .find(|(field, _)| *field == name)
+                        .map_or(Pat::Wild, |(_, field)| lower(field, field_type, scope))
+                })
+                .collect(),
+        ),
+        (Pattern::Group(group), Node::Group(element_type)) => Pat::Constructor(
+            Constructor::Group {
+                length: group.elements.len(),
+                rest: group.rest.is_some(),
+            },
+            group
+                .elements
+                .iter()
+                .map(|element| lower(element, element_type, scope))
+                .collect(),
+        ),
+        (Pattern::Variant(name, found), Node::Union(variants)) => Pat::Constructor(
+            Constructor::Variant(name.clone()),
+            vec![lower(found, variants[name], scope)],
+        ),
+        // the pattern has already been checked against the type, so this can't happen
+        _ => Pat::Wild,
+    }
+}
+
+/// Whether there's a value of the types in `types` that `row` matches and none of `rows` do.
+fn useful(rows: &[Vec<Pat>], row: &[Pat], types: &[TypeId], scope: &mut Context) -> bool {
+    let (head, head_type) = match (row.first(), types.first()) {
+        (Some(head), Some(head_type)) => (head, unfold(*head_type, scope)),
+        _ => return rows.is_empty(),
+    };
+    if is_never(head_type, scope) {
+        return false;
+    }
+    // when nothing in the column looks inside the values there's no need to either, which is
+    // also what stops types that contain themselves from being split up forever
+    if matches!(head, Pat::Wild) && rows.iter().all(|row| matches!(row[0], Pat::Wild)) {
+        return useful(&without_head(rows), &row[1..], &types[1..], scope);
+    }
+    let constructors = match constructors(
+        scope.node(head_type),
+        rows.iter().map(|row| &row[0]).chain([head]),
+    ) {
+        Some(constructors) => constructors,
+        None => {
+            return match head {
+                // a literal of a type with infinitely many values
+                Pat::Constructor(constructor, _) => {
+                    specialized_useful(rows, row, types, constructor, scope)
+                }
+                // none of the literals can cover every value, so only the wildcards matter
+                Pat::Wild => useful(&without_head(rows), &row[1..], &types[1..], scope),
+            };
+        }
+    };
+    constructors.iter().any(|constructor| {
+        let matches = match head {
+            Pat::Wild => true,
+            Pat::Constructor(found, _) => found.covers(constructor),
+        };
+        matches && specialized_useful(rows, row, types, constructor, scope)
+    })
+}
+
+/// The rows that start with a wildcard, without it.
+fn without_head(rows: &[Vec<Pat>]) -> Vec<Vec<Pat>> {
+    rows.iter()
+        .filter(|row| matches!(row[0], Pat::Wild))
+        .map(|row| row[1..].to_vec())
+        .collect()
+}
+
+fn specialized_useful(
+    rows: &[Vec<Pat>],
+    row: &[Pat],
+    types: &[TypeId],
+    constructor: &Constructor,
+    scope: &mut Context,
+) -> bool {
+    let found = unfold(types[0], scope);
+    let field_types = field_types(scope.node(found), constructor);
+    let rows = rows
+        .iter()
+        .filter_map(|found| specialize(found, constructor, field_types.len()))
+        .collect::<Vec<_>>();
+    let row = specialize(row, constructor, field_types.len())
+        .expect("the row is only specialized by constructors it covers");
+    let types = field_types
+        .into_iter()
+        .chain(types[1..].iter().copied())
+        .collect::<Vec<_>>();
+    useful(&rows, &row, &types, scope)
+}
+
+/// The row with it's first pattern replaced by the fields of `constructor`, if it can match
+/// values made with `constructor`.
+fn specialize(row: &[Pat], constructor: &Constructor, arity: usize) -> Option<Vec<Pat>> {
+    let mut fields = match &row[0] {
+        Pat::Wild => vec![],
+        Pat::Constructor(found, fields) if found.covers(constructor) => fields.clone(),
+        Pat::Constructor(..) => return None,
+    };
+    fields.resize(arity, Pat::Wild);
+    fields.extend(row[1..].iter().cloned());
+    Some(fields)
+}
+
+/// Every constructor of `found`, or `None` if there are too many to list.
+///
+/// Groups can be any length, but the patterns can only tell apart lengths up to one more than
+/// the longest exact length they use, so everything past that is grouped into one constructor.
+fn constructors<'a>(
+    found: &Node,
+    column: impl Iterator<Item = &'a Pat>,
+) -> Option<Vec<Constructor>> {
+    match found {
+        Node::Primitive(PrimitiveType::Bln) => {
+            Some(vec![Constructor::Bln(true), Constructor::Bln(false)])
+        }
+        Node::Primitive(PrimitiveType::Emp) => Some(vec![Constructor::Emp]),
+        Node::Primitive(PrimitiveType::Never) => Some(vec![]),
+        Node::Tuple(_) | Node::Complex(_) => Some(vec![Constructor::Record]),
+        Node::Union(variants) => Some(variants.keys().cloned().map(Constructor::Variant).collect()),
+        Node::Group(_) => {
+            let longest = column
+                .filter_map(|found| match found {
+                    Pat::Constructor(Constructor::Group { length, rest }, _) => {
+                        Some(if *rest { *length } else { length + 1 })
+                    }
+                    _ => None,
+                })
+                .max()
+                .unwrap_or(0);
+            Some(
+                (0..=longest)
+                    .map(|length| Constructor::Group {
+                        length,
+                        rest: length == longest,
+                    })
+                    .collect(),
+            )
+        }
+        _ => None,
+    }
+}
+
+/// The types of the fields of a value of type `found` made with `constructor`.
+fn field_types(found: &Node, constructor: &Constructor) -> Vec<TypeId> {
+    match (found, constructor) {
+        (Node::Tuple(element_types), _) => element_types.clone(),
+        (Node::Complex(field_types), _) => field_types.values().copied().collect(),
+        (Node::Group(element_type), Constructor::Group { length, .. }) => {
+            vec![*element_type; *length]
+        }
+        (Node::Union(variants), Constructor::Variant(name)) => vec![variants[name]],
+        _ => vec![],
+    }
+}
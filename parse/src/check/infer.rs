@@ -0,0 +1,356 @@
+//! Working out the type arguments of a generic function from the arguments it's called with, and
+//! the types of lambda parameters that aren't written out from how they're used.
+//!
+//! Each type parameter of the function, and each parameter without a type, is given a fresh type
+//! variable, which is then solved for by unifying the types that it has to be the same as.
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use crate::{
+    check::{
+        named::{is_foldable, substitute, unfold},
+        Context,
+    },
+    types::{
+        interner::{Node, TypeId, TypeInterner},
+        primitive::PrimitiveType,
+    },
+    Identifier,
+};
+
+/// The fresh type variables made so far, and what they've been solved as.
+#[derive(Debug, Default)]
+pub(crate) struct Inference {
+    solved: BTreeMap<Identifier, TypeId>,
+    fresh: usize,
+}
+
+impl Inference {
+    /// Replaces every solved variable in `found` with what it's been solved as.
+    pub(crate) fn apply(&self, found: TypeId, interner: &mut TypeInterner) -> TypeId {
+        if self.solved.is_empty() {
+            return found;
+        }
+        interner.replace(found, &mut |interner, found| {
+            if !interner.has_variables(found) {
+                return Some(found);
+            }
+            match interner.node(found) {
+                Node::Variable(name) => self
+                    .solved
+                    .get(name)
+                    .copied()
+                    .map(|solved| self.apply(solved, interner)),
+                _ => None,
+            }
+        })
+    }
+}
+
+impl Context {
+    /// Replaces a generic function's type parameters with fresh type variables. Their names
+    /// have a `'` in them, so they can't be the same as any that have been written out.
+    ///
+    /// Also returns which variable each type parameter was replaced with.
+    pub(crate) fn instantiate(&mut self, function: TypeId) -> (TypeId, Vec<(Identifier, TypeId)>) {
+        let variables: Vec<_> = type_parameters(function, self)
+            .iter()
+            .map(|parameter| (parameter.clone(), self.fresh(parameter)))
+            .collect();
+        let instance =
+            replace_type_parameters(function, &variables.iter().cloned().collect(), self);
+        (instance, variables)
+    }
+
+    /// Replaces a generic function's type parameters with variables that can't be solved as
+    /// anything, for where a function has to work whatever it's type arguments are. Like fresh
+    /// variables they can't be the same as any that have been written out, since they have a `#`
+    /// in them.
+    fn skolemise(&mut self, function: TypeId) -> TypeId {
+        let substituted = type_parameters(function, self)
+            .iter()
+            .map(|parameter| {
+                self.inference.fresh += 1;
+                let name = Identifier(format!("{}#{}", parameter.0, self.inference.fresh));
+                (parameter.clone(), self.insert(Node::Variable(name)))
+            })
+            .collect();
+        replace_type_parameters(function, &substituted, self)
+    }
+
+    /// A new type variable, named after what it stands for.
+    pub(crate) fn fresh(&mut self, name: &Identifier) -> TypeId {
+        self.inference.fresh += 1;
+        let name = Identifier(format!("{}'{}", name.0, self.inference.fresh));
+        self.insert(Node::Variable(name))
+    }
+
+    /// How many fresh variables have been made so far, to be given to [`Context::made_since`].
+    pub(crate) fn mark(&self) -> usize {
+        self.inference.fresh
+    }
+
+    /// Whether there are any variables in `found` that haven't been solved yet and were made
+    /// after `mark`.
+    pub(crate) fn made_since(&mut self, found: TypeId, mark: usize) -> bool {
+        let found = self.apply(found);
+        fresh_variables(found, &self.interner)
+            .iter()
+            .any(|name| number(name) > mark)
+    }
+
+    /// Replaces every solved variable in `found` with what it's been solved as.
+    pub(crate) fn apply(&mut self, found: TypeId) -> TypeId {
+        self.inference.apply(found, &mut self.interner)
+    }
+
+    /// The variables that haven't been solved yet in the types of everything in scope, which are
+    /// the types of the parameters of the lambdas being checked.
+    pub(crate) fn environment(&mut self) -> BTreeSet<Identifier> {
+        let bound: Vec<_> = self.scope.values().copied().collect();
+        bound
+            .into_iter()
+            .flat_map(|found| {
+                let found = self.apply(found);
+                fresh_variables(found, &self.interner)
+            })
+            .collect()
+    }
+
+    /// Turns the variables in the type of a function that haven't been solved, and aren't in
+    /// `environment`, into type parameters, so that each call can have different type arguments.
+    /// They're named `A`, `B` and so on, in the order they were made in.
+    pub(crate) fn generalise(
+        &mut self,
+        function: TypeId,
+        environment: &BTreeSet<Identifier>,
+    ) -> TypeId {
+        let found = self.apply(function);
+        let mut variables = fresh_variables(found, &self.interner)
+            .into_iter()
+            .filter(|name| !environment.contains(name))
+            .collect::<Vec<_>>();
+        variables.sort_by_key(number);
+        let existing = type_parameters(function, self);
+        let mut names = (b'A'..=b'Z')
+            .map(|letter| Identifier((letter as char).to_string()))
+            .chain((1..).map(|number| Identifier(format!("T{}", number))))
+            .filter(|name| !existing.contains(name));
+        let mut type_parameters = existing.clone();
+        let mut substituted = BTreeMap::new();
+        for variable in variables {
+            let name = names.next().unwrap();
+            substituted.insert(variable, self.insert(Node::Variable(name.clone())));
+            type_parameters.push(name);
+        }
+        let found = substitute(found, &substituted, &mut self.interner);
+        match self.node(found).clone() {
+            Node::Function {
+                parameters,
+                return_type,
+                ..
+            } => self.insert(Node::Function {
+                type_parameters,
+                parameters,
+                return_type,
+            }),
+            _ => unreachable!("substituting variables doesn't change what kind of type it is"),
+        }
+    }
+
+    /// Whether there are any variables in `found` that haven't been solved yet.
+    pub(crate) fn is_unknown(&mut self, found: TypeId) -> bool {
+        if !self.interner.has_variables(found) {
+            return false;
+        }
+        let found = self.apply(found);
+        !fresh_variables(found, &self.interner).is_empty()
+    }
+
+    /// Makes the two types the same by solving for the variables in them, returning whether
+    /// that's possible.
+    pub(crate) fn unify(&mut self, expected: TypeId, found: TypeId) -> bool {
+        self.unify_assuming(expected, found, &mut vec![])
+    }
+
+    /// `assumed` is the pairs of named types that are already being unified further up, like in
+    /// [`same`](crate::check::named::same).
+    fn unify_assuming(
+        &mut self,
+        expected: TypeId,
+        found: TypeId,
+        assumed: &mut Vec<(TypeId, TypeId)>,
+    ) -> bool {
+        let (expected, found) = (self.apply(expected), self.apply(found));
+        if expected == found || *self.node(found) == Node::Primitive(PrimitiveType::Never) {
+            return true;
+        }
+        match (self.node(expected).clone(), self.node(found).clone()) {
+            // the newer of two variables is solved as the older one, so that variables made for a
+            // call are solved as the ones for the parameters of the lambdas around it, and not the
+            // other way around
+            (Node::Variable(first), Node::Variable(second))
+                if is_fresh(&first) && is_fresh(&second) =>
+            {
+                let (newer, older) = if number(&first) > number(&second) {
+                    (first, found)
+                } else {
+                    (second, expected)
+                };
+                self.inference.solved.insert(newer, older);
+                true
+            }
+            (Node::Variable(name), _) if is_fresh(&name) => self.solve(name, found),
+            (_, Node::Variable(name)) if is_fresh(&name) => self.solve(name, expected),
+            (Node::Named(name, arguments), Node::Named(found_name, found_arguments))
+                if name == found_name =>
+            {
+                arguments
+                    .into_iter()
+                    .zip(found_arguments)
+                    .all(|(expected, found)| self.unify_assuming(expected, found, assumed))
+            }
+            _ if is_foldable(expected, self) || is_foldable(found, self) => {
+                let pair = (expected, found);
+                if assumed.contains(&pair) {
+                    return true;
+                }
+                assumed.push(pair);
+                let (expected, found) = (unfold(expected, self), unfold(found, self));
+                !is_foldable(expected, self)
+                    && !is_foldable(found, self)
+                    && self.unify_assuming(expected, found, assumed)
+            }
+            (Node::Tuple(expected), Node::Tuple(found)) => {
+                expected.len() == found.len()
+                    && expected
+                        .into_iter()
+                        .zip(found)
+                        .all(|(expected, found)| self.unify_assuming(expected, found, assumed))
+            }
+            (Node::Group(expected), Node::Group(found)) => {
+                self.unify_assuming(expected, found, assumed)
+            }
+            (Node::Map(expected_key, expected_value), Node::Map(found_key, found_value)) => {
+                self.unify_assuming(expected_key, found_key, assumed)
+                    && self.unify_assuming(expected_value, found_value, assumed)
+            }
+            // a union can be given where one with more variants is expected
+            (Node::Union(expected), Node::Union(found)) => {
+                found
+                    .into_iter()
+                    .all(|(name, found)| match expected.get(&name) {
+                        Some(expected) => self.unify_assuming(*expected, found, assumed),
+                        None => false,
+                    })
+            }
+            (Node::Complex(expected), Node::Complex(found)) => {
+                expected.len() == found.len()
+                    && expected.into_iter().zip(found).all(
+                        |((expected_name, expected), (found_name, found))| {
+                            expected_name == found_name
+                                && self.unify_assuming(expected, found, assumed)
+                        },
+                    )
+            }
+            (
+                Node::Function {
+                    parameters: expected_parameters,
+                    ..
+                },
+                Node::Function {
+                    parameters: found_parameters,
+                    ..
+                },
+            ) if expected_parameters.len() == found_parameters.len() => {
+                // generic functions can be passed where a function is expected, with their own
+                // type arguments worked out too, but where a generic function is expected the
+                // function that's given has to work for every type argument
+                let expected = self.skolemise(expected);
+                let (found, _) = self.instantiate(found);
+                let (expected, found) = (self.node(expected).parts(), self.node(found).parts());
+                expected
+                    .into_iter()
+                    .zip(found)
+                    .all(|(expected, found)| self.unify_assuming(expected, found, assumed))
+            }
+            _ => false,
+        }
+    }
+
+    /// Solves the fresh variable `name` as `found`.
+    fn solve(&mut self, name: Identifier, found: TypeId) -> bool {
+        // a type can't be solved as something that contains itself
+        if fresh_variables(found, &self.interner).contains(&name) {
+            return false;
+        }
+        self.inference.solved.insert(name, found);
+        true
+    }
+}
+
+fn type_parameters(function: TypeId, scope: &Context) -> Vec<Identifier> {
+    match scope.node(function) {
+        Node::Function {
+            type_parameters, ..
+        } => type_parameters.clone(),
+        _ => vec![],
+    }
+}
+
+fn replace_type_parameters(
+    function: TypeId,
+    substituted: &BTreeMap<Identifier, TypeId>,
+    scope: &mut Context,
+) -> TypeId {
+    let node = match scope.node(function) {
+        Node::Function {
+            parameters,
+            return_type,
+            ..
+        } => Node::Function {
+            type_parameters: vec![],
+            parameters: parameters.clone(),
+            return_type: *return_type,
+        },
+        _ => return function,
+    };
+    let node = node.map(|part| substitute(part, substituted, &mut scope.interner));
+    scope.insert(node)
+}
+
+pub(crate) fn is_fresh(name: &Identifier) -> bool {
+    name.0.contains('\'')
+}
+
+/// The order the fresh variable was made in.
+fn number(name: &Identifier) -> usize {
+    name.0
+        .rsplit('\'')
+        .next()
+        .and_then(|number| number.parse().ok())
+        .unwrap_or(0)
+}
+
+/// What a fresh variable stands for, which is the name of the type parameter or parameter it was
+/// made for.
+pub(crate) fn stands_for(name: &Identifier) -> Identifier {
+    Identifier(name.0.split('\'').next().unwrap_or_default().into())
+}
+
+pub(crate) fn fresh_variables(found: TypeId, interner: &TypeInterner) -> BTreeSet<Identifier> {
+    let mut variables = BTreeSet::new();
+    let mut parts = vec![found];
+    while let Some(found) = parts.pop() {
+        if !interner.has_variables(found) {
+            continue;
+        }
+        match interner.node(found) {
+            Node::Variable(name) if is_fresh(name) => {
+                variables.insert(name.clone());
+            }
+            node => parts.extend(node.parts()),
+        }
+    }
+    variables
+}
@@ -0,0 +1,139 @@
+//! Map types, and looking up and inserting keys in them.
+
+use crate::{
+    check::{
+        check, check_against,
+        infer::{is_fresh, stands_for},
+        named::{key_parameters, unfold},
+        union::optional,
+        Context, TypeError,
+    },
+    expression::{
+        map::{Insert, MapLiteral},
+        Expression,
+    },
+    types::{
+        interner::{Node, TypeId},
+        map::Map,
+        Type,
+    },
+};
+
+/// Checks that values of type `found` can be used as map keys.
+///
+/// A type parameter of a generic function can be used as a key, as long as the function's type
+/// shows that it is, by having a map with it as the key type in it. Where the function is called
+/// it's type arguments are checked like any other key type, see [`check_keys`]. The type of a
+/// lambda parameter that isn't written out has to be known by the time it's used as a key.
+pub(crate) fn check_key(found: TypeId, scope: &mut Context) -> Result<(), TypeError> {
+    let found = scope.apply(found);
+    let found = unfold(found, scope);
+    match scope.node(found) {
+        Node::Variable(name) if is_fresh(name) => Err(TypeError::CannotInfer(stands_for(name))),
+        Node::Variable(name) => {
+            let name = name.clone();
+            scope.keys.insert(name);
+            Ok(())
+        }
+        Node::Primitive(primitive) if Map::is_key(&Type::Primitive(primitive.clone())) => Ok(()),
+        _ => Err(TypeError::InvalidKey(scope.boxed(found))),
+    }
+}
+
+/// Checks every key type in `found`, which includes the type arguments of named types that are
+/// used as keys in their declarations.
+pub(crate) fn check_keys(found: TypeId, scope: &mut Context) -> Result<(), TypeError> {
+    match scope.node(found).clone() {
+        Node::Map(key, value) => {
+            check_key(key, scope)?;
+            check_keys(key, scope)?;
+            check_keys(value, scope)
+        }
+        Node::Named(name, arguments) => {
+            for (ind, argument) in arguments.into_iter().enumerate() {
+                if key_parameters(&name, scope).contains(&ind) {
+                    check_key(argument, scope)?;
+                }
+                check_keys(argument, scope)?;
+            }
+            Ok(())
+        }
+        // a generic function type's own type parameters can be keys in it, since it shows that
+        // they are
+        Node::Function {
+            type_parameters,
+            parameters,
+            return_type,
+        } => {
+            let outer_keys = std::mem::take(&mut scope.keys);
+            let checked = parameters
+                .into_iter()
+                .chain(Some(return_type))
+                .try_for_each(|part| check_keys(part, scope));
+            let mut keys = std::mem::replace(&mut scope.keys, outer_keys);
+            for parameter in &type_parameters {
+                keys.remove(parameter);
+            }
+            scope.keys.extend(keys);
+            checked
+        }
+        node => node
+            .parts()
+            .into_iter()
+            .try_for_each(|part| check_keys(part, scope)),
+    }
+}
+
+/// Every key has to have the same type as the first one, which has to be a type that can be a
+/// key, and likewise every value the same type as the first one.
+pub(crate) fn map_literal(literal: &MapLiteral, scope: &mut Context) -> Result<TypeId, TypeError> {
+    let mut entries = literal.0.iter();
+    let (key_type, value_type) = match entries.next() {
+        Some((key, value)) => (check(key, scope)?, check(value, scope)?),
+        None => return Err(TypeError::EmptyMap),
+    };
+    check_key(key_type, scope)?;
+    for (key, value) in entries {
+        check_against(key, key_type, scope)?;
+        check_against(value, value_type, scope)?;
+    }
+    Ok(scope.insert(Node::Map(key_type, value_type)))
+}
+
+/// Checks a map literal against the map type that's expected, which lets it be empty.
+pub(crate) fn check_map_against(
+    literal: &MapLiteral,
+    key_type: TypeId,
+    value_type: TypeId,
+    scope: &mut Context,
+) -> Result<(), TypeError> {
+    for (key, value) in &literal.0 {
+        check_against(key, key_type, scope)?;
+        check_against(value, value_type, scope)?;
+    }
+    Ok(())
+}
+
+/// `insert[[map, key, value]]` has the same type as `map`.
+pub(crate) fn insert(insert: &Insert, scope: &mut Context) -> Result<TypeId, TypeError> {
+    let found = check(&insert.map, scope)?;
+    let unfolded = unfold(found, scope);
+    let (key_type, value_type) = match scope.node(unfolded) {
+        Node::Map(key_type, value_type) => (*key_type, *value_type),
+        _ => return Err(TypeError::NotAMap(scope.boxed(found))),
+    };
+    check_against(&insert.key, key_type, scope)?;
+    check_against(&insert.value, value_type, scope)?;
+    Ok(found)
+}
+
+/// `map[key]` is a `V?`, which is `none` if the key isn't in the map.
+pub(crate) fn lookup_key(
+    key_type: TypeId,
+    value_type: TypeId,
+    key: &Expression,
+    scope: &mut Context,
+) -> Result<TypeId, TypeError> {
+    check_against(key, key_type, scope)?;
+    Ok(optional(value_type, scope))
+}
@@ -0,0 +1,2296 @@
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fmt,
+    ops::{Deref, DerefMut},
+};
+
+use types::Number;
+
+use crate::{
+    check::{
+        access::{field_access, index, projection, slice},
+        exhaustive::{check_arms, is_irrefutable},
+        infer::{fresh_variables, is_fresh, stands_for, Inference},
+        map::{check_keys, check_map_against, insert, map_literal},
+        named::{
+            declaration, declare, key_variables, private_type_slot, qualify, resolve, same,
+            type_slot, unfold, with_variables,
+        },
+        pattern::bind_pattern,
+        union::{
+            check_variant_against, is_narrower, is_variant, merge, narrowing, variant_literal,
+        },
+    },
+    expression::{
+        binary_operations::{BinaryOperation, BinaryOperator},
+        block::{Block, Return},
+        complex::ComplexLiteral,
+        conditional::Conditional,
+        conversion::Conversion,
+        function_call::FunctionCall,
+        group::GroupLiteral,
+        lambda::Lambda,
+        literal::Literal,
+        looping::For,
+        matching::Match,
+        pipeline::Pipeline,
+        unary_operations::{UnaryOperation, UnaryOperator},
+        Expression, ExpressionKind,
+    },
+    pattern::Pattern,
+    pointer::{Pointer, Span},
+    program::Program,
+    scope::Scope,
+    statement::{binding::Binding, type_declaration::TypeDeclaration, Statement},
+    types::{
+        complex::Complex,
+        interner::{Node, TypeId, TypeInterner},
+        primitive::PrimitiveType,
+        Type,
+    },
+    Identifier,
+};
+
+mod access;
+mod exhaustive;
+mod infer;
+mod map;
+pub(crate) mod named;
+mod operator;
+mod pattern;
+pub(crate) mod typed;
+mod union;
+
+#[derive(Debug, Clone, PartialEq)]
+#[non_exhaustive]
+pub enum TypeError {
+    /// An operand or argument didn't have the type it was required to have.
+    Mismatch {
+        expected: Box<Type>,
+        found: Box<Type>,
+    },
+    /// A name was used that isn't bound in the current scope.
+    Unbound(Identifier),
+    /// Something that isn't a function was called.
+    NotAFunction(Box<Type>),
+    /// A function was called with the wrong number of arguments.
+    ArgumentCount { expected: usize, found: usize },
+    /// The same field was given twice in a complex literal.
+    DuplicateField(Identifier),
+    /// An empty group was used somewhere that doesn't say what type it's elements should be.
+    EmptyGroup,
+    /// A field was accessed on something that isn't a complex with that field.
+    NoField { field: Identifier, found: Box<Type> },
+    /// Something other than a tuple was projected, or something other than a group was indexed
+    /// or sliced.
+    NotIndexable(Box<Type>),
+    /// A tuple projection, or an index into a group literal, was past the end.
+    IndexOutOfBounds { index: usize, length: usize },
+    /// A literal index that was negative or not a whole number.
+    InvalidIndex(Number),
+    /// A slice where the start is after the end.
+    InvalidSlice { start: usize, end: usize },
+    /// `return` was used outside of a function.
+    ReturnOutsideFunction,
+    /// A pattern that can't match values of this type.
+    InvalidPattern(Box<Type>),
+    /// The same name was bound twice in one pattern.
+    DuplicateBinding(Identifier),
+    /// A pattern in a binding that doesn't match every value it could be given.
+    RefutablePattern,
+    /// The arms of a `match` don't cover every value.
+    NonExhaustive,
+    /// An arm of a `match` that can't be reached, because the arms before it already match
+    /// everything it does. The arms are counted from 0.
+    UnreachableArm(usize),
+    /// An error in one of the stages of a pipeline, which starts at `ptr`.
+    InStage { ptr: Pointer, error: Box<TypeError> },
+    /// Something that can't be turned into text was concatenated or interpolated.
+    NotPrintable(Box<Type>),
+    /// Something other than a group was looped over.
+    NotIterable(Box<Type>),
+    /// `break` or `continue` was used outside of a loop.
+    BreakOutsideLoop,
+    /// A name that the module imported as `module` binds or declares, but doesn't `export`.
+    NotExported {
+        module: Identifier,
+        name: Identifier,
+    },
+    /// A type name that hasn't been declared.
+    UnknownType(Identifier),
+    /// The same type name was declared twice in one module.
+    DuplicateType(Identifier),
+    /// A type declaration that only names other named types, which end up naming it again.
+    CyclicType(Identifier),
+    /// A named type was given a different number of type arguments than it has type parameters.
+    TypeArgumentCount {
+        name: Identifier,
+        expected: usize,
+        found: usize,
+    },
+    /// A type parameter of a generic function that the arguments it was called with don't say
+    /// anything about.
+    CannotInfer(Identifier),
+    /// A variant was made, checked for or matched that isn't one of the union's.
+    NoVariant {
+        variant: Identifier,
+        found: Box<Type>,
+    },
+    /// What a variant carries was accessed on a union that isn't known to be that variant.
+    NotNarrowed {
+        variant: Identifier,
+        found: Box<Type>,
+    },
+    /// A type operator was used on something that isn't a complex type.
+    NotComplex(Box<Type>),
+    /// Both types given to `merge` have the field, with different types.
+    ConflictingField {
+        field: Identifier,
+        first: Box<Type>,
+        second: Box<Type>,
+    },
+    /// Something other than a `num`, an integer or a `byte` was converted.
+    NotConvertible(Box<Type>),
+    /// A map type with keys of a type that can't be compared, like a function.
+    InvalidKey(Box<Type>),
+    /// An empty map was used somewhere that doesn't say what types it's keys and values should
+    /// be.
+    EmptyMap,
+    /// Something other than a map was inserted into.
+    NotAMap(Box<Type>),
+}
+
+impl fmt::Display for TypeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TypeError::Mismatch { expected, found } => {
+                write!(f, "expected {}, found {}", expected, found)
+            }
+            TypeError::Unbound(name) => write!(f, "{} isn't defined", name),
+            TypeError::NotAFunction(found) => write!(f, "{} isn't a function", found),
+            TypeError::ArgumentCount { expected, found } => {
+                write!(f, "expected {} arguments, found {}", expected, found)
+            }
+            TypeError::DuplicateField(field) => write!(f, "the field {} is given twice", field),
+            TypeError::EmptyGroup => {
+                write!(f, "can't tell what type an empty group's elements are")
+            }
+            TypeError::NoField { field, found } => {
+                write!(f, "{} doesn't have a field {}", found, field)
+            }
+            TypeError::NotIndexable(found) => write!(f, "{} can't be indexed", found),
+            TypeError::IndexOutOfBounds { index, length } => write!(
+                f,
+                "index {} is past the end of something with {} elements",
+                index, length
+            ),
+            TypeError::InvalidIndex(index) => write!(f, "{} isn't a whole number index", index),
+            TypeError::InvalidSlice { start, end } => {
+                write!(f, "the slice starts at {}, after it ends at {}", start, end)
+            }
+            TypeError::ReturnOutsideFunction => write!(f, "return outside of a function"),
+            TypeError::InvalidPattern(found) => {
+                write!(f, "the pattern can't match a value of type {}", found)
+            }
+            TypeError::DuplicateBinding(name) => {
+                write!(f, "{} is bound twice in the same pattern", name)
+            }
+            TypeError::RefutablePattern => {
+                write!(f, "the pattern doesn't match every value it could be given")
+            }
+            TypeError::NonExhaustive => write!(f, "the arms of the match don't cover every value"),
+            TypeError::UnreachableArm(arm) => {
+                write!(f, "arm {} of the match can't be reached", arm + 1)
+            }
+            TypeError::InStage { ptr, error } => {
+                write!(f, "in the pipeline stage at {}: {}", ptr, error)
+            }
+            TypeError::NotPrintable(found) => write!(f, "{} can't be turned into text", found),
+            TypeError::NotIterable(found) => write!(f, "{} can't be looped over", found),
+            TypeError::BreakOutsideLoop => write!(f, "break or continue outside of a loop"),
+            TypeError::NotExported { module, name } => {
+                write!(f, "{} doesn't export {}", module, name)
+            }
+            TypeError::UnknownType(name) => write!(f, "the type {} isn't declared", name),
+            TypeError::DuplicateType(name) => write!(f, "the type {} is declared twice", name),
+            TypeError::CyclicType(name) => write!(f, "the type {} only names itself", name),
+            TypeError::TypeArgumentCount {
+                name,
+                expected,
+                found,
+            } => write!(
+                f,
+                "{} takes {} type arguments, found {}",
+                name, expected, found
+            ),
+            TypeError::CannotInfer(name) => {
+                write!(f, "can't work out what {} is from the arguments", name)
+            }
+            TypeError::NoVariant { variant, found } => {
+                write!(f, "{} doesn't have a variant {}", found, variant)
+            }
+            TypeError::NotNarrowed { variant, found } => {
+                write!(f, "{} isn't known to be the variant {}", found, variant)
+            }
+            TypeError::NotComplex(found) => write!(f, "{} isn't a complex type", found),
+            TypeError::ConflictingField {
+                field,
+                first,
+                second,
+            } => write!(
+                f,
+                "the field {} is {} in one and {} in the other",
+                field, first, second
+            ),
+            TypeError::NotConvertible(found) => write!(f, "{} can't be converted", found),
+            TypeError::InvalidKey(found) => write!(f, "{} can't be a map key", found),
+            TypeError::EmptyMap => {
+                write!(
+                    f,
+                    "can't tell what types an empty map's keys and values are"
+                )
+            }
+            TypeError::NotAMap(found) => write!(f, "{} isn't a map", found),
+        }
+    }
+}
+
+/// Checks every statement in the program, returning the type of the last one.
+pub(crate) fn check_program(program: &Program) -> Result<Type, TypeError> {
+    check_module(program, &[]).map(|(found, _)| found)
+}
+
+/// The scope everything is checked in, along with the type of every expression that's been
+/// checked so far if they're being kept.
+///
+/// Every type is interned, and checking works on the ids, so comparing two types is comparing
+/// their ids unless one of them has to be unfolded or inferred.
+pub(crate) struct Context {
+    scope: Scope<TypeId>,
+    /// Expressions are kept by their spans rather than by what they are, since the same
+    /// expression can be written in more than one place and have a different type in each, but no
+    /// two expressions in a program have the same span.
+    ///
+    /// This is `None` unless the context was made with [`Context::recording`], since only
+    /// [`check_typed`](typed::check_typed) needs them.
+    types: Option<BTreeMap<Span, TypeId>>,
+    /// Every error found so far along with where the expression it's about is, if the types are
+    /// being kept, in which case checking carries on past them.
+    errors: Vec<(Span, TypeError)>,
+    /// Whether the error that's being returned has already been kept in `errors`.
+    located: bool,
+    /// Most expressions have one of only a few types, which are only stored once.
+    interner: TypeInterner,
+    /// What the types of the lambda parameters that aren't written out have been inferred as.
+    inference: Inference,
+    /// The type of each import along with the names it has but doesn't export, which are only
+    /// kept around to say why they can't be used.
+    imports: BTreeMap<Identifier, (TypeId, Complex)>,
+    /// The type parameters that have been used as map keys, see [`check_key`](map::check_key).
+    keys: BTreeSet<Identifier>,
+}
+
+impl Context {
+    pub(crate) fn new() -> Self {
+        Self {
+            scope: Scope::new(),
+            types: None,
+            errors: vec![],
+            located: false,
+            interner: TypeInterner::new(),
+            inference: Inference::default(),
+            imports: BTreeMap::new(),
+            keys: BTreeSet::new(),
+        }
+    }
+
+    /// A context that keeps the type of every expression it checks.
+    pub(crate) fn recording() -> Self {
+        Self {
+            types: Some(BTreeMap::new()),
+            ..Self::new()
+        }
+    }
+
+    pub(crate) fn intern(&mut self, found: &Type) -> TypeId {
+        self.interner.intern(found)
+    }
+
+    pub(crate) fn insert(&mut self, node: Node) -> TypeId {
+        self.interner.insert(node)
+    }
+
+    pub(crate) fn primitive(&mut self, primitive: PrimitiveType) -> TypeId {
+        self.interner.primitive(primitive)
+    }
+
+    pub(crate) fn node(&self, found: TypeId) -> &Node {
+        self.interner.node(found)
+    }
+
+    /// The whole type, for an error to say what it is.
+    pub(crate) fn boxed(&self, found: TypeId) -> Box<Type> {
+        Box::new(self.interner.get(found))
+    }
+
+    pub(crate) fn is_never(&self, found: TypeId) -> bool {
+        *self.node(found) == Node::Primitive(PrimitiveType::Never)
+    }
+
+    /// The two types didn't match, with what's been inferred about them so far filled in.
+    fn mismatch(&mut self, expected: TypeId, found: TypeId) -> TypeError {
+        let (expected, found) = (self.apply(expected), self.apply(found));
+        TypeError::Mismatch {
+            expected: self.boxed(expected),
+            found: self.boxed(found),
+        }
+    }
+
+    /// The alias of the import that `found` is the type of, if that module has `field` but
+    /// doesn't export it.
+    pub(crate) fn not_exported(&self, found: TypeId, field: &Identifier) -> Option<&Identifier> {
+        self.imports
+            .iter()
+            .find(|(_, (exports, private))| *exports == found && private.0.contains_key(field))
+            .map(|(alias, _)| alias)
+    }
+
+    /// Records the type of an expression, replacing whatever it was found to be before, if types
+    /// are being kept.
+    fn record(&mut self, expr: &Expression, found: TypeId) {
+        if let Some(types) = &mut self.types {
+            types.insert(expr.span, found);
+        }
+    }
+
+    /// Keeps the error with where `expr` is if checking it failed and types are being kept, unless
+    /// it's already been kept for an expression inside it, which is closer to where the problem is.
+    fn locate<T>(
+        &mut self,
+        expr: &Expression,
+        result: Result<T, TypeError>,
+    ) -> Result<T, TypeError> {
+        if let Err(error) = &result {
+            if self.types.is_some() && !self.located {
+                self.errors.push((expr.span, error.clone()));
+                self.located = true;
+            }
+        }
+        result
+    }
+
+    /// Carries on with `fallback` in place of whatever failed if the error has been kept, which it
+    /// only is if types are being kept.
+    fn recover<T>(&mut self, result: Result<T, TypeError>, fallback: T) -> Result<T, TypeError> {
+        match result {
+            Err(_) if self.located => {
+                self.located = false;
+                Ok(fallback)
+            }
+            result => result,
+        }
+    }
+}
+
+impl Deref for Context {
+    type Target = Scope<TypeId>;
+
+    fn deref(&self) -> &Scope<TypeId> {
+        &self.scope
+    }
+}
+
+impl DerefMut for Context {
+    fn deref_mut(&mut self) -> &mut Scope<TypeId> {
+        &mut self.scope
+    }
+}
+
+/// What other modules can see of a module when they import it.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct Interface {
+    /// The types of everything the module `export`s.
+    pub(crate) exports: Complex,
+    /// The types of everything else the module binds at the top level, which are only kept
+    /// around to say why they can't be used.
+    pub(crate) private: Complex,
+    /// Every type the module declares or imports, since the exported types and the types of the
+    /// exports can refer to any of them.
+    pub(crate) types: BTreeMap<Identifier, Type>,
+    pub(crate) exported_types: BTreeSet<Identifier>,
+}
+
+/// Checks a module with each of it's imports bound to the type of the module it imports,
+/// returning the type of the last statement along with the module's [`Interface`].
+///
+/// Any types the imported modules declare are available as `alias.Name`, if they're exported.
+pub(crate) fn check_module(
+    program: &Program,
+    imports: &[(Identifier, Interface)],
+) -> Result<(Type, Interface), TypeError> {
+    let mut scope = Context::new();
+    let mut types = import(imports, &mut scope);
+
+    let declarations = declarations(program);
+    declare(&declarations, &mut scope).map_err(|(_, error)| error)?;
+    for found in declarations {
+        types.insert(
+            found.name.clone(),
+            declaration(&found.parameters, &found.definition),
+        );
+    }
+
+    let mut found = scope.primitive(PrimitiveType::Emp);
+    for statement in &program.statements {
+        found = check_statement(statement, &mut scope)?;
+    }
+    let interface = Interface {
+        exports: bound_types(program.exports.iter(), &scope),
+        private: bound_types(program.private_names().into_iter(), &scope),
+        types,
+        exported_types: program.exported_types.clone(),
+    };
+    Ok((scope.interner.get(found), interface))
+}
+
+/// Binds each import to the type of the module it imports, returning every type the imported
+/// modules declare by it's qualified name.
+fn import(imports: &[(Identifier, Interface)], scope: &mut Context) -> BTreeMap<Identifier, Type> {
+    let mut types = BTreeMap::new();
+    for (alias, module) in imports {
+        let exports = qualify(&Type::Complex(module.exports.clone()), alias);
+        let exports = scope.intern(&exports);
+        scope.bind(alias.clone(), exports);
+        scope
+            .imports
+            .insert(alias.clone(), (exports, module.private.clone()));
+        for (name, definition) in &module.types {
+            let qualified = Identifier(format!("{}.{}", alias.0, name.0));
+            let definition = qualify(definition, alias);
+            let declared = scope.intern(&definition);
+            scope.bind(type_slot(&qualified), declared);
+            if !module.exported_types.contains(name) {
+                let emp = scope.primitive(PrimitiveType::Emp);
+                scope.bind(private_type_slot(&qualified), emp);
+            }
+            types.insert(qualified, definition);
+        }
+    }
+    types
+}
+
+/// The types a module declares or imports, bound like they are while it's being checked, so that
+/// the types in an error can be made sense of once checking has stopped.
+pub(crate) fn declared_types(program: &Program, imports: &[(Identifier, Interface)]) -> Context {
+    let mut scope = Context::new();
+    import(imports, &mut scope);
+    // anything wrong with the declarations has already been reported
+    let _ = declare(&declarations(program), &mut scope);
+    scope
+}
+
+fn declarations(program: &Program) -> Vec<&TypeDeclaration> {
+    program
+        .statements
+        .iter()
+        .filter_map(|statement| match statement {
+            Statement::TypeDeclaration(declaration) => Some(declaration),
+            _ => None,
+        })
+        .collect()
+}
+
+fn bound_types<'a>(names: impl Iterator<Item = &'a Identifier>, scope: &Context) -> Complex {
+    Complex(
+        names
+            .filter_map(|name| Some((name.clone(), scope.interner.get(*scope.get(name)?))))
+            .collect(),
+    )
+}
+
+/// Bindings have the type `___`, any names they bind are added to `scope`.
+pub(crate) fn check_statement(
+    statement: &Statement,
+    scope: &mut Context,
+) -> Result<TypeId, TypeError> {
+    match statement {
+        Statement::Binding(found) => {
+            binding(found, scope)?;
+            Ok(scope.primitive(PrimitiveType::Emp))
+        }
+        Statement::Expression(expr) => check(expr, scope),
+        // these are all declared before any of the statements are checked
+        Statement::TypeDeclaration(_) => Ok(scope.primitive(PrimitiveType::Emp)),
+    }
+}
+
+fn binding(binding: &Binding, scope: &mut Context) -> Result<(), TypeError> {
+    let found = match &binding.annotation {
+        Some(annotation) => {
+            let annotation = resolve(annotation, scope)?;
+            check_against(&binding.expr, annotation, scope)?;
+            annotation
+        }
+        None => match (&binding.pattern, check(&binding.expr, scope)?) {
+            // lambdas bound to a name are generic in whatever about them isn't inferred
+            (Pattern::Binding(..), found)
+                if matches!(binding.expr.kind, ExpressionKind::Lambda(_))
+                    && matches!(scope.node(found), Node::Function { .. }) =>
+            {
+                let environment = scope.environment();
+                scope.generalise(found, &environment)
+            }
+            (_, found) => found,
+        },
+    };
+    let found = scope.apply(found);
+    let environment = scope.environment();
+    if let Some(unknown) = fresh_variables(found, &scope.interner)
+        .iter()
+        .find(|name| !environment.contains(*name))
+    {
+        return Err(TypeError::CannotInfer(stands_for(unknown)));
+    }
+    bind_pattern(&binding.pattern, found, scope)?;
+    if is_irrefutable(&binding.pattern, found, scope) {
+        Ok(())
+    } else {
+        Err(TypeError::RefutablePattern)
+    }
+}
+
+/// Checks that `expr` has the type `expected`. Unlike [`check`], this can use `expected` to fill
+/// in types that can't be worked out from the expression on it's own, like the element type of
+/// `(| |)`.
+pub(crate) fn check_against(
+    expr: &Expression,
+    expected: TypeId,
+    scope: &mut Context,
+) -> Result<(), TypeError> {
+    let unfolded = unfold(expected, scope);
+    let found = match (&expr.kind, scope.node(unfolded)) {
+        (ExpressionKind::Group(group), Node::Group(element_type)) => {
+            let element_type = *element_type;
+            group
+                .0
+                .iter()
+                .try_for_each(|element| check_against(element, element_type, scope))
+        }
+        (ExpressionKind::Tuple(tuple), Node::Tuple(element_types))
+            if tuple.0.len() == element_types.len() =>
+        {
+            let element_types = element_types.clone();
+            tuple
+                .0
+                .iter()
+                .zip(element_types)
+                .try_for_each(|(element, element_type)| check_against(element, element_type, scope))
+        }
+        (ExpressionKind::Complex(complex), Node::Complex(field_types))
+            if complex.0.len() == field_types.len()
+                && complex
+                    .0
+                    .iter()
+                    .all(|(name, _)| field_types.contains_key(name)) =>
+        {
+            let field_types = field_types.clone();
+            complex
+                .0
+                .iter()
+                .try_for_each(|(name, value)| check_against(value, field_types[name], scope))
+        }
+        (ExpressionKind::Variant(variant), Node::Union(_)) => {
+            check_variant_against(variant, unfolded, scope)
+        }
+        (ExpressionKind::Map(literal), Node::Map(key_type, value_type)) => {
+            let (key_type, value_type) = (*key_type, *value_type);
+            check_map_against(literal, key_type, value_type, scope)
+        }
+        _ => {
+            let found = check(expr, scope).and_then(|found| expect(expected, found, scope));
+            let found = scope.locate(expr, found);
+            return scope.recover(found, ());
+        }
+    };
+    match scope.locate(expr, found) {
+        Ok(()) => scope.record(expr, unfolded),
+        failed => scope.recover(failed, ())?,
+    }
+    Ok(())
+}
+
+/// Finds the type of an expression, checking that all of it's operands have the right types.
+///
+/// If the type is a named type it's unfolded, so that whatever uses it can see what it is.
+pub(crate) fn check(expr: &Expression, scope: &mut Context) -> Result<TypeId, TypeError> {
+    let found = match &expr.kind {
+        ExpressionKind::Literal(literal) => Ok(scope.primitive(literal_type(literal))),
+        ExpressionKind::UnaryOperation(operation) => unary_operation(operation, scope),
+        ExpressionKind::BinaryOperation(operation) => binary_operation(operation, scope),
+        ExpressionKind::FunctionCall(call) => function_call(call, None, scope),
+        ExpressionKind::Lambda(found) => lambda(found, scope),
+        ExpressionKind::Tuple(tuple) => {
+            let element_types = tuple
+                .0
+                .iter()
+                .map(|element| check(element, scope))
+                .collect::<Result<_, _>>()?;
+            Ok(scope.insert(Node::Tuple(element_types)))
+        }
+        ExpressionKind::Complex(complex) => complex_literal(complex, scope),
+        ExpressionKind::Group(group) => group_literal(group, scope),
+        ExpressionKind::Map(literal) => map_literal(literal, scope),
+        ExpressionKind::Insert(found) => insert(found, scope),
+        ExpressionKind::Variant(variant) => variant_literal(variant, scope),
+        ExpressionKind::Is(is) => is_variant(is, scope),
+        ExpressionKind::Identifier(name) => lookup(name, scope),
+        ExpressionKind::Conditional(found) => conditional(found, scope),
+        ExpressionKind::Match(found) => matching(found, scope),
+        ExpressionKind::Pipeline(found) => pipeline(found, scope),
+        ExpressionKind::Block(found) => block(found, scope),
+        ExpressionKind::For(found) => for_loop(found, scope),
+        ExpressionKind::Break | ExpressionKind::Continue => match scope.get(&loop_slot()) {
+            Some(found) if *scope.node(*found) == Node::Primitive(PrimitiveType::Emp) => {
+                Ok(scope.primitive(PrimitiveType::Never))
+            }
+            _ => Err(TypeError::BreakOutsideLoop),
+        },
+        ExpressionKind::Return(found) => return_expression(found, scope),
+        ExpressionKind::FieldAccess(access) => field_access(access, scope),
+        ExpressionKind::Projection(found) => projection(found, scope),
+        ExpressionKind::Index(found) => index(found, scope),
+        ExpressionKind::Slice(found) => slice(found, scope),
+        ExpressionKind::Conversion(found) => conversion(found, scope),
+    };
+    let found = match scope.locate(expr, found) {
+        Ok(found) => found,
+        // what failed is left without a type, and whatever it's in carries on as if it were `nvr`
+        failed => {
+            let never = scope.primitive(PrimitiveType::Never);
+            return scope.recover(failed, never);
+        }
+    };
+    let found = scope.apply(found);
+    let found = unfold(found, scope);
+    scope.record(expr, found);
+    Ok(found)
+}
+
+/// The condition has to be a `bln`, and both branches have to have the same type, unless one of
+/// them never produces a value.
+///
+/// If the condition is `is[[name, variant]]`, then `name` is narrowed to that variant in the
+/// first branch, and to the rest of the variants in the second.
+fn conditional(conditional: &Conditional, scope: &mut Context) -> Result<TypeId, TypeError> {
+    let bln = scope.primitive(PrimitiveType::Bln);
+    check_against(&conditional.condition, bln, scope)?;
+    let (then_binding, otherwise_binding) = match narrowing(&conditional.condition, scope) {
+        Some((name, then, otherwise)) => (Some((name.clone(), then)), Some((name, otherwise))),
+        None => (None, None),
+    };
+    let then = narrowed(then_binding, scope, |scope| check(&conditional.then, scope))?;
+    narrowed(otherwise_binding, scope, |scope| {
+        if scope.is_never(then) {
+            check(&conditional.otherwise, scope)
+        } else {
+            check_branch(&conditional.otherwise, then, scope)
+        }
+    })
+}
+
+/// Checks something with `binding` bound over the top of whatever it was before.
+fn narrowed<T>(
+    binding: Option<(Identifier, TypeId)>,
+    scope: &mut Context,
+    f: impl FnOnce(&mut Context) -> Result<T, TypeError>,
+) -> Result<T, TypeError> {
+    scope.push();
+    if let Some((name, found)) = binding {
+        scope.bind(name, found);
+    }
+    let found = f(scope);
+    scope.pop();
+    found
+}
+
+/// Checks a branch once, returning the type of it and the branches before it together. That's the
+/// type of the branches before it, unless they're unions with different variants, in which case
+/// it's the union of all of their variants.
+///
+/// Branches after one that isn't a union are checked against it's type, so that something like
+/// an empty group can be a branch.
+fn check_branch(
+    branch: &Expression,
+    so_far: TypeId,
+    scope: &mut Context,
+) -> Result<TypeId, TypeError> {
+    let unfolded = unfold(so_far, scope);
+    if !matches!(scope.node(unfolded), Node::Union(_)) {
+        check_against(branch, so_far, scope)?;
+        return Ok(so_far);
+    }
+    let found = check(branch, scope)?;
+    match (expect(so_far, found, scope), merge(so_far, found, scope)) {
+        (Ok(()), _) => Ok(so_far),
+        (Err(_), Some(merged)) => Ok(merged),
+        (Err(error), None) => scope.locate(branch, Err(error)),
+    }
+}
+
+/// Every arm has to have the same type, unless it never produces a value. The names bound by an
+/// arm's pattern are only in scope in that arm.
+fn matching(matching: &Match, scope: &mut Context) -> Result<TypeId, TypeError> {
+    let found = check(&matching.expr, scope)?;
+    let never = scope.primitive(PrimitiveType::Never);
+    let mut arms_type = never;
+    for (pattern, body) in &matching.arms {
+        scope.push();
+        let arm_type = bind_pattern(pattern, found, scope).and_then(|_| {
+            if arms_type == never {
+                check(body, scope)
+            } else {
+                check_branch(body, arms_type, scope)
+            }
+        });
+        scope.pop();
+        let arm_type = arm_type?;
+        if arm_type != never {
+            arms_type = arm_type;
+        }
+    }
+    let patterns = matching
+        .arms
+        .iter()
+        .map(|(pattern, _)| pattern)
+        .collect::<Vec<_>>();
+    check_arms(&patterns, found, scope)?;
+    Ok(arms_type)
+}
+
+/// The type of the last statement, or `___` if the block is empty.
+fn block(block: &Block, scope: &mut Context) -> Result<TypeId, TypeError> {
+    let emp = scope.primitive(PrimitiveType::Emp);
+    scope.push();
+    let found = block
+        .statements
+        .iter()
+        .try_fold(emp, |_, statement| check_statement(statement, scope));
+    scope.pop();
+    found
+}
+
+/// The type that's returned from the enclosing function is kept in the scope under the name
+/// `return`, which can't clash with any real binding since it's a keyword.
+fn return_slot() -> Identifier {
+    Identifier("return".into())
+}
+
+/// `return` itself never produces a value, but what it returns has to have the same type as
+/// anything else the function returns.
+fn return_expression(found: &Return, scope: &mut Context) -> Result<TypeId, TypeError> {
+    let returned = match &found.expr {
+        Some(expr) => check(expr, scope)?,
+        None => scope.primitive(PrimitiveType::Emp),
+    };
+    let never = scope.primitive(PrimitiveType::Never);
+    let slot = scope
+        .get_mut(&return_slot())
+        .ok_or(TypeError::ReturnOutsideFunction)?;
+    if *slot == never {
+        *slot = returned;
+    } else {
+        let expected = *slot;
+        expect(expected, returned, scope)?;
+    }
+    Ok(never)
+}
+
+/// Whether `break` and `continue` can be used is kept in the scope under the name `for`. It's
+/// bound to `___` in the body of a loop, and to `nvr` in the body of a lambda, since they can't be
+/// used to leave a function.
+fn loop_slot() -> Identifier {
+    Identifier("for".into())
+}
+
+/// Looping over a `T@` with a body of type `U` makes a `U@`. The pattern has to match every `T`.
+fn for_loop(found: &For, scope: &mut Context) -> Result<TypeId, TypeError> {
+    let group = check(&found.group, scope)?;
+    let group = infer_shape(group, scope, some_group)?;
+    let element_type = match scope.node(group) {
+        Node::Group(element_type) => *element_type,
+        _ => return Err(TypeError::NotIterable(scope.boxed(group))),
+    };
+    let emp = scope.primitive(PrimitiveType::Emp);
+    scope.push();
+    scope.bind(loop_slot(), emp);
+    let body_type = bind_pattern(&found.pattern, element_type, scope).and_then(|_| {
+        if is_irrefutable(&found.pattern, element_type, scope) {
+            check(&found.body, scope)
+        } else {
+            Err(TypeError::RefutablePattern)
+        }
+    });
+    scope.pop();
+    let body_type = body_type?;
+    Ok(scope.insert(Node::Group(body_type)))
+}
+
+fn complex_literal(complex: &ComplexLiteral, scope: &mut Context) -> Result<TypeId, TypeError> {
+    let mut fields = BTreeMap::new();
+    for (name, value) in &complex.0 {
+        if fields.insert(name.clone(), check(value, scope)?).is_some() {
+            return Err(TypeError::DuplicateField(name.clone()));
+        }
+    }
+    Ok(scope.insert(Node::Complex(fields)))
+}
+
+/// Every element has to have the same type as the first one that produces a value.
+fn group_literal(group: &GroupLiteral, scope: &mut Context) -> Result<TypeId, TypeError> {
+    if group.0.is_empty() {
+        return Err(TypeError::EmptyGroup);
+    }
+    // like the arms of a match, elements that never produce a value don't decide the type
+    let never = scope.primitive(PrimitiveType::Never);
+    let mut element_type = never;
+    for element in &group.0 {
+        if element_type == never {
+            element_type = check(element, scope)?;
+        } else {
+            check_against(element, element_type, scope)?;
+        }
+    }
+    Ok(scope.insert(Node::Group(element_type)))
+}
+
+pub(crate) fn lookup(name: &Identifier, scope: &Context) -> Result<TypeId, TypeError> {
+    scope
+        .get(name)
+        .copied()
+        .ok_or_else(|| TypeError::Unbound(name.clone()))
+}
+
+/// `piped` is the type of a value passed in front of the written out arguments by a pipeline.
+fn function_call(
+    call: &FunctionCall,
+    piped: Option<TypeId>,
+    scope: &mut Context,
+) -> Result<TypeId, TypeError> {
+    let arg_count = call.args.len() + piped.is_some() as usize;
+    let callee = check(&call.callee, scope)?;
+    // a parameter that's called has to be a function, of something
+    let callee = infer_shape(callee, scope, |scope| {
+        let parameters = (0..arg_count)
+            .map(|_| scope.fresh(&Identifier("argument".into())))
+            .collect();
+        let return_type = scope.fresh(&Identifier("returned".into()));
+        scope.insert(Node::Function {
+            type_parameters: vec![],
+            parameters,
+            return_type,
+        })
+    })?;
+    let (generic, parameters, return_type) = match scope.node(callee) {
+        Node::Function {
+            type_parameters,
+            parameters,
+            return_type,
+        } => (
+            !type_parameters.is_empty(),
+            parameters.clone(),
+            *return_type,
+        ),
+        _ => return Err(TypeError::NotAFunction(scope.boxed(callee))),
+    };
+    if parameters.len() != arg_count {
+        return Err(TypeError::ArgumentCount {
+            expected: parameters.len(),
+            found: arg_count,
+        });
+    }
+    if generic {
+        return generic_call(call, callee, piped, scope);
+    }
+    let mut parameters = parameters.into_iter();
+    if let Some(piped) = piped {
+        // the number of arguments has already been checked, so there's at least one parameter
+        expect(parameters.next().unwrap(), piped, scope)?;
+    }
+    for (parameter, arg) in parameters.zip(&call.args) {
+        check_against(arg, parameter, scope)?;
+    }
+    Ok(return_type)
+}
+
+/// A parameter without a type has to be whatever it's used as, so if `found` is the fresh variable
+/// for one it's solved as `shape`, which has fresh variables for anything the use doesn't say.
+/// Anything else is left as it is.
+pub(crate) fn infer_shape(
+    found: TypeId,
+    scope: &mut Context,
+    shape: impl FnOnce(&mut Context) -> TypeId,
+) -> Result<TypeId, TypeError> {
+    match scope.node(found) {
+        Node::Variable(name) if is_fresh(name) => {
+            let shape = shape(scope);
+            expect(shape, found, scope)?;
+            Ok(shape)
+        }
+        _ => Ok(found),
+    }
+}
+
+/// A group of whatever a fresh variable is solved as, for a parameter without a type that's
+/// indexed or looped over.
+pub(crate) fn some_group(scope: &mut Context) -> TypeId {
+    let element_type = scope.fresh(&Identifier("element".into()));
+    scope.insert(Node::Group(element_type))
+}
+
+/// The type arguments of a generic function are worked out from the arguments, one at a time. An
+/// argument is checked against it's parameter if the type arguments it uses are already known, and
+/// an empty group is a group of something that's worked out later.
+fn generic_call(
+    call: &FunctionCall,
+    function: TypeId,
+    piped: Option<TypeId>,
+    scope: &mut Context,
+) -> Result<TypeId, TypeError> {
+    let mark = scope.mark();
+    let (instance, variables) = scope.instantiate(function);
+    let (parameters, return_type) = match scope.node(instance) {
+        Node::Function {
+            parameters,
+            return_type,
+            ..
+        } => (parameters.clone(), *return_type),
+        _ => unreachable!("instantiating a function doesn't change what kind of type it is"),
+    };
+    let mut parameters = parameters.into_iter();
+    let unify = |parameter: TypeId, found: TypeId, scope: &mut Context| {
+        if scope.unify(parameter, found) {
+            Ok(())
+        } else {
+            Err(scope.mismatch(parameter, found))
+        }
+    };
+    if let Some(piped) = piped {
+        unify(parameters.next().unwrap(), piped, scope)?;
+    }
+    let mut empty_groups = vec![];
+    for (parameter, arg) in parameters.zip(&call.args) {
+        if scope.is_unknown(parameter) {
+            let found = match &arg.kind {
+                ExpressionKind::Group(group) if group.0.is_empty() => {
+                    let found = some_group(scope);
+                    empty_groups.push((arg, found));
+                    found
+                }
+                _ => check(arg, scope)?,
+            };
+            let unified = unify(parameter, found, scope);
+            scope.locate(arg, unified)?;
+        } else {
+            let parameter = scope.apply(parameter);
+            check_against(arg, parameter, scope)?;
+        }
+    }
+    // the type arguments can still be the types of parameters of the lambdas around the call,
+    // which are worked out later
+    for (parameter, variable) in variables {
+        if scope.made_since(variable, mark) {
+            return Err(TypeError::CannotInfer(parameter));
+        }
+    }
+    for (arg, found) in empty_groups {
+        let found = scope.apply(found);
+        scope.record(arg, found);
+    }
+    // the type arguments have to be able to be keys wherever the function uses them as keys
+    let instance = scope.apply(instance);
+    check_keys(instance, scope)?;
+    Ok(scope.apply(return_type))
+}
+
+/// Each stage is checked as a call with the type of the stage before it as it's first argument.
+/// Any errors in a stage are wrapped with where the stage is.
+fn pipeline(pipeline: &Pipeline, scope: &mut Context) -> Result<TypeId, TypeError> {
+    let mut found = check(&pipeline.expr, scope)?;
+    for stage in &pipeline.stages {
+        found =
+            function_call(&stage.call, Some(found), scope).map_err(|error| TypeError::InStage {
+                ptr: stage.ptr,
+                error: Box::new(error),
+            })?;
+    }
+    Ok(found)
+}
+
+/// The parameters are only in scope in the body of the lambda, where they shadow any outer
+/// bindings with the same names.
+///
+/// The return type is the type of the body, which has to be the same as the type of anything
+/// returned early with `return`. Parameters without a type start out as a fresh type variable,
+/// which is solved as whatever the body needs it to be.
+fn lambda(lambda: &Lambda, scope: &mut Context) -> Result<TypeId, TypeError> {
+    let outer_keys = std::mem::take(&mut scope.keys);
+    scope.push();
+    // inside a generic lambda it's type parameters can be written out as types that are only the
+    // same as themselves
+    for parameter in &lambda.type_parameters {
+        let declared = scope.intern(&declaration(&[], &Type::Variable(parameter.clone())));
+        scope.bind(type_slot(parameter), declared);
+    }
+    let parameters = match lambda
+        .parameters
+        .iter()
+        .map(|(name, parameter, _)| match parameter {
+            Some(parameter) => resolve(parameter, scope),
+            None => Ok(scope.fresh(name)),
+        })
+        .collect::<Result<Vec<_>, _>>()
+    {
+        Ok(parameters) => parameters,
+        Err(error) => {
+            scope.pop();
+            return Err(error);
+        }
+    };
+    let never = scope.primitive(PrimitiveType::Never);
+    scope.bind(return_slot(), never);
+    scope.bind(loop_slot(), never);
+    for ((name, ..), parameter) in lambda.parameters.iter().zip(&parameters) {
+        scope.bind(name.clone(), *parameter);
+    }
+    let return_type = check(&lambda.body, scope).and_then(|body| {
+        let returned = lookup(&return_slot(), scope)?;
+        if body == never {
+            Ok(returned)
+        } else {
+            expect(body, returned, scope)?;
+            Ok(body)
+        }
+    });
+    scope.pop();
+    let mut keys = std::mem::replace(&mut scope.keys, outer_keys);
+    let return_type = with_variables(return_type?, &lambda.type_parameters, &mut scope.interner);
+    let function = scope.insert(Node::Function {
+        type_parameters: vec![],
+        parameters,
+        return_type,
+    });
+    let function = scope.apply(function);
+    // a type parameter that's used as a key has to be one in the function's type too, so that
+    // it's only ever called with types that can be keys
+    let shown = key_variables(function, scope);
+    for parameter in &lambda.type_parameters {
+        if keys.remove(parameter) && !shown.contains(parameter) {
+            return Err(TypeError::InvalidKey(Box::new(Type::Variable(
+                parameter.clone(),
+            ))));
+        }
+    }
+    scope.keys.extend(keys);
+    match scope.node(function).clone() {
+        Node::Function {
+            parameters,
+            return_type,
+            ..
+        } => Ok(scope.insert(Node::Function {
+            type_parameters: lambda.type_parameters.clone(),
+            parameters,
+            return_type,
+        })),
+        _ => unreachable!("applying what's been inferred doesn't change what kind of type it is"),
+    }
+}
+
+pub(crate) fn literal_type(literal: &Literal) -> PrimitiveType {
+    match literal {
+        Literal::Bln(_) => PrimitiveType::Bln,
+        Literal::Str(_) => PrimitiveType::Str,
+        Literal::Num(num) => num.suffix.clone().unwrap_or(PrimitiveType::Num),
+        Literal::Emp(_) => PrimitiveType::Emp,
+    }
+}
+
+/// Only the primitive types that have values can be turned into text, by `++` and string
+/// interpolation.
+fn printable(found: TypeId, scope: &Context) -> Result<(), TypeError> {
+    match scope.node(found) {
+        // there's more than one type it could be
+        Node::Variable(name) if is_fresh(name) => Err(TypeError::CannotInfer(stands_for(name))),
+        Node::Primitive(
+            PrimitiveType::Str
+            | PrimitiveType::Bln
+            | PrimitiveType::Num
+            | PrimitiveType::Int(_)
+            | PrimitiveType::Byte,
+        ) => Ok(()),
+        _ => Err(TypeError::NotPrintable(scope.boxed(found))),
+    }
+}
+
+/// Anything that never produces a value can be used where any type is expected, and a union can
+/// be used where a union with more variants is expected.
+///
+/// If either type has the type of a parameter that's still being inferred in it, they're unified
+/// instead, which works out what the parameter's type has to be.
+pub(crate) fn expect(
+    expected: TypeId,
+    found: TypeId,
+    scope: &mut Context,
+) -> Result<(), TypeError> {
+    if scope.is_never(found) || same(expected, found, scope) || is_narrower(expected, found, scope)
+    {
+        return Ok(());
+    }
+    if (scope.is_unknown(expected) || scope.is_unknown(found)) && scope.unify(expected, found) {
+        Ok(())
+    } else {
+        Err(scope.mismatch(expected, found))
+    }
+}
+
+/// The number type that an operator works on, which is whichever one its first operand is. The
+/// integer types can be used wherever a `num` can, and `byte`s can only be compared.
+fn number_type(found: &Node, signed: bool, byte: bool) -> PrimitiveType {
+    match found {
+        Node::Primitive(PrimitiveType::Int(int)) if int.is_signed() || !signed => {
+            PrimitiveType::Int(*int)
+        }
+        Node::Primitive(PrimitiveType::Byte) if byte => PrimitiveType::Byte,
+        _ => PrimitiveType::Num,
+    }
+}
+
+/// Whether values of the type can be converted to and from the other number types.
+fn is_number(found: &Node) -> bool {
+    matches!(
+        found,
+        Node::Primitive(PrimitiveType::Num | PrimitiveType::Int(_) | PrimitiveType::Byte)
+    )
+}
+
+/// Positions in a group can be given as a `num` or as any of the integer types.
+pub(crate) fn expect_position(found: TypeId, scope: &mut Context) -> Result<(), TypeError> {
+    let expected = scope.primitive(number_type(scope.node(found), false, false));
+    expect(expected, found, scope)
+}
+
+/// `-` takes and returns a `num` or a signed integer, `!` takes and returns a `bln`.
+fn unary_operation(operation: &UnaryOperation, scope: &mut Context) -> Result<TypeId, TypeError> {
+    let found = check(&operation.expr, scope)?;
+    let operand_type = match operation.operand {
+        UnaryOperator::Neg(_) => number_type(scope.node(found), true, false),
+        UnaryOperator::Not(_) => PrimitiveType::Bln,
+    };
+    let operand_type = scope.primitive(operand_type);
+    let expected = expect(operand_type, found, scope);
+    scope.locate(&operation.expr, expected)?;
+    Ok(operand_type)
+}
+
+/// The arithmetic operators take two `num`s and return a `num`, the comparisons take two `num`s
+/// and return a `bln`, and the logical operators take two `bln`s and return a `bln`. Both sides
+/// of the arithmetic operators can also be the same integer type, and of the comparisons the
+/// same integer type or `byte`s.
+///
+/// `==` and `!=` work on any type, as long as both sides have the same type, and `++` works on any
+/// two printable types. `..` takes two `num`s and returns a `num@`.
+fn binary_operation(operation: &BinaryOperation, scope: &mut Context) -> Result<TypeId, TypeError> {
+    let first = check(&operation.first, scope)?;
+    let second = check(&operation.second, scope)?;
+    let (operand_type, return_type) = match operation.operand {
+        BinaryOperator::Add(_)
+        | BinaryOperator::Sub(_)
+        | BinaryOperator::Mul(_)
+        | BinaryOperator::Div(_)
+        | BinaryOperator::Rem(_) => {
+            let number = number_type(scope.node(first), false, false);
+            (number.clone(), number)
+        }
+        BinaryOperator::Gt(_)
+        | BinaryOperator::Lt(_)
+        | BinaryOperator::Gte(_)
+        | BinaryOperator::Lte(_) => (
+            number_type(scope.node(first), false, true),
+            PrimitiveType::Bln,
+        ),
+        BinaryOperator::And(_) | BinaryOperator::Or(_) => (PrimitiveType::Bln, PrimitiveType::Bln),
+        BinaryOperator::Eq(_) | BinaryOperator::Neq(_) => {
+            let expected = expect(first, second, scope);
+            scope.locate(&operation.second, expected)?;
+            return Ok(scope.primitive(PrimitiveType::Bln));
+        }
+        BinaryOperator::Range(_) => {
+            let num = scope.primitive(PrimitiveType::Num);
+            let expected = expect(num, first, scope);
+            scope.locate(&operation.first, expected)?;
+            let expected = expect(num, second, scope);
+            scope.locate(&operation.second, expected)?;
+            return Ok(scope.insert(Node::Group(num)));
+        }
+        BinaryOperator::Concat(_) => {
+            let printed = printable(first, scope);
+            scope.locate(&operation.first, printed)?;
+            let printed = printable(second, scope);
+            scope.locate(&operation.second, printed)?;
+            return Ok(scope.primitive(PrimitiveType::Str));
+        }
+    };
+    // each operand is where the error is if it doesn't have the right type
+    let operand_type = scope.primitive(operand_type);
+    let expected = expect(operand_type, first, scope);
+    scope.locate(&operation.first, expected)?;
+    let expected = expect(operand_type, second, scope);
+    scope.locate(&operation.second, expected)?;
+    Ok(scope.primitive(return_type))
+}
+
+/// `u8[[x]]` has the type it converts to, and `x` can be any of the number types.
+fn conversion(conversion: &Conversion, scope: &mut Context) -> Result<TypeId, TypeError> {
+    let found = check(&conversion.expr, scope)?;
+    if let Node::Variable(name) = scope.node(found) {
+        if is_fresh(name) {
+            // it could be any of the number types
+            return Err(TypeError::CannotInfer(stands_for(name)));
+        }
+    }
+    if !scope.is_never(found) && !is_number(scope.node(found)) {
+        return Err(TypeError::NotConvertible(scope.boxed(found)));
+    }
+    Ok(scope.primitive(conversion.to.clone()))
+}
+
+#[cfg(test)]
+mod test_check {
+    use crate::{
+        parse_program,
+        pointer::{Pointer, Span},
+        types::{fixtures::yaupl_type_str, function::Function, primitive::IntType, Group, Named},
+    };
+
+    use super::*;
+
+    fn check_str(i: &str) -> Result<Type, TypeError> {
+        check_program(&parse_program(i).unwrap())
+    }
+
+    #[test]
+    fn test_operators() {
+        assert_eq!(
+            check_str("% - 7 2 3"),
+            Ok(Type::Primitive(PrimitiveType::Num))
+        );
+        assert_eq!(
+            check_str("|| ! false && true < 1 2"),
+            Ok(Type::Primitive(PrimitiveType::Bln))
+        );
+        assert_eq!(
+            check_str(r#"== "a" "b""#),
+            Ok(Type::Primitive(PrimitiveType::Bln))
+        );
+    }
+
+    #[test]
+    fn test_integers() {
+        assert_eq!(
+            check_str("% * 7i32 2i32 3i32"),
+            Ok(Type::Primitive(PrimitiveType::Int(IntType::I32)))
+        );
+        assert_eq!(
+            check_str("< 1u8 2u8"),
+            Ok(Type::Primitive(PrimitiveType::Bln))
+        );
+        assert_eq!(
+            check_str("(| 1, 2, 3 |)[1u64]"),
+            Ok(Type::Primitive(PrimitiveType::Num))
+        );
+        assert_eq!(
+            check_str("+ 1i32 1"),
+            Err(TypeError::Mismatch {
+                expected: Box::new(Type::Primitive(PrimitiveType::Int(IntType::I32))),
+                found: Box::new(Type::Primitive(PrimitiveType::Num)),
+            })
+        );
+        assert_eq!(
+            check_str("- 1u8"),
+            Err(TypeError::Mismatch {
+                expected: Box::new(Type::Primitive(PrimitiveType::Num)),
+                found: Box::new(Type::Primitive(PrimitiveType::Int(IntType::U8))),
+            })
+        );
+        // bytes can be compared but not added
+        assert_eq!(
+            check_str("== >= 1byte 2byte false"),
+            Ok(Type::Primitive(PrimitiveType::Bln))
+        );
+        assert_eq!(
+            check_str("+ 1byte 2byte"),
+            Err(TypeError::Mismatch {
+                expected: Box::new(Type::Primitive(PrimitiveType::Num)),
+                found: Box::new(Type::Primitive(PrimitiveType::Byte)),
+            })
+        );
+    }
+
+    #[test]
+    fn test_conversion() {
+        assert_eq!(
+            check_str("x <- 300 + u16[[x]] byte[[7]]"),
+            Err(TypeError::Mismatch {
+                expected: Box::new(Type::Primitive(PrimitiveType::Int(IntType::U16))),
+                found: Box::new(Type::Primitive(PrimitiveType::Byte)),
+            })
+        );
+        assert_eq!(
+            check_str("+ num[[5i64]] 1"),
+            Ok(Type::Primitive(PrimitiveType::Num))
+        );
+        assert_eq!(
+            check_str(r#"i8[["1"]]"#),
+            Err(TypeError::NotConvertible(Box::new(Type::Primitive(
+                PrimitiveType::Str
+            ))))
+        );
+    }
+
+    #[test]
+    fn test_mismatch() {
+        assert_eq!(
+            check_str("! 1"),
+            Err(TypeError::Mismatch {
+                expected: Box::new(Type::Primitive(PrimitiveType::Bln)),
+                found: Box::new(Type::Primitive(PrimitiveType::Num)),
+            })
+        );
+        assert_eq!(
+            check_str("&& true ___"),
+            Err(TypeError::Mismatch {
+                expected: Box::new(Type::Primitive(PrimitiveType::Bln)),
+                found: Box::new(Type::Primitive(PrimitiveType::Emp)),
+            })
+        );
+    }
+
+    #[test]
+    fn test_bindings() {
+        assert_eq!(
+            check_str("a <- 1 b: bln <- > a 2; b"),
+            Ok(yaupl_type_str("bln"))
+        );
+        assert_eq!(
+            check_str("a: str <- 1"),
+            Err(TypeError::Mismatch {
+                expected: Box::new(yaupl_type_str("str")),
+                found: Box::new(yaupl_type_str("num")),
+            })
+        );
+        // bindings are only visible after they're made
+        assert_eq!(
+            check_str("a <- b b <- 1"),
+            Err(TypeError::Unbound(Identifier("b".into())))
+        );
+    }
+
+    #[test]
+    fn test_shadowing() {
+        assert_eq!(check_str("a <- 1 a <- == a 1 a"), Ok(yaupl_type_str("bln")));
+    }
+
+    #[test]
+    fn test_lambdas() {
+        assert_eq!(
+            check_str("[a: num, b: bln] -> && b > a 1"),
+            Ok(yaupl_type_str("[num, bln]=>bln"))
+        );
+        assert_eq!(
+            check_str(
+                "offset <- 10
+                 add <- [a: num] -> [b: num] -> + + a b offset
+                 add_one <- add[[1]]
+                 add_one[[2]]"
+            ),
+            Ok(yaupl_type_str("num"))
+        );
+    }
+
+    #[test]
+    fn test_calls() {
+        assert_eq!(
+            check_str("f <- [a: num] -> a f[[true]]"),
+            Err(TypeError::Mismatch {
+                expected: Box::new(yaupl_type_str("num")),
+                found: Box::new(yaupl_type_str("bln")),
+            })
+        );
+        assert_eq!(
+            check_str("f <- [a: num] -> a f[[1, 2]]"),
+            Err(TypeError::ArgumentCount {
+                expected: 1,
+                found: 2
+            })
+        );
+        assert_eq!(
+            check_str("f <- 1 f[[]]"),
+            Err(TypeError::NotAFunction(Box::new(yaupl_type_str("num"))))
+        );
+        // parameters aren't visible outside of the lambda
+        assert_eq!(
+            check_str("f <- [a: num] -> a a"),
+            Err(TypeError::Unbound(Identifier("a".into())))
+        );
+    }
+
+    #[test]
+    fn test_compound_literals() {
+        assert_eq!(
+            check_str(r#"[| 1, "one", |- a: (| true, false |), b: [| ___ |] -| |]"#),
+            Ok(yaupl_type_str("[|num, str, |-b: [|___|], a: bln@-|,|]"))
+        );
+        assert_eq!(
+            check_str("(| 1, true |)"),
+            Err(TypeError::Mismatch {
+                expected: Box::new(yaupl_type_str("num")),
+                found: Box::new(yaupl_type_str("bln")),
+            })
+        );
+        assert_eq!(
+            check_str("|- a: 1, a: 2 -|"),
+            Err(TypeError::DuplicateField(Identifier("a".into())))
+        );
+    }
+
+    #[test]
+    fn test_empty_group() {
+        assert_eq!(check_str("(| |)"), Err(TypeError::EmptyGroup));
+        assert_eq!(
+            check_str("(| (| |), (| 1 |) |)"),
+            Err(TypeError::EmptyGroup)
+        );
+        assert_eq!(
+            check_str("a: [|num@@, bln|] <- [| (| (| |), (| 1 |) |), true |] a"),
+            Ok(yaupl_type_str("[|num@@, bln|]"))
+        );
+        assert_eq!(
+            check_str("f <- [a: str@] -> a f[[(| |)]]"),
+            Ok(yaupl_type_str("str@"))
+        );
+        assert_eq!(
+            check_str("a: |- xs: num@, n: num -| <- |- xs: (| |), n: 1 -| a"),
+            Ok(yaupl_type_str("|- xs: num@, n: num -|"))
+        );
+    }
+
+    #[test]
+    fn test_access() {
+        assert_eq!(
+            check_str("a <- |- b: [| 1, (| true |) |] -| a.b.1[0]"),
+            Ok(yaupl_type_str("bln"))
+        );
+        assert_eq!(
+            check_str("a <- (| 1, 2 |) a[1:]"),
+            Ok(yaupl_type_str("num@"))
+        );
+        assert_eq!(
+            check_str("a <- |- b: 1 -| a.c"),
+            Err(TypeError::NoField {
+                field: Identifier("c".into()),
+                found: Box::new(yaupl_type_str("|-b: num-|")),
+            })
+        );
+        assert_eq!(
+            check_str("a <- (| 1 |) a.0"),
+            Err(TypeError::NotIndexable(Box::new(yaupl_type_str("num@"))))
+        );
+        assert_eq!(
+            check_str(r#"(| 1 |)["0"]"#),
+            Err(TypeError::Mismatch {
+                expected: Box::new(yaupl_type_str("num")),
+                found: Box::new(yaupl_type_str("str")),
+            })
+        );
+    }
+
+    #[test]
+    fn test_static_bounds() {
+        assert_eq!(
+            check_str("[| 1, 2 |].2"),
+            Err(TypeError::IndexOutOfBounds {
+                index: 2,
+                length: 2
+            })
+        );
+        assert_eq!(
+            check_str("(| 1, 2 |)[2]"),
+            Err(TypeError::IndexOutOfBounds {
+                index: 2,
+                length: 2
+            })
+        );
+        assert_eq!(check_str("(| 1, 2 |)[2:]"), Ok(yaupl_type_str("num@")));
+        assert_eq!(
+            check_str("(| 1, 2 |)[-1]"),
+            Err(TypeError::InvalidIndex(Number::from_parts(-1, 0)))
+        );
+        assert_eq!(
+            check_str("(| 1, 2 |)[2:1]"),
+            Err(TypeError::InvalidSlice { start: 2, end: 1 })
+        );
+    }
+
+    #[test]
+    fn test_conditional() {
+        assert_eq!(
+            check_str(r#"if[[> 1 2, "a", "b"]]"#),
+            Ok(yaupl_type_str("str"))
+        );
+        assert_eq!(
+            check_str("if[[1, 2, 3]]"),
+            Err(TypeError::Mismatch {
+                expected: Box::new(yaupl_type_str("bln")),
+                found: Box::new(yaupl_type_str("num")),
+            })
+        );
+        assert_eq!(
+            check_str(r#"if[[true, 1, "1"]]"#),
+            Err(TypeError::Mismatch {
+                expected: Box::new(yaupl_type_str("num")),
+                found: Box::new(yaupl_type_str("str")),
+            })
+        );
+        // each branch is only checked once, however deeply they're nested
+        let variant = |ind| (b'a' + ind) as char;
+        let nested = (0..25).rev().fold("<| z |>".to_string(), |otherwise, ind| {
+            format!("if[[true, <| {} |>, {}]]", variant(ind), otherwise)
+        });
+        let variants = (0..26)
+            .map(|ind| variant(ind).to_string())
+            .collect::<Vec<_>>()
+            .join(", ");
+        assert_eq!(
+            check_str(&nested),
+            Ok(yaupl_type_str(&format!("<| {} |>", variants)))
+        );
+    }
+
+    #[test]
+    fn test_blocks() {
+        assert_eq!(
+            check_str("a <- 1 { a <- true; b <- 2; && a > b 1 }"),
+            Ok(yaupl_type_str("bln"))
+        );
+        assert_eq!(check_str("{ }"), Ok(yaupl_type_str("___")));
+        // bindings made in a block aren't visible after it
+        assert_eq!(
+            check_str("{ b <- 2 } b"),
+            Err(TypeError::Unbound(Identifier("b".into())))
+        );
+    }
+
+    #[test]
+    fn test_return() {
+        assert_eq!(
+            check_str(
+                r#"[a: num] -> {
+                    if[[> a 1, return "big", ___]];
+                    "small"
+                }"#
+            ),
+            Ok(yaupl_type_str("[num]=>str"))
+        );
+        assert_eq!(
+            check_str("[a: num] -> if[[> a 1, return 1, 2]]"),
+            Ok(yaupl_type_str("[num]=>num"))
+        );
+        assert_eq!(
+            check_str("[a: num] -> return a"),
+            Ok(yaupl_type_str("[num]=>num"))
+        );
+        assert_eq!(
+            check_str("[a: num] -> { if[[> a 1, return true, ___]]; 1 }"),
+            Err(TypeError::Mismatch {
+                expected: Box::new(yaupl_type_str("num")),
+                found: Box::new(yaupl_type_str("bln")),
+            })
+        );
+        assert_eq!(check_str("return 1"), Err(TypeError::ReturnOutsideFunction));
+    }
+
+    #[test]
+    fn test_destructuring() {
+        assert_eq!(
+            check_str(
+                r#"[| a, |- b: b, c: (| c, ..rest |) -| |] <- [| 1, |- b: "b", c: (| true |) -| |]
+                   [| a, b, c, rest |]"#
+            ),
+            Err(TypeError::RefutablePattern)
+        );
+        assert_eq!(
+            check_str(r#"[| a, |- b: b -| |] <- [| 1, |- b: "b", c: true -| |] [| a, b |]"#),
+            Ok(yaupl_type_str("[|num, str|]"))
+        );
+        assert_eq!(
+            check_str("[| a, a |] <- [| 1, 2 |]"),
+            Err(TypeError::DuplicateBinding(Identifier("a".into())))
+        );
+        assert_eq!(
+            check_str("[| a, b |] <- [| 1, 2, 3 |]"),
+            Err(TypeError::InvalidPattern(Box::new(yaupl_type_str(
+                "[|num, num, num|]"
+            ))))
+        );
+    }
+
+    #[test]
+    fn test_match() {
+        assert_eq!(
+            check_str(
+                r#"match[[(| 1, 2 |),
+                    (| |) -> "empty",
+                    (| 1, ..rest |) -> "starts with one",
+                    (| _, ..rest |) -> "something else"
+                ]]"#
+            ),
+            Ok(yaupl_type_str("str"))
+        );
+        assert_eq!(
+            check_str("match[[[| true, 1 |], [| true, n |] -> n, [| false, 1 |] -> 2]]"),
+            Err(TypeError::NonExhaustive)
+        );
+        assert_eq!(
+            check_str("match[[[| true, 1 |], [| _, n |] -> n, [| false, 1 |] -> 2]]"),
+            Err(TypeError::UnreachableArm(1))
+        );
+        assert_eq!(
+            check_str("match[[(| 1 |), (| |) -> 0, (| a |) -> a, (| a, b, ..rest |) -> + a b]]"),
+            Ok(yaupl_type_str("num"))
+        );
+        assert_eq!(
+            check_str("match[[(| 1 |), (| |) -> 0, (| a, b, ..rest |) -> + a b]]"),
+            Err(TypeError::NonExhaustive)
+        );
+        assert_eq!(
+            check_str(r#"match[[1, 1 -> "one", _ -> true]]"#),
+            Err(TypeError::Mismatch {
+                expected: Box::new(yaupl_type_str("str")),
+                found: Box::new(yaupl_type_str("bln")),
+            })
+        );
+        assert_eq!(
+            check_str(r#"match[[|- a: 1, b: ___ -|, |- b: ___ -| -> 1, |- a: 2 -| -> 2]]"#),
+            Err(TypeError::UnreachableArm(1))
+        );
+    }
+
+    #[test]
+    fn test_pipeline() {
+        assert_eq!(
+            check_str(
+                "double <- [a: num] -> * a 2
+                 add <- [a: num, b: num] -> + a b
+                 is_big <- [a: num] -> > a 100
+                 1 ~> double ~> add[[3]] ~> is_big"
+            ),
+            Ok(yaupl_type_str("bln"))
+        );
+        assert_eq!(
+            check_str(
+                "double <- [a: num] -> * a 2
+                 is_big <- [a: num] -> > a 100
+                 1 ~> is_big
+                   ~> double"
+            ),
+            Err(TypeError::InStage {
+                ptr: Pointer::new(3, 22),
+                error: Box::new(TypeError::Mismatch {
+                    expected: Box::new(yaupl_type_str("num")),
+                    found: Box::new(yaupl_type_str("bln")),
+                }),
+            })
+        );
+        assert_eq!(
+            check_str("add <- [a: num, b: num] -> + a b 1 ~> add"),
+            Err(TypeError::InStage {
+                ptr: Pointer::new(0, 38),
+                error: Box::new(TypeError::ArgumentCount {
+                    expected: 2,
+                    found: 1
+                }),
+            })
+        );
+    }
+
+    #[test]
+    fn test_interpolation() {
+        assert_eq!(
+            check_str(r#"name <- "yaupl" "{name} {+ 1 2} {true} {2u8}""#),
+            Ok(yaupl_type_str("str"))
+        );
+        assert_eq!(
+            check_str(r#""{___}""#),
+            Err(TypeError::NotPrintable(Box::new(yaupl_type_str("___"))))
+        );
+        assert_eq!(
+            check_str(r#"f <- [] -> ++ "a" return "b" f[[]]"#),
+            Err(TypeError::NotPrintable(Box::new(yaupl_type_str("nvr"))))
+        );
+        assert_eq!(
+            check_str(r#"group <- (| 1 |) "{group}""#),
+            Err(TypeError::NotPrintable(Box::new(yaupl_type_str("num@"))))
+        );
+    }
+
+    #[test]
+    fn test_loops() {
+        assert_eq!(
+            check_str(r#"for[[n <- .. 0 10, if[[> n 5, break, "{n}"]]]]"#),
+            Ok(yaupl_type_str("str@"))
+        );
+        assert_eq!(
+            check_str("for[[[| a, b |] <- (| [| 1, true |] |), && b > a 1]]"),
+            Ok(yaupl_type_str("bln@"))
+        );
+        assert_eq!(
+            check_str("for[[n <- 1, n]]"),
+            Err(TypeError::NotIterable(Box::new(yaupl_type_str("num"))))
+        );
+        assert_eq!(
+            check_str("for[[(| a |) <- (| (| 1 |) |), a]]"),
+            Err(TypeError::RefutablePattern)
+        );
+        assert_eq!(check_str("continue"), Err(TypeError::BreakOutsideLoop));
+        // a loop's `break` can't be used from a lambda inside of it
+        assert_eq!(
+            check_str("for[[n <- .. 0 10, [] -> break]]"),
+            Err(TypeError::BreakOutsideLoop)
+        );
+    }
+
+    #[test]
+    fn test_named_types() {
+        assert_eq!(
+            check_str("type Id <- num\nx: Id <- 1\n+ x 1"),
+            Ok(yaupl_type_str("num"))
+        );
+        assert_eq!(
+            check_str(
+                "type Point <- |- x: num, y: num -|
+                type Line <- [| Point, Point |]
+                length <- [line: Line] -> - line.1.x line.0.x
+                length[[[| |- x: 1, y: 2 -|, |- x: 4, y: 6 -| |]]]"
+            ),
+            Ok(yaupl_type_str("num"))
+        );
+        assert_eq!(
+            check_str("type Point <- |- x: num, y: num -|\np: Point <- |- x: 1 -|"),
+            Err(TypeError::Mismatch {
+                expected: Box::new(Type::Named(Named {
+                    name: Identifier("Point".into()),
+                    arguments: vec![],
+                    span: Span::default(),
+                })),
+                found: Box::new(yaupl_type_str("|- x: num -|")),
+            })
+        );
+        assert_eq!(
+            check_str("x: Nope <- 1"),
+            Err(TypeError::UnknownType(Identifier("Nope".into())))
+        );
+        assert_eq!(
+            check_str("type A <- num\ntype A <- str"),
+            Err(TypeError::DuplicateType(Identifier("A".into())))
+        );
+        assert_eq!(
+            check_str("type A <- B\ntype B <- A"),
+            Err(TypeError::CyclicType(Identifier("A".into())))
+        );
+    }
+
+    #[test]
+    fn test_recursive_types() {
+        // declarations can be used before they're declared, and refer to themselves
+        let tree = "
+            leaf: Tree <- |- value: 1, children: (| |) -|
+            tree: Tree <- |- value: 2, children: (| leaf, leaf |) -|
+            type Tree <- |- value: num, children: Tree@ -|
+        ";
+        assert_eq!(
+            check_str(&format!("{}tree.children[0].children", tree)),
+            Ok(yaupl_type_str("Tree@"))
+        );
+        assert_eq!(
+            check_str(&format!(
+                "{}match[[tree, |- children: (| |) -| -> 0, |- children: (| first, .. |) -| -> first.value]]",
+                tree
+            )),
+            Ok(yaupl_type_str("num"))
+        );
+        // mutually recursive types, written out in full on one side
+        assert_eq!(
+            check_str(
+                "type Even <- |- next: Odd@ -|
+                type Odd <- |- next: Even@ -|
+                e: Even <- |- next: (| |) -|
+                o: |- next: |- next: Odd@ -|@ -| <- |- next: (| e |) -|
+                same: Odd <- o
+                same"
+            ),
+            Ok(yaupl_type_str("|- next: Even@ -|"))
+        );
+        // types that contain themselves directly, which can't be looked inside forever
+        assert_eq!(
+            check_str(
+                "type L <- |- next: L -|
+                f <- [a: L] -> match[[a, x -> 1]]
+                g <- [a: L] -> match[[a, |- next: |- next: n -| -| -> 1]]
+                h <- [a: L] -> match[[a, _ -> 1, |- next: n -| -> 2]]
+                h"
+            ),
+            Err(TypeError::UnreachableArm(1))
+        );
+        assert_eq!(
+            check_str(
+                "type A <- [| A, num |]
+                f <- [a: A] -> match[[a, [| [| b, 1 |], n |] -> n]]"
+            ),
+            Err(TypeError::NonExhaustive)
+        );
+        assert_eq!(
+            check_str(
+                "type A <- [| A, num |]
+                f <- [a: A] -> match[[a, [| [| b, 1 |], n |] -> n, [| b, n |] -> n]]
+                g <- [a: A] -> {[| b, n |] <- a; for[[x <- (| a |), x.1]]}
+                f"
+            ),
+            Ok(yaupl_type_str("[A]=>num"))
+        );
+    }
+
+    #[test]
+    fn test_generics() {
+        assert_eq!(
+            yaupl_type_str("<T>[T@] => T"),
+            Type::Function(Function {
+                type_parameters: vec![Identifier("T".into())],
+                parameters: vec![Type::Group(Group(Box::new(Type::Variable(Identifier(
+                    "T".into()
+                )))))],
+                return_type: Box::new(Type::Variable(Identifier("T".into()))),
+            })
+        );
+        let generic = "
+            id <- <T>[x: T] -> x
+            first <- <T>[xs: T@, default: T] -> match[[xs, (| x, .. |) -> x, _ -> default]]
+            apply <- <A, B>[f: [A] => B, x: A] -> f[[x]]
+        ";
+        assert_eq!(
+            check_str(&format!("{}id", generic)),
+            Ok(yaupl_type_str("<T>[T] => T"))
+        );
+        assert_eq!(
+            check_str(&format!("{}+ id[[1]] first[[(| 2, 3 |), 4]]", generic)),
+            Ok(yaupl_type_str("num"))
+        );
+        // an empty group is fine once the type it's a group of is known from the other arguments
+        assert_eq!(
+            check_str(&format!("{}first[[(| |), \"none\"]]", generic)),
+            Ok(yaupl_type_str("str"))
+        );
+        assert_eq!(
+            check_str(&format!("{}apply[[[n: num] -> == n 0, 1]]", generic)),
+            Ok(yaupl_type_str("bln"))
+        );
+        assert_eq!(
+            check_str(&format!("{}apply[[id, true]]", generic)),
+            Ok(yaupl_type_str("bln"))
+        );
+        assert_eq!(
+            check_str(&format!("{}1 ~> id", generic)),
+            Ok(yaupl_type_str("num"))
+        );
+        assert_eq!(
+            check_str(&format!("{}first[[(| 1 |), \"none\"]]", generic)),
+            Err(TypeError::Mismatch {
+                expected: Box::new(yaupl_type_str("num")),
+                found: Box::new(yaupl_type_str("str")),
+            })
+        );
+        assert_eq!(
+            check_str("count <- <T>[xs: T@] -> 0\ncount[[(| |)]]"),
+            Err(TypeError::CannotInfer(Identifier("T".into())))
+        );
+        // inside the body a type parameter is only the same as itself
+        assert_eq!(
+            check_str("<T>[x: T] -> + x 1"),
+            Err(TypeError::Mismatch {
+                expected: Box::new(yaupl_type_str("num")),
+                found: Box::new(Type::Variable(Identifier("T".into()))),
+            })
+        );
+        // a function that only works for one type can't be given where a generic one is expected
+        let twice = "twice <- [f: <T>[T]=>T] -> [| f[[1]], f[[true]] |]\n";
+        assert_eq!(
+            check_str(&format!("{}{}twice[[id]]", generic, twice)),
+            Ok(yaupl_type_str("[| num, bln |]"))
+        );
+        assert_eq!(
+            check_str(&format!("{}twice[[[n: num] -> + n 1]]", twice)),
+            Err(TypeError::Mismatch {
+                expected: Box::new(yaupl_type_str("<T>[T]=>T")),
+                found: Box::new(yaupl_type_str("[num]=>num")),
+            })
+        );
+    }
+
+    #[test]
+    fn test_generic_types() {
+        assert_eq!(
+            check_str(
+                "type Pair<T> <- [| T, T |]
+                swap <- <T>[pair: Pair<T>] -> [| pair.1, pair.0 |]
+                p: Pair<num> <- [| 1, 2 |]
+                swap[[p]].0"
+            ),
+            Ok(yaupl_type_str("num"))
+        );
+        assert_eq!(
+            check_str("type Pair<T> <- [| T, T |]\np: Pair<num> <- [| 1, \"2\" |]"),
+            Err(TypeError::Mismatch {
+                expected: Box::new(yaupl_type_str("num")),
+                found: Box::new(yaupl_type_str("str")),
+            })
+        );
+        assert_eq!(
+            check_str("type Pair<T> <- [| T, T |]\np: Pair <- [| 1, 2 |]"),
+            Err(TypeError::TypeArgumentCount {
+                name: Identifier("Pair".into()),
+                expected: 1,
+                found: 0,
+            })
+        );
+        assert_eq!(
+            check_str(
+                "type List<T> <- |- head: T, rest: List<T>@ -|
+                head <- <T>[list: List<T>] -> list.head
+                list: List<str> <- |- head: \"a\", rest: (| |- head: \"b\", rest: (| |) -| |) -|
+                [| head[[list]], list.rest[0].rest |]"
+            ),
+            Ok(yaupl_type_str("[| str, List<str>@ |]"))
+        );
+    }
+
+    #[test]
+    fn test_unions() {
+        assert_eq!(
+            yaupl_type_str("num?"),
+            yaupl_type_str("<| some: num, none |>")
+        );
+        let result = "
+            type Result <- <| ok: num, err: str |>
+            parse <- [s: str] -> if[[== s \"1\", <| ok: 1 |>, <| err: \"not a number\" |>]]
+            r: Result <- parse[[\"2\"]]
+        ";
+        assert_eq!(
+            check_str(&format!("{}parse", result)),
+            Ok(yaupl_type_str("[str] => <| ok: num, err: str |>"))
+        );
+        // narrowing in both branches of an `if`
+        assert_eq!(
+            check_str(&format!(
+                "{}if[[is[[r, ok]], r.ok, if[[== r.err \"\", 0, 1]]]]",
+                result
+            )),
+            Ok(yaupl_type_str("num"))
+        );
+        assert_eq!(
+            check_str(&format!(
+                "{}if[[! is[[r, ok]], if[[== r.err \"\", 0, 1]], r.ok]]",
+                result
+            )),
+            Ok(yaupl_type_str("num"))
+        );
+        assert_eq!(
+            check_str(&format!("{}r.ok", result)),
+            Err(TypeError::NotNarrowed {
+                variant: Identifier("ok".into()),
+                found: Box::new(yaupl_type_str("<| ok: num, err: str |>")),
+            })
+        );
+        assert_eq!(
+            check_str(&format!("{}is[[r, nope]]", result)),
+            Err(TypeError::NoVariant {
+                variant: Identifier("nope".into()),
+                found: Box::new(yaupl_type_str("<| ok: num, err: str |>")),
+            })
+        );
+        assert_eq!(
+            check_str("x: num? <- <| some: \"a\" |>"),
+            Err(TypeError::Mismatch {
+                expected: Box::new(yaupl_type_str("num")),
+                found: Box::new(yaupl_type_str("str")),
+            })
+        );
+        assert_eq!(
+            check_str("x: num? <- <| none |>\nmatch[[x, <| some: n |> -> n, <| none |> -> 0]]"),
+            Ok(yaupl_type_str("num"))
+        );
+        assert_eq!(
+            check_str("x: num? <- <| none |>\nmatch[[x, <| some: n |> -> n]]"),
+            Err(TypeError::NonExhaustive)
+        );
+    }
+
+    #[test]
+    fn test_type_operators() {
+        let user = "
+            type User <- |- name: str, email: str, password: str -|
+            type Row <- merge<omit<User, password>, |- id: num -|>
+        ";
+        assert_eq!(
+            check_str(&format!(
+                "{}row: Row <- |- id: 1, name: \"a\", email: \"b\" -|\nrow",
+                user
+            )),
+            Ok(yaupl_type_str("|- id: num, name: str, email: str -|"))
+        );
+        assert_eq!(
+            check_str(&format!("{}x: pick<User, name> <- |- name: 1 -|", user)),
+            Err(TypeError::Mismatch {
+                expected: Box::new(yaupl_type_str("str")),
+                found: Box::new(yaupl_type_str("num")),
+            })
+        );
+        assert_eq!(
+            check_str(&format!(
+                "{}x: partial<pick<User, name, email>> <- |- name: <| none |>, email: <| some: \"e\" |> -|\nx",
+                user
+            )),
+            Ok(yaupl_type_str("|- name: str?, email: str? -|"))
+        );
+        // errors show the fields of the types, not the operators
+        assert_eq!(
+            check_str(&format!("{}x: merge<User, |- name: num -|> <- 1", user)),
+            Err(TypeError::ConflictingField {
+                field: Identifier("name".into()),
+                first: Box::new(yaupl_type_str("str")),
+                second: Box::new(yaupl_type_str("num")),
+            })
+        );
+        assert_eq!(
+            check_str(&format!("{}x: omit<User, id> <- 1", user)),
+            Err(TypeError::NoField {
+                field: Identifier("id".into()),
+                found: Box::new(yaupl_type_str("|- name: str, email: str, password: str -|")),
+            })
+        );
+        assert_eq!(
+            check_str("x: pick<num, a> <- 1"),
+            Err(TypeError::NotComplex(Box::new(yaupl_type_str("num"))))
+        );
+        assert_eq!(
+            check_str("type A <- merge<A, |- x: num -|>"),
+            Err(TypeError::CyclicType(Identifier("A".into())))
+        );
+        // operators on type parameters are evaluated once the parameters are known
+        assert_eq!(
+            check_str(
+                "type WithId<T> <- merge<T, |- id: num -|>
+                x: WithId<|- id: str -|> <- 1"
+            ),
+            Err(TypeError::ConflictingField {
+                field: Identifier("id".into()),
+                first: Box::new(yaupl_type_str("str")),
+                second: Box::new(yaupl_type_str("num")),
+            })
+        );
+        assert_eq!(
+            check_str(
+                "type WithId<T> <- merge<T, |- id: num -|>
+                x: WithId<|- name: str -|> <- |- id: 1, name: \"a\" -|
+                x.id"
+            ),
+            Ok(yaupl_type_str("num"))
+        );
+    }
+
+    #[test]
+    fn test_maps() {
+        assert_eq!(
+            check_str(r#"ages <- {| "ann": 31, "bo": 4 |} ages["ann"]"#),
+            Ok(yaupl_type_str("num?"))
+        );
+        assert_eq!(
+            check_str(r#"m: map<str, num@> <- {| |} insert[[m, "a", (| |)]]"#),
+            Ok(yaupl_type_str("map<str, num@>"))
+        );
+        assert_eq!(check_str("{| |}"), Err(TypeError::EmptyMap));
+        assert_eq!(
+            check_str(r#"{| 1: "one", "two": "two" |}"#),
+            Err(TypeError::Mismatch {
+                expected: Box::new(yaupl_type_str("num")),
+                found: Box::new(yaupl_type_str("str")),
+            })
+        );
+        assert_eq!(
+            check_str(r#"m <- {| 1u8: true |} m[1]"#),
+            Err(TypeError::Mismatch {
+                expected: Box::new(yaupl_type_str("u8")),
+                found: Box::new(yaupl_type_str("num")),
+            })
+        );
+        assert_eq!(
+            check_str("m: map<[num]=>num, num> <- {| |}"),
+            Err(TypeError::InvalidKey(Box::new(yaupl_type_str(
+                "[num]=>num"
+            ))))
+        );
+        assert_eq!(
+            check_str(r#"{| (| 1 |): 1 |}"#),
+            Err(TypeError::InvalidKey(Box::new(yaupl_type_str("num@"))))
+        );
+        // type parameters are checked once they're known
+        let singleton = "singleton <- <K>[k: K] -> {| k: 1 |}\n";
+        assert_eq!(
+            check_str(&format!("{}singleton[[\"a\"]]", singleton)),
+            Ok(yaupl_type_str("map<str, num>"))
+        );
+        assert_eq!(
+            check_str(&format!("{}singleton[[[x: num] -> x]]", singleton)),
+            Err(TypeError::InvalidKey(Box::new(yaupl_type_str(
+                "[num]=>num"
+            ))))
+        );
+        // which they can only be if the function's type says they're keys
+        assert_eq!(
+            check_str("<K>[k: K] -> {m <- {| k: 1 |}; 1}"),
+            Err(TypeError::InvalidKey(Box::new(Type::Variable(Identifier(
+                "K".into()
+            )))))
+        );
+        assert_eq!(
+            check_str("[k] -> {| k: 1 |}"),
+            Err(TypeError::CannotInfer(Identifier("k".into())))
+        );
+        assert_eq!(
+            check_str("type Dict<K> <- map<K, num>\nd: Dict<num@> <- {| |}"),
+            Err(TypeError::InvalidKey(Box::new(yaupl_type_str("num@"))))
+        );
+        assert_eq!(
+            check_str(
+                "type Dict<K> <- map<K, num>
+                type Both<A, B> <- [| Dict<A>, Dict<B> |]
+                b: Both<str, [num]=>num> <- [| {| |}, {| |} |]"
+            ),
+            Err(TypeError::InvalidKey(Box::new(yaupl_type_str(
+                "[num]=>num"
+            ))))
+        );
+        // maps and complexes with the same keys are different types
+        assert_eq!(
+            check_str(r#"m: map<str, num> <- |- a: 1 -|"#),
+            Err(TypeError::Mismatch {
+                expected: Box::new(yaupl_type_str("map<str, num>")),
+                found: Box::new(yaupl_type_str("|- a: num -|")),
+            })
+        );
+        assert_eq!(
+            check_str(r#"insert[[|- a: 1 -|, "b", 2]]"#),
+            Err(TypeError::NotAMap(Box::new(yaupl_type_str("|- a: num -|"))))
+        );
+    }
+
+    #[test]
+    fn test_inference() {
+        assert_eq!(
+            check_str("add <- [a, b] -> + a b\nadd"),
+            Ok(yaupl_type_str("[num, num] => num"))
+        );
+        // parameters that are indexed, looped over, taken apart or have a field accessed are
+        // whatever they're used as
+        assert_eq!(
+            check_str("f <- [xs, i] -> == xs[i] \"a\"\nf"),
+            Ok(yaupl_type_str("[str@, num] => bln"))
+        );
+        assert_eq!(
+            check_str("[xs] -> for[[x <- xs, + x 1]]"),
+            Ok(yaupl_type_str("[num@] => num@"))
+        );
+        assert_eq!(
+            check_str("f <- [p] -> { [| a, b |] <- p; [| b, a |] }\nf"),
+            Ok(yaupl_type_str("<A, B>[[| A, B |]] => [| B, A |]"))
+        );
+        assert_eq!(
+            check_str("[c] -> + c.x 1"),
+            Ok(yaupl_type_str("[|- x: num -|] => num"))
+        );
+        assert_eq!(
+            check_str("f <- [xs] -> xs[1:]\nf"),
+            Ok(yaupl_type_str("<A>[A@] => A@"))
+        );
+        // but only if that's enough to say what they are
+        assert_eq!(
+            check_str("[t] -> t.0"),
+            Err(TypeError::CannotInfer(Identifier("t".into())))
+        );
+        assert_eq!(
+            check_str("[x] -> ++ \"x is \" x"),
+            Err(TypeError::CannotInfer(Identifier("x".into())))
+        );
+        assert_eq!(
+            check_str("[c] -> match[[c, <| some: n |> -> n, _ -> 0]]"),
+            Err(TypeError::CannotInfer(Identifier("c".into())))
+        );
+        assert_eq!(
+            check_str("twice <- [f, x] -> f[[f[[x]]]]\ntwice"),
+            Ok(yaupl_type_str("<A>[[A] => A, A] => A"))
+        );
+        // functions bound to a name are generic, so each use can be of a different type
+        let generic = "
+            id <- [x] -> x
+            pair <- [a, b] -> [| a, b |]
+        ";
+        assert_eq!(
+            check_str(&format!("{}pair", generic)),
+            Ok(yaupl_type_str("<A, B>[A, B] => [| A, B |]"))
+        );
+        assert_eq!(
+            check_str(&format!("{}pair[[id[[1]], id[[\"a\"]]]]", generic)),
+            Ok(yaupl_type_str("[| num, str |]"))
+        );
+        // a lambda that's passed to a function has it's parameters inferred from what it's
+        // passed to
+        assert_eq!(
+            check_str(
+                "apply <- <A, B>[f: [A] => B, x: A] -> f[[x]]
+                apply[[[n] -> == n 0, 1]]"
+            ),
+            Ok(yaupl_type_str("bln"))
+        );
+        assert_eq!(
+            check_str("[n] -> if[[n, 1, 2]]"),
+            Ok(yaupl_type_str("[bln] => num"))
+        );
+        assert_eq!(
+            check_str("add <- [a, b] -> + a b\nadd[[1, true]]"),
+            Err(TypeError::Mismatch {
+                expected: Box::new(yaupl_type_str("num")),
+                found: Box::new(yaupl_type_str("bln")),
+            })
+        );
+        assert_eq!(
+            check_str("[f] -> + f[[1]] f[[true]]"),
+            Err(TypeError::Mismatch {
+                expected: Box::new(yaupl_type_str("num")),
+                found: Box::new(yaupl_type_str("bln")),
+            })
+        );
+        // a parameter that nothing says anything about can't be bound to something that isn't
+        // generic
+        assert_eq!(
+            check_str("g <- [| [x] -> x, 1 |]"),
+            Err(TypeError::CannotInfer(Identifier("x".into())))
+        );
+    }
+}
@@ -0,0 +1,476 @@
+//! Named types, declared with `type`.
+//!
+//! Named types are kept as [`Type::Named`] rather than being replaced by what they name, which is
+//! what lets them refer to themselves. They're only unfolded when something needs to look inside
+//! them, and two types are the same if they're the same once every name in them has been
+//! unfolded as far as it needs to be.
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use crate::{
+    check::{map::check_key, operator::evaluate, Context, TypeError},
+    pointer::Span,
+    statement::type_declaration::TypeDeclaration,
+    types::{
+        complex::Complex,
+        function::Function,
+        interner::{Node, TypeId, TypeInterner},
+        map::Map,
+        tuple::Tuple,
+        union::Union,
+        Group, Named, Type,
+    },
+    Identifier,
+};
+
+/// What a named type is declared as is kept in the scope under `type Name`, which can't clash
+/// with any real binding since it has a space in it.
+///
+/// The declaration is kept as a function type from it's type parameters to it's definition, see
+/// [`declaration`].
+pub(crate) fn type_slot(name: &Identifier) -> Identifier {
+    Identifier(format!("type {}", name.0))
+}
+
+/// Types that an imported module declares but doesn't export are kept under `private Name` as
+/// well, so that they can be unfolded but not written out.
+pub(crate) fn private_type_slot(name: &Identifier) -> Identifier {
+    Identifier(format!("private {}", name.0))
+}
+
+/// How a declaration with type parameters and a definition is kept in a [`type_slot`].
+pub(crate) fn declaration(parameters: &[Identifier], definition: &Type) -> Type {
+    Type::Function(Function {
+        type_parameters: parameters.to_vec(),
+        parameters: vec![],
+        return_type: Box::new(definition.clone()),
+    })
+}
+
+/// The type parameters and definition of a declared type.
+fn declared(name: &Identifier, scope: &Context) -> Option<(Vec<Identifier>, TypeId)> {
+    match scope.get(&type_slot(name)).map(|found| scope.node(*found)) {
+        Some(Node::Function {
+            type_parameters,
+            return_type,
+            ..
+        }) => Some((type_parameters.clone(), *return_type)),
+        _ => None,
+    }
+}
+
+/// Binds all of the declarations, and then checks that every name they use is declared and that
+/// none of them are just names for each other all the way down, like `type A <- B; type B <- A`,
+/// or `type A <- merge<A, B>`. Errors are given with where the declaration they're in is.
+pub(crate) fn declare(
+    declarations: &[&TypeDeclaration],
+    scope: &mut Context,
+) -> Result<(), (Span, TypeError)> {
+    let mut names = BTreeSet::new();
+    for found in declarations {
+        if !names.insert(&found.name) {
+            return Err((found.span, TypeError::DuplicateType(found.name.clone())));
+        }
+        let declared = scope.intern(&declaration(&found.parameters, &found.definition));
+        scope.bind(type_slot(&found.name), declared);
+    }
+    // operators are evaluated while resolving, which would never finish if there was a cycle
+    for found in declarations {
+        let definition = scope.intern(&found.definition);
+        if reaches(&found.name, definition, scope, &mut BTreeSet::new()) {
+            return Err((found.span, TypeError::CyclicType(found.name.clone())));
+        }
+    }
+    for found in declarations {
+        resolve(&found.definition, scope).map_err(|error| (found.span, error))?;
+    }
+    Ok(())
+}
+
+/// Whether unfolding `found` would come back around to the declaration of `name` before getting
+/// to a type that isn't a name or an operator.
+fn reaches(
+    name: &Identifier,
+    found: TypeId,
+    scope: &Context,
+    seen: &mut BTreeSet<Identifier>,
+) -> bool {
+    match scope.node(found) {
+        Node::Named(found_name, _) => {
+            found_name == name
+                || (seen.insert(found_name.clone())
+                    && declared(found_name, scope)
+                        .is_some_and(|(_, definition)| reaches(name, definition, scope, seen)))
+        }
+        node if node.is_operator() => node
+            .parts()
+            .into_iter()
+            .any(|operand| reaches(name, operand, scope, seen)),
+        _ => false,
+    }
+}
+
+/// Checks that every name in a type that's been written out is declared, that it's given the
+/// right number of type arguments, and that any that come from other modules are exported by
+/// them.
+///
+/// Returns the type with every type operator in it evaluated, unless it's operands are type
+/// variables.
+pub(crate) fn resolve(found: &Type, scope: &mut Context) -> Result<TypeId, TypeError> {
+    check_names(found, scope)?;
+    let found = scope.intern(found);
+    evaluate_operators(found, scope)
+}
+
+fn check_names(found: &Type, scope: &mut Context) -> Result<(), TypeError> {
+    match found {
+        Type::Primitive(_) | Type::Variable(_) => Ok(()),
+        Type::Tuple(Tuple(element_types)) => element_types
+            .iter()
+            .try_for_each(|element_type| check_names(element_type, scope)),
+        Type::Group(Group(element_type)) => check_names(element_type, scope),
+        Type::Map(Map { key, value }) => {
+            check_names(key, scope)?;
+            check_names(value, scope)?;
+            let key = scope.intern(key);
+            check_key(key, scope)
+        }
+        Type::Complex(Complex(field_types)) | Type::Union(Union(field_types)) => field_types
+            .values()
+            .try_for_each(|field_type| check_names(field_type, scope)),
+        Type::Function(function) => {
+            function
+                .parameters
+                .iter()
+                .try_for_each(|parameter| check_names(parameter, scope))?;
+            check_names(&function.return_type, scope)
+        }
+        Type::Named(Named {
+            name, arguments, ..
+        }) => {
+            if scope.get(&private_type_slot(name)).is_some() {
+                let mut parts = name.0.splitn(2, '.');
+                return Err(TypeError::NotExported {
+                    module: Identifier(parts.next().unwrap_or_default().into()),
+                    name: Identifier(parts.next().unwrap_or_default().into()),
+                });
+            }
+            let (parameters, definition) =
+                declared(name, scope).ok_or_else(|| TypeError::UnknownType(name.clone()))?;
+            if parameters.len() != arguments.len() {
+                return Err(TypeError::TypeArgumentCount {
+                    name: name.clone(),
+                    expected: parameters.len(),
+                    found: arguments.len(),
+                });
+            }
+            arguments
+                .iter()
+                .try_for_each(|argument| check_names(argument, scope))?;
+            let arguments: Vec<_> = arguments
+                .iter()
+                .map(|argument| scope.intern(argument))
+                .collect();
+            let keys = key_parameters(name, scope);
+            for (ind, argument) in arguments.iter().enumerate() {
+                if keys.contains(&ind) {
+                    check_key(*argument, scope)?;
+                }
+            }
+            // errors in operators that use the type parameters only show up once they're given
+            if !arguments.is_empty() && scope.node(definition).is_operator() {
+                let variables = parameters.into_iter().zip(arguments).collect();
+                let operator = substitute(definition, &variables, &mut scope.interner);
+                evaluate(operator, scope)?;
+            }
+            Ok(())
+        }
+        Type::Operator(operator) => operator
+            .map(|operand| check_names(operand, scope).map(|_| operand.clone()))
+            .map(|_| ()),
+    }
+}
+
+/// Evaluates every type operator in `found` that can be.
+fn evaluate_operators(found: TypeId, scope: &mut Context) -> Result<TypeId, TypeError> {
+    let mut error = None;
+    let node = scope.node(found).clone();
+    let evaluated = node.map(|part| {
+        evaluate_operators(part, scope).unwrap_or_else(|found_error| {
+            error.get_or_insert(found_error);
+            part
+        })
+    });
+    if let Some(error) = error {
+        return Err(error);
+    }
+    let evaluated = scope.insert(evaluated);
+    if scope.node(evaluated).is_operator() {
+        Ok(evaluate(evaluated, scope)?.unwrap_or(evaluated))
+    } else {
+        Ok(evaluated)
+    }
+}
+
+/// The positions of the type parameters of the named type `name` that are used as map keys in
+/// it's declaration, which can only be given types that can be keys.
+pub(crate) fn key_parameters(name: &Identifier, scope: &mut Context) -> BTreeSet<usize> {
+    key_parameters_along(name, scope, &mut vec![])
+}
+
+/// `path` is the named types that are already being looked inside of further up, which are
+/// skipped if they come up again.
+fn key_parameters_along(
+    name: &Identifier,
+    scope: &mut Context,
+    path: &mut Vec<Identifier>,
+) -> BTreeSet<usize> {
+    let (parameters, definition) = match declared(name, scope) {
+        Some(found) if !path.contains(name) => found,
+        _ => return BTreeSet::new(),
+    };
+    path.push(name.clone());
+    let keys = key_variables_along(definition, scope, path);
+    path.pop();
+    parameters
+        .iter()
+        .enumerate()
+        .filter(|(_, parameter)| keys.contains(parameter))
+        .map(|(ind, _)| ind)
+        .collect()
+}
+
+/// The type variables that are used as map keys in `found`, not counting those that are the
+/// type parameters of a generic function type in it.
+pub(crate) fn key_variables(found: TypeId, scope: &mut Context) -> BTreeSet<Identifier> {
+    key_variables_along(found, scope, &mut vec![])
+}
+
+fn key_variables_along(
+    found: TypeId,
+    scope: &mut Context,
+    path: &mut Vec<Identifier>,
+) -> BTreeSet<Identifier> {
+    let node = scope.node(found).clone();
+    let keys: Vec<TypeId> = match &node {
+        Node::Map(key, _) => vec![*key],
+        Node::Named(name, arguments) => {
+            let positions = key_parameters_along(name, scope, path);
+            arguments
+                .iter()
+                .enumerate()
+                .filter(|(ind, _)| positions.contains(ind))
+                .map(|(_, argument)| *argument)
+                .collect()
+        }
+        _ => vec![],
+    };
+    let mut variables = BTreeSet::new();
+    for key in keys {
+        let key = unfold(key, scope);
+        if let Node::Variable(name) = scope.node(key) {
+            variables.insert(name.clone());
+        }
+    }
+    for part in node.parts() {
+        variables.extend(key_variables_along(part, scope, path));
+    }
+    if let Node::Function {
+        type_parameters, ..
+    } = &node
+    {
+        for parameter in type_parameters {
+            variables.remove(parameter);
+        }
+    }
+    variables
+}
+
+/// Replaces every type variable in `found` that's in `variables` with the type it maps to.
+pub(crate) fn substitute(
+    found: TypeId,
+    variables: &BTreeMap<Identifier, TypeId>,
+    interner: &mut TypeInterner,
+) -> TypeId {
+    interner.replace(found, &mut |interner, found| {
+        if !interner.has_variables(found) {
+            return Some(found);
+        }
+        match interner.node(found).clone() {
+            Node::Variable(name) => variables.get(&name).copied(),
+            // a generic function's own type parameters shadow any with the same names
+            Node::Function {
+                type_parameters, ..
+            } if type_parameters
+                .iter()
+                .any(|parameter| variables.contains_key(parameter)) =>
+            {
+                let mut variables = variables.clone();
+                for parameter in &type_parameters {
+                    variables.remove(parameter);
+                }
+                let node = interner.node(found).clone();
+                let substituted = node.map(|part| substitute(part, &variables, interner));
+                Some(interner.insert(substituted))
+            }
+            _ => None,
+        }
+    })
+}
+
+/// Like [`Type::with_variables`], for a type that's already been interned.
+pub(crate) fn with_variables(
+    found: TypeId,
+    parameters: &[Identifier],
+    interner: &mut TypeInterner,
+) -> TypeId {
+    if parameters.is_empty() {
+        return found;
+    }
+    interner.replace(found, &mut |interner, found| match interner.node(found) {
+        Node::Named(name, arguments) if arguments.is_empty() && parameters.contains(name) => {
+            let name = name.clone();
+            Some(interner.insert(Node::Variable(name)))
+        }
+        _ => None,
+    })
+}
+
+/// Replaces a named type with what it names, with it's type arguments filled in, and a type
+/// operator with the complex type it makes, until it's neither any more. Anything inside the type
+/// is left as it is.
+pub(crate) fn unfold(found: TypeId, scope: &mut Context) -> TypeId {
+    let mut found = found;
+    while is_foldable(found, scope) {
+        found = match scope.node(found).clone() {
+            Node::Named(name, arguments) => match declared(&name, scope) {
+                Some((parameters, definition)) => {
+                    let variables = parameters.into_iter().zip(arguments).collect();
+                    substitute(definition, &variables, &mut scope.interner)
+                }
+                // an unknown name, which has already been reported when it was written
+                None => break,
+            },
+            _ => match evaluate(found, scope) {
+                Ok(Some(evaluated)) => evaluated,
+                // errors have already been reported when it was written
+                _ => break,
+            },
+        }
+    }
+    found
+}
+
+/// Whether [`unfold`] would do anything to the type.
+pub(crate) fn is_foldable(found: TypeId, scope: &Context) -> bool {
+    let node = scope.node(found);
+    matches!(node, Node::Named(..)) || node.is_operator()
+}
+
+/// Whether the two types are the same, unfolding any named types in them as needed.
+pub(crate) fn same(first: TypeId, second: TypeId, scope: &mut Context) -> bool {
+    same_assuming(first, second, scope, &mut vec![])
+}
+
+/// `assumed` is the pairs of named types that are already being compared further up. If they
+/// come up again they're assumed to be the same, since if they weren't the comparison further up
+/// would find out.
+fn same_assuming(
+    first: TypeId,
+    second: TypeId,
+    scope: &mut Context,
+    assumed: &mut Vec<(TypeId, TypeId)>,
+) -> bool {
+    if first == second {
+        return true;
+    }
+    if is_foldable(first, scope) || is_foldable(second, scope) {
+        let pair = (first, second);
+        if assumed.contains(&pair) {
+            return true;
+        }
+        assumed.push(pair);
+        let (first, second) = (unfold(first, scope), unfold(second, scope));
+        // unknown names can't be unfolded, and are only the same as themselves
+        return !is_foldable(first, scope)
+            && !is_foldable(second, scope)
+            && same_assuming(first, second, scope, assumed);
+    }
+    match (scope.node(first).clone(), scope.node(second).clone()) {
+        (Node::Tuple(first), Node::Tuple(second)) => {
+            first.len() == second.len()
+                && first
+                    .into_iter()
+                    .zip(second)
+                    .all(|(first, second)| same_assuming(first, second, scope, assumed))
+        }
+        (Node::Group(first), Node::Group(second)) => same_assuming(first, second, scope, assumed),
+        (Node::Map(first_key, first_value), Node::Map(second_key, second_value)) => {
+            same_assuming(first_key, second_key, scope, assumed)
+                && same_assuming(first_value, second_value, scope, assumed)
+        }
+        (Node::Complex(first), Node::Complex(second))
+        | (Node::Union(first), Node::Union(second)) => {
+            first.len() == second.len()
+                && first.into_iter().zip(second).all(
+                    |((first_name, first), (second_name, second))| {
+                        first_name == second_name && same_assuming(first, second, scope, assumed)
+                    },
+                )
+        }
+        (
+            Node::Function {
+                type_parameters: first_type_parameters,
+                parameters: first_parameters,
+                return_type: first_return_type,
+            },
+            Node::Function {
+                type_parameters: second_type_parameters,
+                parameters: second_parameters,
+                return_type: second_return_type,
+            },
+        ) if first_type_parameters.len() == second_type_parameters.len()
+            && first_parameters.len() == second_parameters.len() =>
+        {
+            // generic functions are the same no matter what their type parameters are called
+            let renamed = second_type_parameters
+                .into_iter()
+                .zip(first_type_parameters)
+                .map(|(second, first)| (second, scope.insert(Node::Variable(first))))
+                .collect();
+            first_parameters
+                .into_iter()
+                .chain(Some(first_return_type))
+                .zip(
+                    second_parameters
+                        .into_iter()
+                        .chain(Some(second_return_type)),
+                )
+                .all(|(first, second)| {
+                    let second = substitute(second, &renamed, &mut scope.interner);
+                    same_assuming(first, second, scope, assumed)
+                })
+        }
+        _ => false,
+    }
+}
+
+/// Prefixes every name in a type from the module imported as `alias` with `alias.`, so that
+/// they refer to the module's declarations and not to any in the importing module.
+pub(crate) fn qualify(found: &Type, alias: &Identifier) -> Type {
+    found.replace(&|found| match found {
+        Type::Named(Named {
+            name,
+            arguments,
+            span,
+        }) => Some(Type::Named(Named {
+            name: Identifier(format!("{}.{}", alias.0, name.0)),
+            arguments: arguments
+                .iter()
+                .map(|argument| qualify(argument, alias))
+                .collect(),
+            span: *span,
+        })),
+        _ => None,
+    })
+}
@@ -0,0 +1,116 @@
+//! Evaluating type operators to the complex types they make.
+
+use std::collections::BTreeMap;
+
+use crate::{
+    check::{
+        named::{same, unfold},
+        union::optional,
+        Context, TypeError,
+    },
+    types::interner::{Node, TypeId},
+    Identifier,
+};
+
+/// The complex type the operator makes, or `None` if one of it's operands is a type variable,
+/// which can't be evaluated until it's known what the variable is.
+pub(crate) fn evaluate(operator: TypeId, scope: &mut Context) -> Result<Option<TypeId>, TypeError> {
+    let evaluated = match scope.node(operator).clone() {
+        Node::Merge(first, second) => {
+            let (mut first, second) = match (fields(first, scope)?, fields(second, scope)?) {
+                (Some(first), Some(second)) => (first, second),
+                _ => return Ok(None),
+            };
+            for (name, found) in second {
+                match first.get(&name) {
+                    Some(existing) if !same(*existing, found, scope) => {
+                        return Err(TypeError::ConflictingField {
+                            field: name,
+                            first: scope.boxed(*existing),
+                            second: scope.boxed(found),
+                        })
+                    }
+                    Some(_) => {}
+                    None => {
+                        first.insert(name, found);
+                    }
+                }
+            }
+            first
+        }
+        Node::Pick(found, names) => match fields(found, scope)? {
+            Some(mut fields) => names
+                .iter()
+                .map(|name| Ok((name.clone(), take(&mut fields, name, found, scope)?)))
+                .collect::<Result<_, TypeError>>()?,
+            None => return Ok(None),
+        },
+        Node::Omit(found, names) => match fields(found, scope)? {
+            Some(mut fields) => {
+                for name in &names {
+                    take(&mut fields, name, found, scope)?;
+                }
+                fields
+            }
+            None => return Ok(None),
+        },
+        Node::Partial(found) => match fields(found, scope)? {
+            Some(fields) => fields
+                .into_iter()
+                .map(|(name, field)| {
+                    let optional = match unfold(field, scope) {
+                        unfolded if is_optional(unfolded, scope) => field,
+                        _ => optional(field, scope),
+                    };
+                    (name, optional)
+                })
+                .collect(),
+            None => return Ok(None),
+        },
+        _ => return Ok(None),
+    };
+    Ok(Some(scope.insert(Node::Complex(evaluated))))
+}
+
+/// The fields of an operand, which has to be a complex type.
+fn fields(
+    found: TypeId,
+    scope: &mut Context,
+) -> Result<Option<BTreeMap<Identifier, TypeId>>, TypeError> {
+    let found = unfold(found, scope);
+    match scope.node(found) {
+        Node::Complex(fields) => Ok(Some(fields.clone())),
+        Node::Variable(_) => Ok(None),
+        _ => Err(TypeError::NotComplex(scope.boxed(found))),
+    }
+}
+
+/// Removes a field that has to be there from the fields of `found`.
+fn take(
+    fields: &mut BTreeMap<Identifier, TypeId>,
+    name: &Identifier,
+    found: TypeId,
+    scope: &mut Context,
+) -> Result<TypeId, TypeError> {
+    match fields.remove(name) {
+        Some(field) => Ok(field),
+        None => {
+            let found = unfold(found, scope);
+            Err(TypeError::NoField {
+                field: name.clone(),
+                found: scope.boxed(found),
+            })
+        }
+    }
+}
+
+/// Whether the type is `T?` for some `T`.
+fn is_optional(found: TypeId, scope: &mut Context) -> bool {
+    match scope.node(found) {
+        Node::Union(variants) => match variants.get(&Identifier("some".into())) {
+            Some(some) => optional(*some, scope) == found,
+            None => false,
+        },
+        _ => false,
+    }
+}
@@ -0,0 +1,134 @@
+use std::collections::BTreeSet;
+
+use crate::{
+    check::{
+        expect,
+        infer::{is_fresh, stands_for},
+        infer_shape, literal_type,
+        named::unfold,
+        some_group, Context, TypeError,
+    },
+    pattern::Pattern,
+    types::{
+        interner::{Node, TypeId},
+        primitive::PrimitiveType,
+    },
+    Identifier,
+};
+
+/// Checks that `pattern` can match values of type `found`, and binds all of the names in it to
+/// the types of the parts of the value they'll be bound to.
+pub(crate) fn bind_pattern(
+    pattern: &Pattern,
+    found: TypeId,
+    scope: &mut Context,
+) -> Result<(), TypeError> {
+    let mut names = BTreeSet::new();
+    for name in pattern.names() {
+        if !names.insert(name) {
+            return Err(TypeError::DuplicateBinding(name.clone()));
+        }
+    }
+    bind(pattern, found, scope)
+}
+
+fn bind(pattern: &Pattern, found: TypeId, scope: &mut Context) -> Result<(), TypeError> {
+    let found = unfold(found, scope);
+    // a parameter without a type that's taken apart has to be something that can be
+    if let Node::Variable(name) = scope.node(found) {
+        if is_fresh(name) {
+            let name = name.clone();
+            let shape = match pattern {
+                Pattern::Tuple(elements) => {
+                    let element_types = elements
+                        .iter()
+                        .map(|_| scope.fresh(&Identifier("element".into())))
+                        .collect();
+                    Some(scope.insert(Node::Tuple(element_types)))
+                }
+                Pattern::Complex(fields) => {
+                    let field_types = fields
+                        .iter()
+                        .map(|(name, _)| (name.clone(), scope.fresh(name)))
+                        .collect();
+                    Some(scope.insert(Node::Complex(field_types)))
+                }
+                Pattern::Group(_) => Some(some_group(scope)),
+                // the other variants of the union can't be worked out from one of them
+                Pattern::Variant(..) => return Err(TypeError::CannotInfer(stands_for(&name))),
+                Pattern::Wildcard | Pattern::Binding(..) | Pattern::Literal(_) => None,
+            };
+            if let Some(shape) = shape {
+                let found = infer_shape(found, scope, |_| shape)?;
+                return bind(pattern, found, scope);
+            }
+        }
+    }
+    // there's never going to be a value to take apart, so anything goes
+    if *scope.node(found) == Node::Primitive(PrimitiveType::Never) {
+        for name in pattern.names() {
+            scope.bind(name.clone(), found);
+        }
+        return Ok(());
+    }
+    match (pattern, scope.node(found).clone()) {
+        (Pattern::Wildcard, _) => Ok(()),
+        (Pattern::Binding(name, _), _) => {
+            scope.bind(name.clone(), found);
+            Ok(())
+        }
+        (Pattern::Literal(literal), _) => {
+            let literal = scope.primitive(literal_type(literal));
+            expect(found, literal, scope)
+        }
+        (Pattern::Tuple(elements), Node::Tuple(element_types))
+            if elements.len() == element_types.len() =>
+        {
+            for (element, element_type) in elements.iter().zip(element_types) {
+                bind(element, element_type, scope)?;
+            }
+            Ok(())
+        }
+        (Pattern::Complex(fields), Node::Complex(field_types)) => {
+            let mut seen = BTreeSet::new();
+            for (name, field) in fields {
+                if !seen.insert(name) {
+                    return Err(TypeError::DuplicateField(name.clone()));
+                }
+                let field_type = match field_types.get(name) {
+                    Some(field_type) => *field_type,
+                    None => {
+                        return Err(TypeError::NoField {
+                            field: name.clone(),
+                            found: scope.boxed(found),
+                        })
+                    }
+                };
+                bind(field, field_type, scope)?;
+            }
+            Ok(())
+        }
+        (Pattern::Group(group), Node::Group(element_type)) => {
+            for element in &group.elements {
+                bind(element, element_type, scope)?;
+            }
+            if let Some(Some((rest, _))) = &group.rest {
+                scope.bind(rest.clone(), found);
+            }
+            Ok(())
+        }
+        (Pattern::Variant(name, found_pattern), Node::Union(variants)) => {
+            let variant_type = match variants.get(name) {
+                Some(variant_type) => *variant_type,
+                None => {
+                    return Err(TypeError::NoVariant {
+                        variant: name.clone(),
+                        found: scope.boxed(found),
+                    })
+                }
+            };
+            bind(found_pattern, variant_type, scope)
+        }
+        _ => Err(TypeError::InvalidPattern(scope.boxed(found))),
+    }
+}
@@ -0,0 +1,331 @@
+//! Checking a whole program at once, keeping the type of every expression and carrying on past
+//! any errors.
+
+use std::{cmp::Reverse, collections::BTreeMap};
+
+use crate::{
+    check::{check_statement, declarations, declare, import, Context, Interface, TypeError},
+    expression::Expression,
+    pointer::{Pointer, Span},
+    program::Program,
+    statement::Statement,
+    types::{
+        interner::{TypeId, TypeInterner},
+        primitive::PrimitiveType,
+        Type,
+    },
+    Identifier,
+};
+
+/// A program along with the type of each of it's expressions and statements, and every type
+/// error in it.
+///
+/// The types are kept by the span of the expression they're the type of, so they can be found
+/// for any expression in the program while walking it, or for wherever something is in the source.
+#[derive(Debug)]
+pub struct Typed<'a> {
+    program: &'a Program,
+    types: BTreeMap<Span, TypeId>,
+    /// The type of each top level statement.
+    statements: Vec<TypeId>,
+    interner: TypeInterner,
+    /// Each error is given with where the expression it's about is, or where the statement or
+    /// type declaration it's in is if it isn't about one expression.
+    pub errors: Vec<(Span, TypeError)>,
+}
+
+impl<'a> Typed<'a> {
+    pub fn program(&self) -> &'a Program {
+        self.program
+    }
+
+    /// What all of the types are interned in.
+    pub fn interner(&self) -> &TypeInterner {
+        &self.interner
+    }
+
+    /// The type of an expression in the program, which is `None` for expressions that aren't in
+    /// it and for those that couldn't be checked because of an error.
+    pub fn type_id(&self, expr: &Expression) -> Option<TypeId> {
+        self.types.get(&expr.span).copied()
+    }
+
+    /// The innermost expression with a type that `ptr` is in, and it's type.
+    pub fn type_at(&self, ptr: Pointer) -> Option<(Span, TypeId)> {
+        self.types
+            .iter()
+            .filter(|(span, _)| span.start <= ptr && ptr < span.end)
+            .max_by_key(|(span, _)| (span.start, Reverse(span.end)))
+            .map(|(span, id)| (*span, *id))
+    }
+
+    /// Like [`Typed::type_id`], but with the type built back up out of the interner.
+    pub fn type_of(&self, expr: &Expression) -> Option<Type> {
+        self.type_id(expr).map(|id| self.interner.get(id))
+    }
+
+    /// Every top level statement with it's type. Bindings and type declarations have the type
+    /// `___`, and statements with errors in them have the type `nvr`.
+    pub fn statements(&self) -> impl Iterator<Item = (&'a Statement, TypeId)> + '_ {
+        self.program
+            .statements
+            .iter()
+            .zip(self.statements.iter().copied())
+    }
+}
+
+/// Checks every statement in the program, including the ones after an error, with each of it's
+/// imports bound to the type of the module it imports like in
+/// [`check_module`](super::check_module).
+///
+/// Checking carries on past an error in an expression as if the expression had the type `nvr`,
+/// which can be used anywhere without causing more errors, so every expression that doesn't
+/// depend on one with an error is still checked. A statement that fails anyway has the type
+/// `nvr`, and so does everything it binds if it's a binding.
+pub(crate) fn check_typed<'a>(
+    program: &'a Program,
+    imports: &[(Identifier, Interface)],
+) -> Typed<'a> {
+    let mut scope = Context::recording();
+    import(imports, &mut scope);
+    if let Err(error) = declare(&declarations(program), &mut scope) {
+        scope.errors.push(error);
+    }
+    let never = scope.primitive(PrimitiveType::Never);
+    let mut statements = vec![];
+    for (statement, span) in program.statements.iter().zip(&program.spans) {
+        let found = check_statement(statement, &mut scope).unwrap_or_else(|error| {
+            if !std::mem::take(&mut scope.located) {
+                scope.errors.push((*span, error));
+            }
+            if let Statement::Binding(binding) = statement {
+                for name in binding.pattern.names() {
+                    scope.bind(name.clone(), never);
+                }
+            }
+            never
+        });
+        statements.push(found);
+    }
+    // the types of parameters can be worked out after the expressions that use them are checked
+    let Context {
+        types,
+        errors,
+        mut interner,
+        inference,
+        ..
+    } = scope;
+    // most expressions share a type with others, which only needs to be applied to once
+    let mut applied = BTreeMap::new();
+    let mut apply = |id: TypeId| {
+        *applied
+            .entry(id)
+            .or_insert_with(|| inference.apply(id, &mut interner))
+    };
+    let types = types
+        .unwrap_or_default()
+        .into_iter()
+        .map(|(expr, id)| (expr, apply(id)))
+        .collect();
+    let statements = statements.into_iter().map(apply).collect();
+    Typed {
+        program,
+        types,
+        statements,
+        interner,
+        errors,
+    }
+}
+
+#[cfg(test)]
+mod test_typed {
+    use crate::{
+        expression::ExpressionKind, parse_program, pointer::Pointer, statement::binding::Binding,
+        types::fixtures::num,
+    };
+
+    use super::*;
+
+    /// The expression each binding is bound to, in order.
+    fn bound(program: &Program) -> Vec<&Expression> {
+        program
+            .statements
+            .iter()
+            .filter_map(|statement| match statement {
+                Statement::Binding(Binding { expr, .. }) => Some(expr),
+                _ => None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_expression_types() {
+        let program = parse_program("f <- [x] -> + x 1\nn <- f[[2]]\nl <- < 1 2").unwrap();
+        let typed = check_typed(&program, &[]);
+        assert_eq!(typed.errors, vec![]);
+        let bound = bound(&program);
+
+        let body = match &bound[0].kind {
+            ExpressionKind::Lambda(lambda) => &lambda.body,
+            _ => unreachable!(),
+        };
+        assert_eq!(typed.type_of(body), Some(num()));
+        match &body.kind {
+            ExpressionKind::BinaryOperation(operation) => {
+                assert_eq!(typed.type_of(&operation.first), Some(num()));
+                assert_eq!(typed.type_of(&operation.second), Some(num()));
+                // both are the same type, so they have the same id
+                assert_eq!(
+                    typed.type_id(&operation.first),
+                    typed.type_id(&operation.second)
+                );
+            }
+            _ => unreachable!(),
+        }
+        match &bound[1].kind {
+            ExpressionKind::FunctionCall(call) => {
+                assert_eq!(typed.type_of(&call.args[0]), Some(num()));
+                assert_eq!(typed.type_of(bound[1]), Some(num()));
+            }
+            _ => unreachable!(),
+        }
+        assert_eq!(
+            typed.type_of(bound[2]),
+            Some(Type::Primitive(PrimitiveType::Bln))
+        );
+        // an expression that isn't in the program
+        match &parse_program("\n\n\n\n2").unwrap().statements[0] {
+            Statement::Expression(elsewhere) => assert_eq!(typed.type_of(elsewhere), None),
+            _ => unreachable!(),
+        }
+
+        // the innermost expression at each place
+        let at = |row, col| typed.type_at(Pointer::new(row, col)).map(|(span, _)| span);
+        assert_eq!(
+            at(1, 8),
+            Some(Span::new(Pointer::new(1, 8), Pointer::new(1, 9)))
+        );
+        assert_eq!(at(1, 5).map(|span| span.end()), Some(Pointer::new(1, 6)));
+        assert_eq!(at(1, 9), Some(bound[1].span));
+        assert_eq!(at(1, 3), None);
+    }
+
+    #[test]
+    fn test_checked_against() {
+        let program = parse_program("g: num@ <- (| |)\nh <- [| g, (| 1, 2 |) |]").unwrap();
+        let typed = check_typed(&program, &[]);
+        assert_eq!(typed.errors, vec![]);
+        let group = Type::Group(crate::types::Group(Box::new(num())));
+        let bound = bound(&program);
+        assert_eq!(typed.type_of(bound[0]), Some(group.clone()));
+        match &bound[1].kind {
+            ExpressionKind::Tuple(tuple) => assert_eq!(typed.type_of(&tuple.0[1]), Some(group)),
+            _ => unreachable!(),
+        }
+    }
+
+    #[test]
+    fn test_errors() {
+        let program =
+            parse_program("a <- + 1 true\nb: str <- 1\nc <- + a 1\nd <- missing\nc").unwrap();
+        let typed = check_typed(&program, &[]);
+        let errors = typed
+            .errors
+            .iter()
+            .map(|(span, error)| (span.start(), span.end(), error))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            errors,
+            vec![
+                (
+                    Pointer::new(0, 9),
+                    Pointer::new(0, 13),
+                    &TypeError::Mismatch {
+                        expected: Box::new(num()),
+                        found: Box::new(Type::Primitive(PrimitiveType::Bln)),
+                    }
+                ),
+                (
+                    Pointer::new(1, 10),
+                    Pointer::new(1, 11),
+                    &TypeError::Mismatch {
+                        expected: Box::new(Type::Primitive(PrimitiveType::Str)),
+                        found: Box::new(num()),
+                    }
+                ),
+                (
+                    Pointer::new(3, 5),
+                    Pointer::new(3, 12),
+                    &TypeError::Unbound(crate::Identifier("missing".into()))
+                ),
+            ]
+        );
+        assert_eq!(typed.type_of(bound(&program)[0]), None);
+        // `a` had an error, so `c` checks and has the type it would have had anyway
+        let (_, last) = typed.statements().last().unwrap();
+        assert_eq!(typed.interner().get(last), num());
+
+        // the error is where the expression it's about is, however deep inside the statement
+        let program = parse_program("f <- [x: num] -> x\n[| 1, f[[false]] |]").unwrap();
+        let typed = check_typed(&program, &[]);
+        assert_eq!(
+            typed.errors,
+            vec![(
+                Span::new(Pointer::new(1, 9), Pointer::new(1, 14)),
+                TypeError::Mismatch {
+                    expected: Box::new(num()),
+                    found: Box::new(Type::Primitive(PrimitiveType::Bln)),
+                }
+            )]
+        );
+    }
+
+    #[test]
+    fn test_errors_in_one_statement() {
+        let program = parse_program(
+            "f <- [x: num] -> x\nt <- [| + 1 true, f[[false]], missing, 2 |]\n(| t.0, t.3 |)",
+        )
+        .unwrap();
+        let typed = check_typed(&program, &[]);
+        let errors = typed
+            .errors
+            .iter()
+            .map(|(span, error)| (span.start(), error))
+            .collect::<Vec<_>>();
+        let bln = Type::Primitive(PrimitiveType::Bln);
+        let mismatch = TypeError::Mismatch {
+            expected: Box::new(num()),
+            found: Box::new(bln),
+        };
+        assert_eq!(
+            errors,
+            vec![
+                (Pointer::new(1, 12), &mismatch),
+                (Pointer::new(1, 21), &mismatch),
+                (
+                    Pointer::new(1, 30),
+                    &TypeError::Unbound(crate::Identifier("missing".into()))
+                ),
+            ]
+        );
+        // the parts without errors still have types, and the ones with them don't cause any more
+        let (_, last) = typed.statements().last().unwrap();
+        assert_eq!(
+            typed.interner().get(last),
+            Type::Group(crate::types::Group(Box::new(num())))
+        );
+    }
+
+    #[test]
+    fn test_declaration_errors() {
+        let program = parse_program("type A <- B\nx: A <- 1\nx").unwrap();
+        let typed = check_typed(&program, &[]);
+        assert_eq!(typed.errors.len(), 2);
+        assert_eq!(typed.errors[0].0.start(), Pointer::new(0, 5));
+        assert_eq!(
+            typed.errors[0].1,
+            TypeError::UnknownType(crate::Identifier("B".into()))
+        );
+        assert_eq!(typed.errors[1].0.start(), Pointer::new(1, 8));
+    }
+}
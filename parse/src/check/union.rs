@@ -0,0 +1,155 @@
+//! Union types, and narrowing them down to fewer variants after checking which one a value is.
+
+use std::collections::BTreeMap;
+
+use crate::{
+    check::{check, check_against, expect, lookup, named::same, named::unfold, Context, TypeError},
+    expression::{
+        unary_operations::{UnaryOperation, UnaryOperator},
+        variant::{Is, VariantLiteral},
+        Expression, ExpressionKind,
+    },
+    types::{
+        interner::{Node, TypeId},
+        primitive::PrimitiveType,
+    },
+    Identifier,
+};
+
+/// On it's own a variant literal has the type of the union with just it's variant, which
+/// [`is_narrower`] lets be used as any union with that variant.
+pub(crate) fn variant_literal(
+    variant: &VariantLiteral,
+    scope: &mut Context,
+) -> Result<TypeId, TypeError> {
+    let found = match &variant.value {
+        Some(value) => check(value, scope)?,
+        None => scope.primitive(PrimitiveType::Emp),
+    };
+    Ok(scope.insert(Node::Union(
+        vec![(variant.name.clone(), found)].into_iter().collect(),
+    )))
+}
+
+/// Checks a variant literal against a union that's expected to have it's variant.
+pub(crate) fn check_variant_against(
+    variant: &VariantLiteral,
+    expected: TypeId,
+    scope: &mut Context,
+) -> Result<(), TypeError> {
+    let variant_type = variants(expected, &variant.name, scope)?[&variant.name];
+    match &variant.value {
+        Some(value) => check_against(value, variant_type, scope),
+        None => {
+            let emp = scope.primitive(PrimitiveType::Emp);
+            expect(variant_type, emp, scope)
+        }
+    }
+}
+
+/// `is[[value, variant]]` is a `bln`, and `value` has to be a union with that variant.
+pub(crate) fn is_variant(is: &Is, scope: &mut Context) -> Result<TypeId, TypeError> {
+    let found = check(&is.expr, scope)?;
+    variants(found, &is.variant, scope)?;
+    Ok(scope.primitive(PrimitiveType::Bln))
+}
+
+/// The variants of `found`, which has to be a union with `variant` in it.
+pub(crate) fn variants(
+    found: TypeId,
+    variant: &Identifier,
+    scope: &Context,
+) -> Result<BTreeMap<Identifier, TypeId>, TypeError> {
+    match scope.node(found) {
+        Node::Union(variants) if variants.contains_key(variant) => Ok(variants.clone()),
+        _ => Err(TypeError::NoVariant {
+            variant: variant.clone(),
+            found: scope.boxed(found),
+        }),
+    }
+}
+
+/// `T?`, which is either `some` value of type `T` or `none`.
+pub(crate) fn optional(found: TypeId, scope: &mut Context) -> TypeId {
+    let none = scope.primitive(PrimitiveType::Emp);
+    scope.insert(Node::Union(
+        vec![
+            (Identifier("none".into()), none),
+            (Identifier("some".into()), found),
+        ]
+        .into_iter()
+        .collect(),
+    ))
+}
+
+/// If `condition` checks which variant a name is, the name and what it's known to be when the
+/// condition is `true` and when it's `false`.
+///
+/// When it's `true` it's the union with just that variant, and when it's `false` it's the union
+/// with every other variant, or `nvr` if there aren't any.
+pub(crate) fn narrowing(
+    condition: &Expression,
+    scope: &mut Context,
+) -> Option<(Identifier, TypeId, TypeId)> {
+    match &condition.kind {
+        ExpressionKind::Is(Is { expr, variant }) => {
+            let name = match &expr.kind {
+                ExpressionKind::Identifier(name) => name,
+                _ => return None,
+            };
+            let found = lookup(name, scope).ok()?;
+            let found = unfold(found, scope);
+            let mut rest = variants(found, variant, scope).ok()?;
+            let then = rest.remove_entry(variant)?;
+            let otherwise = if rest.is_empty() {
+                scope.primitive(PrimitiveType::Never)
+            } else {
+                scope.insert(Node::Union(rest))
+            };
+            Some((
+                name.clone(),
+                scope.insert(Node::Union(vec![then].into_iter().collect())),
+                otherwise,
+            ))
+        }
+        ExpressionKind::UnaryOperation(UnaryOperation {
+            operand: UnaryOperator::Not(_),
+            expr,
+        }) => narrowing(expr, scope).map(|(name, then, otherwise)| (name, otherwise, then)),
+        _ => None,
+    }
+}
+
+/// Whether `found` is a union with only some of the variants of the union `expected`, which
+/// means that any of it's values are values of `expected` too.
+pub(crate) fn is_narrower(expected: TypeId, found: TypeId, scope: &mut Context) -> bool {
+    let (expected, found) = (unfold(expected, scope), unfold(found, scope));
+    match (scope.node(expected).clone(), scope.node(found).clone()) {
+        (Node::Union(expected), Node::Union(found)) => found.into_iter().all(|(name, found)| {
+            expected
+                .get(&name)
+                .is_some_and(|expected| same(*expected, found, scope))
+        }),
+        _ => false,
+    }
+}
+
+/// The union of every variant of both unions, if the variants they share carry the same types.
+pub(crate) fn merge(first: TypeId, second: TypeId, scope: &mut Context) -> Option<TypeId> {
+    let (first, second) = (unfold(first, scope), unfold(second, scope));
+    match (scope.node(first).clone(), scope.node(second).clone()) {
+        (Node::Union(mut first), Node::Union(second)) => {
+            for (name, found) in second {
+                match first.get(&name) {
+                    Some(existing) if !same(*existing, found, scope) => return None,
+                    Some(_) => {}
+                    None => {
+                        first.insert(name, found);
+                    }
+                }
+            }
+            Some(scope.insert(Node::Union(first)))
+        }
+        _ => None,
+    }
+}
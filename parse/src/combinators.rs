@@ -2,6 +2,21 @@ use crate::tokens::{comma, token::Token};
 
 use super::{pointer::Pointer, ParseError};
 
+/// Trying one parser after another, like `or_else`, but stopping at the first error that's
+/// [committed](ParseError::is_committed) instead of throwing it away.
+pub(crate) trait OrNext<T> {
+    fn or_next(self, next: impl FnOnce() -> Result<T, ParseError>) -> Result<T, ParseError>;
+}
+
+impl<T> OrNext<T> for Result<T, ParseError> {
+    fn or_next(self, next: impl FnOnce() -> Result<T, ParseError>) -> Result<T, ParseError> {
+        match self {
+            Err(error) if !error.is_committed() => next(),
+            found => found,
+        }
+    }
+}
+
 pub(crate) fn optionally<'a, T>(
     i: &'a str,
     ptr: Pointer,
@@ -21,16 +36,18 @@ pub(crate) fn csv<'a, T>(
     let mut found_types = vec![];
     let (mut i, mut ptr) = (i, ptr);
     loop {
-        if let Ok(values) = f(i, ptr) {
-            i = values.0;
-            ptr = values.1;
-            found_types.push(values.2);
-            if let Ok(comma) = comma(i, ptr) {
-                i = comma.0;
-                ptr = comma.1;
+        match f(i, ptr) {
+            Ok(values) => {
+                i = values.0;
+                ptr = values.1;
+                found_types.push(values.2);
+                if let Ok(comma) = comma(i, ptr) {
+                    i = comma.0;
+                    ptr = comma.1;
+                }
             }
-        } else {
-            break;
+            Err(error) if error.is_committed() => return Err(error),
+            Err(_) => break,
         }
     }
     Ok((i, ptr, found_types))
@@ -81,9 +98,7 @@ pub(crate) fn one_or_more<'a, T: Token + Default + 'static>(
                 continue;
             }
             Err(err) => match found.len() {
-                0 => {
-                    return Err(err)
-                }
+                0 => return Err(err),
                 _ => return Ok((i, ptr, found)),
             },
         }
@@ -0,0 +1,82 @@
+//! Evaluating the fixed-width integer types and `byte`s.
+//!
+//! The arithmetic is done on `i128`s, which every integer type fits in, and then checked against
+//! the range of the type, so any result that doesn't fit is an [`EvalError::Overflow`] rather
+//! than wrapping around.
+
+use types::Number;
+
+use crate::{
+    eval::{EvalError, Value},
+    expression::binary_operations::BinaryOperator,
+    types::primitive::{IntType, PrimitiveType},
+};
+
+/// Evaluates an arithmetic operator or comparison on two integers or two `byte`s, or returns
+/// `None` if the operands aren't those.
+pub(super) fn binary_operation(
+    operand: &BinaryOperator,
+    first: &Value,
+    second: &Value,
+) -> Option<Result<Value, EvalError>> {
+    let (int, first, second) = match (first, second) {
+        (Value::Int(int, first), Value::Int(_, second)) => (Some(*int), *first, *second),
+        (Value::Byte(first), Value::Byte(second)) => (None, *first as i128, *second as i128),
+        _ => return None,
+    };
+    let comparison = |compared| Some(Ok(Value::Bln(compared)));
+    let result = match operand {
+        BinaryOperator::Gt(_) => return comparison(first > second),
+        BinaryOperator::Lt(_) => return comparison(first < second),
+        BinaryOperator::Gte(_) => return comparison(first >= second),
+        BinaryOperator::Lte(_) => return comparison(first <= second),
+        BinaryOperator::Add(_) => first.checked_add(second),
+        BinaryOperator::Sub(_) => first.checked_sub(second),
+        BinaryOperator::Mul(_) => first.checked_mul(second),
+        // division rounds towards zero, and the remainder has the sign of the first operand
+        BinaryOperator::Div(_) | BinaryOperator::Rem(_) if second == 0 => {
+            return Some(Err(EvalError::DivisionByZero))
+        }
+        BinaryOperator::Div(_) => first.checked_div(second),
+        BinaryOperator::Rem(_) => first.checked_rem(second),
+        _ => return None,
+    };
+    // `byte`s can't be used in arithmetic
+    let int = int?;
+    Some(result.map_or(Err(EvalError::Overflow(int)), |result| integer(int, result)))
+}
+
+/// `- x` for a signed integer.
+pub(super) fn negate(int: IntType, value: i128) -> Result<Value, EvalError> {
+    integer(int, -value)
+}
+
+/// Converts between `num`, the integer types and `byte`. The value has to be representable in
+/// the type it's converted to.
+pub(super) fn convert(value: Value, to: &PrimitiveType) -> Result<Value, EvalError> {
+    let out_of_range = |value: &Value| EvalError::OutOfRange {
+        value: value.clone(),
+        to: to.clone(),
+    };
+    let whole = match &value {
+        Value::Num(num) if *to == PrimitiveType::Num => return Ok(Value::Num(*num)),
+        Value::Num(num) => num.to_i128().ok_or_else(|| out_of_range(&value))?,
+        Value::Int(_, int) => *int,
+        Value::Byte(byte) => *byte as i128,
+        _ => return Err(EvalError::UnexpectedValue(value)),
+    };
+    match to {
+        PrimitiveType::Num => Ok(Value::Num(Number::from_parts(whole, 0))),
+        PrimitiveType::Int(int) if int.fits(whole) => Ok(Value::Int(*int, whole)),
+        PrimitiveType::Byte if IntType::U8.fits(whole) => Ok(Value::Byte(whole as u8)),
+        _ => Err(out_of_range(&value)),
+    }
+}
+
+fn integer(int: IntType, value: i128) -> Result<Value, EvalError> {
+    if int.fits(value) {
+        Ok(Value::Int(int, value))
+    } else {
+        Err(EvalError::Overflow(int))
+    }
+}
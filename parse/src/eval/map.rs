@@ -0,0 +1,42 @@
+//! Evaluating maps, which are kept sorted by their keys.
+
+use std::collections::BTreeMap;
+
+use types::Number;
+
+use crate::{
+    eval::{EvalError, Value},
+    types::primitive::IntType,
+    Identifier,
+};
+
+/// The values that can be keys of a map, which unlike every [`Value`] can be ordered.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Key {
+    Num(Number),
+    Int(IntType, i128),
+    Byte(u8),
+    Bln(bool),
+    Str(String),
+}
+
+impl Key {
+    pub(super) fn from_value(value: Value) -> Result<Key, EvalError> {
+        match value {
+            Value::Num(num) => Ok(Key::Num(num)),
+            Value::Int(int, value) => Ok(Key::Int(int, value)),
+            Value::Byte(byte) => Ok(Key::Byte(byte)),
+            Value::Bln(bln) => Ok(Key::Bln(bln)),
+            Value::Str(str) => Ok(Key::Str(str)),
+            value => Err(EvalError::UnexpectedValue(value)),
+        }
+    }
+}
+
+/// `map[key]` is `<| some: value |>` if the key is in the map, and `<| none |>` otherwise.
+pub(super) fn lookup(mut entries: BTreeMap<Key, Value>, key: Value) -> Result<Value, EvalError> {
+    Ok(match entries.remove(&Key::from_value(key)?) {
+        Some(value) => Value::Variant(Identifier("some".into()), Box::new(value)),
+        None => Value::Variant(Identifier("none".into()), Box::new(Value::Emp)),
+    })
+}
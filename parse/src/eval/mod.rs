@@ -0,0 +1,913 @@
+use std::{collections::BTreeMap, fmt};
+
+use types::Number;
+
+use crate::{
+    eval::{map::Key, pattern::matches},
+    expression::{
+        access::{Index, Slice},
+        binary_operations::{BinaryOperation, BinaryOperator},
+        block::Block,
+        conditional::Conditional,
+        conversion::Conversion,
+        function_call::FunctionCall,
+        lambda::Lambda,
+        literal::Literal,
+        looping::For,
+        matching::Match,
+        unary_operations::{UnaryOperation, UnaryOperator},
+        Expression, ExpressionKind,
+    },
+    program::Program,
+    scope::Scope,
+    statement::{binding::Binding, Statement},
+    tokens::token::Token,
+    types::primitive::{IntType, PrimitiveType},
+    Identifier,
+};
+
+mod integer;
+mod map;
+mod pattern;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Value {
+    Num(Number),
+    /// A value of one of the fixed-width integer types, which always fits in that type.
+    Int(IntType, i128),
+    Byte(u8),
+    Bln(bool),
+    Str(String),
+    Emp,
+    Tuple(Vec<Value>),
+    Complex(BTreeMap<Identifier, Value>),
+    Group(Vec<Value>),
+    Map(BTreeMap<Key, Value>),
+    /// A value of a union type, and which variant it is.
+    Variant(Identifier, Box<Value>),
+    Function(Closure),
+}
+
+/// A lambda, along with the bindings that were in scope when it was made.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Closure {
+    lambda: Lambda,
+    scope: Scope<Value>,
+}
+
+#[derive(Debug, PartialEq)]
+#[non_exhaustive]
+pub enum EvalError {
+    /// `x / 0` and `x % 0`.
+    DivisionByZero,
+    /// `oo / oo` and `oo % x` don't have a value.
+    Undefined,
+    /// A value of the wrong type was found, which can only happen if the expression wasn't type
+    /// checked first.
+    UnexpectedValue(Value),
+    Unbound(Identifier),
+    /// An index or slice bound that was past the end of the group.
+    IndexOutOfBounds {
+        index: Number,
+        length: usize,
+    },
+    /// An index that was negative or not a whole number.
+    InvalidIndex(Number),
+    /// A slice where the start is after the end.
+    InvalidSlice {
+        start: usize,
+        end: usize,
+    },
+    /// A value didn't match the pattern in a binding, or any of the arms of a `match`.
+    NoMatch(Value),
+    /// A range that starts or ends at one of the infinities, which would never end.
+    InfiniteRange,
+    /// A range with more than 2^24 numbers in it.
+    RangeTooLong,
+    /// Integer arithmetic with a result that doesn't fit in the type.
+    Overflow(IntType),
+    /// A value was converted to a type that can't represent it, like a `num` with a fractional
+    /// part to an integer type, or an integer that doesn't fit.
+    OutOfRange {
+        value: Value,
+        to: PrimitiveType,
+    },
+}
+
+impl fmt::Display for EvalError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            EvalError::DivisionByZero => write!(f, "division by zero"),
+            EvalError::Undefined => write!(f, "the result is undefined"),
+            EvalError::UnexpectedValue(value) => write!(
+                f,
+                "unexpected value {:?}, the program should be type checked first",
+                value
+            ),
+            EvalError::Unbound(name) => write!(f, "{} isn't defined", name),
+            EvalError::IndexOutOfBounds { index, length } => write!(
+                f,
+                "index {} is past the end of a group with {} elements",
+                index, length
+            ),
+            EvalError::InvalidIndex(index) => write!(f, "{} isn't a whole number index", index),
+            EvalError::InvalidSlice { start, end } => {
+                write!(f, "the slice starts at {}, after it ends at {}", start, end)
+            }
+            EvalError::NoMatch(value) => write!(f, "nothing matches {:?}", value),
+            EvalError::InfiniteRange => write!(f, "the range never ends"),
+            EvalError::RangeTooLong => write!(f, "the range is too long"),
+            EvalError::Overflow(int) => write!(f, "the result doesn't fit in {}", int.token()),
+            EvalError::OutOfRange { value, to } => {
+                write!(f, "{:?} can't be converted to {}", value, to.token())
+            }
+        }
+    }
+}
+
+/// Something that stops an expression from being evaluated to a value.
+#[derive(Debug, PartialEq)]
+pub(crate) enum Interrupt {
+    Error(Box<EvalError>),
+    /// A `return`, which unwinds to the enclosing function call.
+    Return(Box<Value>),
+    /// A `break`, which unwinds to the enclosing loop.
+    Break,
+    /// A `continue`, which unwinds to the enclosing loop.
+    Continue,
+}
+
+impl From<EvalError> for Interrupt {
+    fn from(error: EvalError) -> Self {
+        Interrupt::Error(Box::new(error))
+    }
+}
+
+/// Evaluates every statement in the program, returning the value of the last one.
+pub(crate) fn eval_program(program: &Program) -> Result<Value, EvalError> {
+    eval_module(program, &[]).map(|(value, _)| value)
+}
+
+/// Runs a module with each of it's imports bound to the value of the module it imports.
+/// Returns the value of the last statement, along with a complex of everything the module
+/// `export`s.
+pub(crate) fn eval_module(
+    program: &Program,
+    imports: &[(Identifier, Value)],
+) -> Result<(Value, Value), EvalError> {
+    let mut scope = Scope::new();
+    for (alias, module) in imports {
+        scope.bind(alias.clone(), module.clone());
+    }
+    let mut value = Value::Emp;
+    for statement in &program.statements {
+        match eval_statement(statement, &mut scope) {
+            Ok(found) => value = found,
+            // the checker doesn't allow these, but the most sensible thing to do is to stop there
+            Err(Interrupt::Return(found)) => {
+                value = *found;
+                break;
+            }
+            Err(Interrupt::Break) | Err(Interrupt::Continue) => break,
+            Err(Interrupt::Error(error)) => return Err(*error),
+        }
+    }
+    let exports = program
+        .exports
+        .iter()
+        .filter_map(|name| Some((name.clone(), scope.get(name)?.clone())))
+        .collect();
+    Ok((value, Value::Complex(exports)))
+}
+
+pub(crate) fn eval_statement(
+    statement: &Statement,
+    scope: &mut Scope<Value>,
+) -> Result<Value, Interrupt> {
+    match statement {
+        Statement::Binding(found) => {
+            binding(found, scope)?;
+            Ok(Value::Emp)
+        }
+        Statement::Expression(expr) => eval(expr, scope),
+        Statement::TypeDeclaration(_) => Ok(Value::Emp),
+    }
+}
+
+fn binding(binding: &Binding, scope: &mut Scope<Value>) -> Result<(), Interrupt> {
+    let value = eval(&binding.expr, scope)?;
+    if matches(&binding.pattern, &value, scope) {
+        Ok(())
+    } else {
+        Err(EvalError::NoMatch(value).into())
+    }
+}
+
+pub(crate) fn eval(expr: &Expression, scope: &Scope<Value>) -> Result<Value, Interrupt> {
+    match &expr.kind {
+        ExpressionKind::Literal(literal) => Ok(literal_value(literal)),
+        ExpressionKind::UnaryOperation(operation) => unary_operation(operation, scope),
+        ExpressionKind::BinaryOperation(operation) => binary_operation(operation, scope),
+        ExpressionKind::FunctionCall(call) => function_call(call, None, scope),
+        ExpressionKind::Lambda(lambda) => Ok(Value::Function(Closure {
+            lambda: lambda.clone(),
+            scope: scope.clone(),
+        })),
+        ExpressionKind::Tuple(tuple) => Ok(Value::Tuple(eval_all(&tuple.0, scope)?)),
+        ExpressionKind::Complex(complex) => Ok(Value::Complex(
+            complex
+                .0
+                .iter()
+                .map(|(name, value)| Ok((name.clone(), eval(value, scope)?)))
+                .collect::<Result<_, Interrupt>>()?,
+        )),
+        ExpressionKind::Group(group) => Ok(Value::Group(eval_all(&group.0, scope)?)),
+        ExpressionKind::Map(literal) => Ok(Value::Map(
+            literal
+                .0
+                .iter()
+                .map(|(key, value)| Ok((Key::from_value(eval(key, scope)?)?, eval(value, scope)?)))
+                .collect::<Result<_, Interrupt>>()?,
+        )),
+        ExpressionKind::Insert(insert) => {
+            let mut entries = match eval(&insert.map, scope)? {
+                Value::Map(entries) => entries,
+                value => return Err(EvalError::UnexpectedValue(value).into()),
+            };
+            let key = Key::from_value(eval(&insert.key, scope)?)?;
+            entries.insert(key, eval(&insert.value, scope)?);
+            Ok(Value::Map(entries))
+        }
+        ExpressionKind::Variant(variant) => Ok(Value::Variant(
+            variant.name.clone(),
+            Box::new(match &variant.value {
+                Some(value) => eval(value, scope)?,
+                None => Value::Emp,
+            }),
+        )),
+        ExpressionKind::Is(is) => match eval(&is.expr, scope)? {
+            Value::Variant(name, _) => Ok(Value::Bln(name == is.variant)),
+            value => Err(EvalError::UnexpectedValue(value).into()),
+        },
+        ExpressionKind::Identifier(name) => Ok(lookup(name, scope)?),
+        ExpressionKind::Conditional(found) => conditional(found, scope),
+        ExpressionKind::Match(found) => matching(found, scope),
+        ExpressionKind::Pipeline(pipeline) => pipeline
+            .stages
+            .iter()
+            .try_fold(eval(&pipeline.expr, scope)?, |value, stage| {
+                function_call(&stage.call, Some(value), scope)
+            }),
+        ExpressionKind::Block(found) => block(found, scope),
+        ExpressionKind::For(found) => for_loop(found, scope),
+        ExpressionKind::Break => Err(Interrupt::Break),
+        ExpressionKind::Continue => Err(Interrupt::Continue),
+        ExpressionKind::Return(found) => Err(Interrupt::Return(Box::new(match &found.expr {
+            Some(expr) => eval(expr, scope)?,
+            None => Value::Emp,
+        }))),
+        ExpressionKind::FieldAccess(access) => match eval(&access.expr, scope)? {
+            Value::Complex(mut fields) => fields
+                .remove(&access.field)
+                .ok_or_else(|| EvalError::UnexpectedValue(Value::Complex(fields)).into()),
+            Value::Variant(name, value) if name == access.field => Ok(*value),
+            value => Err(EvalError::UnexpectedValue(value).into()),
+        },
+        ExpressionKind::Projection(projection) => match eval(&projection.expr, scope)? {
+            Value::Tuple(elements) if projection.index < elements.len() => {
+                Ok(elements.into_iter().nth(projection.index).unwrap())
+            }
+            value => Err(EvalError::UnexpectedValue(value).into()),
+        },
+        ExpressionKind::Index(found) => index(found, scope),
+        ExpressionKind::Slice(found) => slice(found, scope),
+        ExpressionKind::Conversion(found) => conversion(found, scope),
+    }
+}
+
+/// Evaluates each of the expressions in order.
+fn eval_all(exprs: &[Expression], scope: &Scope<Value>) -> Result<Vec<Value>, Interrupt> {
+    exprs.iter().map(|expr| eval(expr, scope)).collect()
+}
+
+fn lookup(name: &Identifier, scope: &Scope<Value>) -> Result<Value, EvalError> {
+    scope
+        .get(name)
+        .cloned()
+        .ok_or_else(|| EvalError::Unbound(name.clone()))
+}
+
+/// The arguments are evaluated in the caller's scope, and then the body is evaluated in the scope
+/// the lambda was made in. A `return` anywhere in the body stops there.
+///
+/// `piped` is a value passed in front of the written out arguments by a pipeline.
+fn function_call(
+    call: &FunctionCall,
+    piped: Option<Value>,
+    scope: &Scope<Value>,
+) -> Result<Value, Interrupt> {
+    let closure = match eval(&call.callee, scope)? {
+        Value::Function(closure) => closure,
+        value => return Err(EvalError::UnexpectedValue(value).into()),
+    };
+    let mut args = piped.into_iter().collect::<Vec<_>>();
+    args.extend(eval_all(&call.args, scope)?);
+    let mut closure_scope = closure.scope;
+    for ((name, ..), arg) in closure.lambda.parameters.iter().zip(args) {
+        closure_scope.bind(name.clone(), arg);
+    }
+    match eval(&closure.lambda.body, &closure_scope) {
+        Err(Interrupt::Return(value)) => Ok(*value),
+        found => found,
+    }
+}
+
+/// Each arm is tried with it's own copy of the scope, so that the names bound by one arm's
+/// pattern aren't visible in any other arm.
+fn matching(matching: &Match, scope: &Scope<Value>) -> Result<Value, Interrupt> {
+    let value = eval(&matching.expr, scope)?;
+    for (pattern, body) in &matching.arms {
+        let mut arm_scope = scope.clone();
+        if matches(pattern, &value, &mut arm_scope) {
+            return eval(body, &arm_scope);
+        }
+    }
+    Err(EvalError::NoMatch(value).into())
+}
+
+/// Each element gets it's own copy of the scope to be bound in.
+fn for_loop(found: &For, scope: &Scope<Value>) -> Result<Value, Interrupt> {
+    let mut values = vec![];
+    for element in group(eval(&found.group, scope)?)? {
+        let mut element_scope = scope.clone();
+        if !matches(&found.pattern, &element, &mut element_scope) {
+            return Err(EvalError::NoMatch(element).into());
+        }
+        match eval(&found.body, &element_scope) {
+            Ok(value) => values.push(value),
+            Err(Interrupt::Continue) => {}
+            Err(Interrupt::Break) => break,
+            Err(interrupt) => return Err(interrupt),
+        }
+    }
+    Ok(Value::Group(values))
+}
+
+/// The block gets it's own copy of the scope, so nothing bound in it is visible afterwards.
+fn block(block: &Block, scope: &Scope<Value>) -> Result<Value, Interrupt> {
+    let mut scope = scope.clone();
+    block
+        .statements
+        .iter()
+        .try_fold(Value::Emp, |_, statement| {
+            eval_statement(statement, &mut scope)
+        })
+}
+
+/// Only the branch that's chosen is evaluated.
+fn conditional(conditional: &Conditional, scope: &Scope<Value>) -> Result<Value, Interrupt> {
+    if bln(eval(&conditional.condition, scope)?)? {
+        eval(&conditional.then, scope)
+    } else {
+        eval(&conditional.otherwise, scope)
+    }
+}
+
+fn group(value: Value) -> Result<Vec<Value>, EvalError> {
+    match value {
+        Value::Group(elements) => Ok(elements),
+        value => Err(EvalError::UnexpectedValue(value)),
+    }
+}
+
+/// Converts `index` to a position in a group of length `length`. The end of the group is only
+/// allowed if it's `inclusive`.
+fn position(index: Number, length: usize, inclusive: bool) -> Result<usize, EvalError> {
+    let position = index.to_usize().ok_or(EvalError::InvalidIndex(index))?;
+    if position < length || (inclusive && position == length) {
+        Ok(position)
+    } else {
+        Err(EvalError::IndexOutOfBounds { index, length })
+    }
+}
+
+/// Positions can be given as a `num` or as any of the integer types.
+fn index_number(value: Value) -> Result<Number, EvalError> {
+    match value {
+        Value::Int(_, int) => Ok(Number::from_parts(int, 0)),
+        value => num(value),
+    }
+}
+
+fn index(index: &Index, scope: &Scope<Value>) -> Result<Value, Interrupt> {
+    let mut elements = match eval(&index.expr, scope)? {
+        Value::Map(entries) => return Ok(map::lookup(entries, eval(&index.index, scope)?)?),
+        value => group(value)?,
+    };
+    let position = position(
+        index_number(eval(&index.index, scope)?)?,
+        elements.len(),
+        false,
+    )?;
+    Ok(elements.swap_remove(position))
+}
+
+/// A missing start is the start of the group, and a missing end is the end of it.
+fn slice(slice: &Slice, scope: &Scope<Value>) -> Result<Value, Interrupt> {
+    let elements = group(eval(&slice.expr, scope)?)?;
+    let length = elements.len();
+    let start = match &slice.start {
+        Some(start) => position(index_number(eval(start, scope)?)?, length, true)?,
+        None => 0,
+    };
+    let end = match &slice.end {
+        Some(end) => position(index_number(eval(end, scope)?)?, length, true)?,
+        None => length,
+    };
+    if start > end {
+        return Err(EvalError::InvalidSlice { start, end }.into());
+    }
+    Ok(Value::Group(elements[start..end].to_vec()))
+}
+
+pub(crate) fn literal_value(literal: &Literal) -> Value {
+    match literal {
+        Literal::Bln(bln) => Value::Bln(bln.0),
+        Literal::Str(str) => Value::Str(str.0.clone()),
+        // the parser only allows suffixes on whole numbers that fit in the type
+        Literal::Num(num) => match (&num.suffix, num.whole()) {
+            (Some(PrimitiveType::Int(int)), Some(whole)) => Value::Int(*int, whole),
+            (Some(PrimitiveType::Byte), Some(whole)) => Value::Byte(whole as u8),
+            _ => Value::Num(num.value()),
+        },
+        Literal::Emp(_) => Value::Emp,
+    }
+}
+
+fn num(value: Value) -> Result<Number, EvalError> {
+    match value {
+        Value::Num(num) => Ok(num),
+        value => Err(EvalError::UnexpectedValue(value)),
+    }
+}
+
+/// The most numbers a range can have, since all of them are made up front.
+const MAX_RANGE_LENGTH: i128 = 1 << 24;
+
+fn range(start: Number, end: Number) -> Result<Value, EvalError> {
+    let finite = |number| matches!(number, Number::Num { .. });
+    if start < end && !(finite(start) && finite(end)) {
+        return Err(EvalError::InfiniteRange);
+    }
+    if end - start > Number::from_parts(MAX_RANGE_LENGTH, 0) {
+        return Err(EvalError::RangeTooLong);
+    }
+    let mut values = vec![];
+    let mut value = start;
+    while value < end {
+        values.push(Value::Num(value));
+        value = value + Number::one();
+    }
+    Ok(Value::Group(values))
+}
+
+/// The text of a printable value, as it's written in `++` and string interpolation.
+fn text(value: Value) -> Result<String, EvalError> {
+    match value {
+        Value::Num(num) => Ok(num.to_string()),
+        Value::Int(_, int) => Ok(int.to_string()),
+        Value::Byte(byte) => Ok(byte.to_string()),
+        Value::Bln(bln) => Ok(bln.to_string()),
+        Value::Str(str) => Ok(str),
+        value => Err(EvalError::UnexpectedValue(value)),
+    }
+}
+
+fn bln(value: Value) -> Result<bool, EvalError> {
+    match value {
+        Value::Bln(bln) => Ok(bln),
+        value => Err(EvalError::UnexpectedValue(value)),
+    }
+}
+
+fn unary_operation(operation: &UnaryOperation, scope: &Scope<Value>) -> Result<Value, Interrupt> {
+    let value = eval(&operation.expr, scope)?;
+    match operation.operand {
+        UnaryOperator::Neg(_) => match value {
+            Value::Int(int, value) => Ok(integer::negate(int, value)?),
+            value => Ok(Value::Num(-num(value)?)),
+        },
+        UnaryOperator::Not(_) => Ok(Value::Bln(!bln(value)?)),
+    }
+}
+
+fn binary_operation(operation: &BinaryOperation, scope: &Scope<Value>) -> Result<Value, Interrupt> {
+    let first = eval(&operation.first, scope)?;
+    // the logical operators only evaluate the second operand if they need to
+    match operation.operand {
+        BinaryOperator::And(_) => {
+            return if bln(first)? {
+                Ok(Value::Bln(bln(eval(&operation.second, scope)?)?))
+            } else {
+                Ok(Value::Bln(false))
+            };
+        }
+        BinaryOperator::Or(_) => {
+            return if bln(first)? {
+                Ok(Value::Bln(true))
+            } else {
+                Ok(Value::Bln(bln(eval(&operation.second, scope)?)?))
+            };
+        }
+        _ => {}
+    }
+
+    let second = eval(&operation.second, scope)?;
+    if let Some(value) = integer::binary_operation(&operation.operand, &first, &second) {
+        return Ok(value?);
+    }
+    let undefined = |divisor: Number| {
+        if divisor.is_zero() {
+            EvalError::DivisionByZero
+        } else {
+            EvalError::Undefined
+        }
+    };
+    Ok(match operation.operand {
+        BinaryOperator::Eq(_) => Value::Bln(first == second),
+        BinaryOperator::Neq(_) => Value::Bln(first != second),
+        BinaryOperator::Add(_) => Value::Num(num(first)? + num(second)?),
+        BinaryOperator::Sub(_) => Value::Num(num(first)? - num(second)?),
+        BinaryOperator::Mul(_) => Value::Num(num(first)? * num(second)?),
+        BinaryOperator::Div(_) => {
+            let divisor = num(second)?;
+            Value::Num(
+                num(first)?
+                    .checked_div(divisor)
+                    .ok_or_else(|| undefined(divisor))?,
+            )
+        }
+        BinaryOperator::Rem(_) => {
+            let divisor = num(second)?;
+            Value::Num(
+                num(first)?
+                    .checked_rem(divisor)
+                    .ok_or_else(|| undefined(divisor))?,
+            )
+        }
+        BinaryOperator::Gt(_) => Value::Bln(num(first)? > num(second)?),
+        BinaryOperator::Lt(_) => Value::Bln(num(first)? < num(second)?),
+        BinaryOperator::Gte(_) => Value::Bln(num(first)? >= num(second)?),
+        BinaryOperator::Lte(_) => Value::Bln(num(first)? <= num(second)?),
+        BinaryOperator::Range(_) => range(num(first)?, num(second)?)?,
+        BinaryOperator::Concat(_) => Value::Str(format!("{}{}", text(first)?, text(second)?)),
+        BinaryOperator::And(_) | BinaryOperator::Or(_) => unreachable!(),
+    })
+}
+
+fn conversion(conversion: &Conversion, scope: &Scope<Value>) -> Result<Value, Interrupt> {
+    let value = eval(&conversion.expr, scope)?;
+    Ok(integer::convert(value, &conversion.to)?)
+}
+
+#[cfg(test)]
+mod test_eval {
+    use crate::parse_program;
+
+    use super::*;
+
+    fn eval_str(i: &str) -> Result<Value, EvalError> {
+        eval_program(&parse_program(i).unwrap())
+    }
+
+    #[test]
+    fn test_arithmetic() {
+        assert_eq!(
+            eval_str("% - 7 -1 3"),
+            Ok(Value::Num(Number::from_parts(2, 0)))
+        );
+        assert_eq!(eval_str("- 4"), Ok(Value::Num(Number::from_parts(-4, 0))));
+        assert_eq!(eval_str("% 1 0"), Err(EvalError::DivisionByZero));
+    }
+
+    #[test]
+    fn test_integers() {
+        assert_eq!(
+            eval_str("% / -7i8 2i8 2i8"),
+            Ok(Value::Int(IntType::I8, -1))
+        );
+        assert_eq!(
+            eval_str("+ 255u8 1u8"),
+            Err(EvalError::Overflow(IntType::U8))
+        );
+        assert_eq!(eval_str("- -128i8"), Err(EvalError::Overflow(IntType::I8)));
+        assert_eq!(eval_str("/ 1u32 0u32"), Err(EvalError::DivisionByZero));
+        assert_eq!(eval_str("< 2byte 10byte"), Ok(Value::Bln(true)));
+        assert_eq!(eval_str("++ 3u16 1byte"), Ok(Value::Str("31".into())));
+    }
+
+    #[test]
+    fn test_conversion() {
+        assert_eq!(
+            eval_str("num[[5i32]]"),
+            Ok(Value::Num(Number::from_parts(5, 0)))
+        );
+        assert_eq!(eval_str("byte[[255u64]]"), Ok(Value::Byte(255)));
+        assert_eq!(
+            eval_str("u8[[300]]"),
+            Err(EvalError::OutOfRange {
+                value: Value::Num(Number::from_parts(300, 0)),
+                to: PrimitiveType::Int(IntType::U8),
+            })
+        );
+        assert_eq!(
+            eval_str("i64[[0.5]]"),
+            Err(EvalError::OutOfRange {
+                value: Value::Num(Number::from_parts(
+                    0,
+                    5 * 10u128.pow(types::FRACTIONAL_DIGITS as u32 - 1)
+                )),
+                to: PrimitiveType::Int(IntType::I64),
+            })
+        );
+    }
+
+    #[test]
+    fn test_logic() {
+        assert_eq!(eval_str("! || false true"), Ok(Value::Bln(false)));
+        assert_eq!(eval_str("&& true >= 2 2"), Ok(Value::Bln(true)));
+    }
+
+    #[test]
+    fn test_short_circuit() {
+        // the second operand would fail to evaluate if it was reached
+        assert_eq!(eval_str("&& false == % 1 0 1"), Ok(Value::Bln(false)));
+        assert_eq!(eval_str("|| true == % 1 0 1"), Ok(Value::Bln(true)));
+    }
+
+    #[test]
+    fn test_bindings() {
+        assert_eq!(
+            eval_str(
+                "a <- 1
+                 b <- + a 2
+                 a <- * b 2
+                 - a b"
+            ),
+            Ok(Value::Num(Number::from_parts(3, 0)))
+        );
+        assert_eq!(eval_str("a <- 1"), Ok(Value::Emp));
+    }
+
+    #[test]
+    fn test_closures() {
+        assert_eq!(
+            eval_str(
+                "offset <- 10
+                 add <- [a: num] -> [b: num] -> + + a b offset
+                 add_one <- add[[1]]
+                 offset <- 1000
+                 add_one[[2]]"
+            ),
+            Ok(Value::Num(Number::from_parts(13, 0)))
+        );
+    }
+
+    #[test]
+    fn test_compound_literals() {
+        let one = Value::Num(Number::one());
+        assert_eq!(
+            eval_str(r#"a <- 1 [| a, |- b: (| a, a |), c: "c" -| |]"#),
+            Ok(Value::Tuple(vec![
+                one.clone(),
+                Value::Complex(
+                    vec![
+                        (Identifier("b".into()), Value::Group(vec![one.clone(), one])),
+                        (Identifier("c".into()), Value::Str("c".into())),
+                    ]
+                    .into_iter()
+                    .collect()
+                ),
+            ]))
+        );
+    }
+
+    #[test]
+    fn test_access() {
+        assert_eq!(
+            eval_str(
+                "a <- |- b: [| 1, (| 2, 3, 4 |) |] -|
+                 a.b.1[+ 1 1]"
+            ),
+            Ok(Value::Num(Number::from_parts(4, 0)))
+        );
+        assert_eq!(
+            eval_str("(| 1, 2, 3 |)[1:]"),
+            Ok(Value::Group(vec![
+                Value::Num(Number::from_parts(2, 0)),
+                Value::Num(Number::from_parts(3, 0)),
+            ]))
+        );
+        assert_eq!(eval_str("(| 1, 2, 3 |)[:0]"), Ok(Value::Group(vec![])));
+        assert_eq!(
+            eval_str("i <- 3 (| 1, 2, 3 |)[i]"),
+            Err(EvalError::IndexOutOfBounds {
+                index: Number::from_parts(3, 0),
+                length: 3
+            })
+        );
+        assert_eq!(
+            eval_str("i <- 2 j <- 1 (| 1, 2, 3 |)[i:j]"),
+            Err(EvalError::InvalidSlice { start: 2, end: 1 })
+        );
+    }
+
+    #[test]
+    fn test_conditional() {
+        assert_eq!(
+            eval_str(r#"a <- 1 if[[== a 1, "one", "not one"]]"#),
+            Ok(Value::Str("one".into()))
+        );
+        // the branch that isn't taken would fail to evaluate if it was reached
+        assert_eq!(
+            eval_str("if[[false, % 1 0, 2]]"),
+            Ok(Value::Num(Number::from_parts(2, 0)))
+        );
+    }
+
+    #[test]
+    fn test_blocks() {
+        assert_eq!(
+            eval_str("a <- 1 b <- { a <- 2; + a 1 } + a b"),
+            Ok(Value::Num(Number::from_parts(4, 0)))
+        );
+        assert_eq!(eval_str("{ }"), Ok(Value::Emp));
+    }
+
+    #[test]
+    fn test_return() {
+        assert_eq!(
+            eval_str(
+                r#"size <- [a: num] -> {
+                    if[[> a 1, return "big", ___]];
+                    "small"
+                }
+                [| size[[2]], size[[0]] |]"#
+            ),
+            Ok(Value::Tuple(vec![
+                Value::Str("big".into()),
+                Value::Str("small".into())
+            ]))
+        );
+        // a return only leaves the innermost function
+        assert_eq!(
+            eval_str(
+                "f <- [] -> return 1
+                 g <- [] -> + f[[]] 1
+                 g[[]]"
+            ),
+            Ok(Value::Num(Number::from_parts(2, 0)))
+        );
+    }
+
+    #[test]
+    fn test_destructuring() {
+        assert_eq!(
+            eval_str(
+                r#"[| a, |- b: b -| |] <- [| 1, |- b: "b", c: true -| |]
+                   [| a, b |]"#
+            ),
+            Ok(Value::Tuple(vec![
+                Value::Num(Number::one()),
+                Value::Str("b".into())
+            ]))
+        );
+    }
+
+    #[test]
+    fn test_match() {
+        assert_eq!(
+            eval_str(
+                "first_two <- [group: num@] -> match[[group,
+                    (| |) -> 0,
+                    (| a |) -> a,
+                    (| a, b, ..rest |) -> + a b
+                 ]]
+                 first_two[[(| 1, 2, 3, 4, 5 |)]]"
+            ),
+            Ok(Value::Num(Number::from_parts(3, 0)))
+        );
+        assert_eq!(
+            eval_str(
+                r#"match[[[| true, 2 |], [| false, _ |] -> "a", [| _, 2 |] -> "b", _ -> "c"]]"#
+            ),
+            Ok(Value::Str("b".into()))
+        );
+    }
+
+    #[test]
+    fn test_pipeline() {
+        assert_eq!(
+            eval_str(
+                "double <- [a: num] -> * a 2
+                 sub <- [a: num, b: num] -> - a b
+                 1 ~> double ~> sub[[3]] ~> double"
+            ),
+            Ok(Value::Num(Number::from_parts(-2, 0)))
+        );
+    }
+
+    #[test]
+    fn test_interpolation() {
+        assert_eq!(
+            eval_str(r#"name <- "yaupl" "{name}: {- 1 2.5}, {true} {{{- 3 1}}}""#),
+            Ok(Value::Str("yaupl: -1.5, true {2}".into()))
+        );
+    }
+
+    #[test]
+    fn test_loops() {
+        let nums = |nums: &[i128]| {
+            Value::Group(
+                nums.iter()
+                    .map(|num| Value::Num(Number::from_parts(*num, 0)))
+                    .collect(),
+            )
+        };
+        assert_eq!(eval_str(".. -2 1"), Ok(nums(&[-2, -1, 0])));
+        assert_eq!(eval_str(".. 1 1"), Ok(nums(&[])));
+        // too big to fit, so it saturates to `oo`
+        assert_eq!(
+            eval_str(&format!(".. 0 1{}", "0".repeat(40))),
+            Err(EvalError::InfiniteRange)
+        );
+        assert_eq!(
+            eval_str(&format!(".. 0 1{}", "0".repeat(30))),
+            Err(EvalError::RangeTooLong)
+        );
+        assert_eq!(
+            eval_str(
+                "for[[n <- .. 0 10,
+                    if[[== % n 2 0, continue, if[[> n 6, break, * n n]]]]
+                 ]]"
+            ),
+            Ok(nums(&[1, 9, 25]))
+        );
+        assert_eq!(
+            eval_str("for[[row <- (| (| 1, 2 |), (| 3 |) |), for[[n <- row, + n 1]]]]"),
+            Ok(Value::Group(vec![nums(&[2, 3]), nums(&[4])]))
+        );
+    }
+
+    #[test]
+    fn test_unions() {
+        let safe_div = "
+            div <- [a: num, b: num] -> if[[== b 0, <| err: \"division by zero\" |>, <| ok: / a b |>]]
+            unwrap <- [r: <| ok: num, err: str |>] -> if[[is[[r, ok]], r.ok, 0]]
+        ";
+        assert_eq!(
+            eval_str(&format!("{}unwrap[[div[[6, 3]]]]", safe_div)),
+            Ok(Value::Num(Number::from_parts(2, 0)))
+        );
+        assert_eq!(
+            eval_str(&format!(
+                "{}match[[div[[1, 0]], <| ok: _ |> -> \"fine\", <| err: e |> -> e]]",
+                safe_div
+            )),
+            Ok(Value::Str("division by zero".into()))
+        );
+        assert_eq!(
+            eval_str("<| none |>"),
+            Ok(Value::Variant(
+                Identifier("none".into()),
+                Box::new(Value::Emp)
+            ))
+        );
+    }
+
+    #[test]
+    fn test_maps() {
+        let ages = r#"ages <- insert[[{| "ann": 30, "bo": 4, "ann": 31 |}, "cy", 9]]"#;
+        let variant = |name: &str, value| Value::Variant(Identifier(name.into()), Box::new(value));
+        assert_eq!(
+            eval_str(&format!(r#"{} ages["ann"]"#, ages)),
+            Ok(variant("some", Value::Num(Number::from_parts(31, 0))))
+        );
+        assert_eq!(
+            eval_str(&format!(r#"{} ages["cy"]"#, ages)),
+            Ok(variant("some", Value::Num(Number::from_parts(9, 0))))
+        );
+        assert_eq!(
+            eval_str(&format!(r#"{} ages["dee"]"#, ages)),
+            Ok(variant("none", Value::Emp))
+        );
+        // inserting makes a new map, and leaves the old one as it was
+        assert_eq!(
+            eval_str(r#"a <- {| 1: true |} b <- insert[[a, 2, false]] [| a[2], == a b |]"#),
+            Ok(Value::Tuple(vec![
+                variant("none", Value::Emp),
+                Value::Bln(false)
+            ]))
+        );
+    }
+}
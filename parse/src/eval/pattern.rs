@@ -0,0 +1,59 @@
+use crate::{
+    eval::{literal_value, Value},
+    pattern::Pattern,
+    scope::Scope,
+};
+
+/// Whether `value` matches `pattern`, binding the names in the pattern to the parts of the value
+/// if it does. Some of the names may have already been bound if it doesn't.
+pub(crate) fn matches(pattern: &Pattern, value: &Value, scope: &mut Scope<Value>) -> bool {
+    match (pattern, value) {
+        (Pattern::Wildcard, _) => true,
+        (Pattern::Binding(name, _), _) => {
+            scope.bind(name.clone(), value.clone());
+            true
+        }
+        (Pattern::Literal(literal), _) => literal_value(literal) == *value,
+        (Pattern::Tuple(patterns), Value::Tuple(elements)) => {
+            patterns.len() == elements.len()
+                && patterns
+                    .iter()
+                    .zip(elements)
+                    .all(|(pattern, element)| matches(pattern, element, scope))
+        }
+        (Pattern::Complex(patterns), Value::Complex(fields)) => {
+            patterns
+                .iter()
+                .all(|(name, pattern)| match fields.get(name) {
+                    Some(field) => matches(pattern, field, scope),
+                    None => false,
+                })
+        }
+        (Pattern::Group(group), Value::Group(elements)) => {
+            let length_matches = match group.rest {
+                Some(_) => elements.len() >= group.elements.len(),
+                None => elements.len() == group.elements.len(),
+            };
+            if !length_matches
+                || !group
+                    .elements
+                    .iter()
+                    .zip(elements)
+                    .all(|(pattern, element)| matches(pattern, element, scope))
+            {
+                return false;
+            }
+            if let Some(Some((rest, _))) = &group.rest {
+                scope.bind(
+                    rest.clone(),
+                    Value::Group(elements[group.elements.len()..].to_vec()),
+                );
+            }
+            true
+        }
+        (Pattern::Variant(name, pattern), Value::Variant(variant, value)) => {
+            name == variant && matches(pattern, value, scope)
+        }
+        _ => false,
+    }
+}
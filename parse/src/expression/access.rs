@@ -0,0 +1,144 @@
+use crate::{
+    combinators::{optionally, OrNext},
+    expression::{
+        expression,
+        function_call::{arguments, FunctionCall},
+        literal::digits,
+        Expression, ExpressionKind,
+    },
+    ident,
+    parse_error::ParseError,
+    pointer::{Pointer, Span},
+    tokens::{brace_square_close, brace_square_open, colon, dot},
+    Identifier,
+};
+
+/// Parses any calls, field accesses, tuple projections, indexes and slices that come directly
+/// after `expr`, so `a.b.0[1]` is `((a.b).0)[1]` and `m.f[[x]]` calls the `f` field of `m`.
+///
+/// ```yaupl
+/// function[[arg]]
+/// complex.field
+/// tuple.0
+/// group[1]
+/// group[1:3]
+/// ```
+///
+/// There can't be any whitespace before them, `a [1]` is `a` followed by something else. Calls
+/// are the exception, since nothing else starts with `[[`.
+pub(crate) fn postfix(
+    i: &str,
+    ptr: Pointer,
+    expr: Expression,
+) -> Result<(&str, Pointer, Expression), ParseError> {
+    let (mut i, mut ptr, mut expr) = (i, ptr, expr);
+    while i.starts_with(['.', '[']) || i.trim_start().starts_with("[[") {
+        match suffix(i, ptr) {
+            Ok((new_i, new_ptr, found)) => {
+                i = new_i;
+                ptr = new_ptr;
+                let span = Span::new(expr.span.start, ptr);
+                expr = Expression::new(found.apply(expr), span);
+            }
+            Err(error) if error.is_committed() => return Err(error),
+            Err(_) => break,
+        }
+    }
+    Ok((i, ptr, expr))
+}
+
+enum Suffix {
+    Call(Vec<Expression>),
+    Field(Identifier),
+    Projection(usize),
+    Index(Expression),
+    Slice(Option<Expression>, Option<Expression>),
+}
+
+impl Suffix {
+    fn apply(self, expr: Expression) -> ExpressionKind {
+        let expr = Box::new(expr);
+        match self {
+            Suffix::Call(args) => ExpressionKind::FunctionCall(FunctionCall { callee: expr, args }),
+            Suffix::Field(field) => ExpressionKind::FieldAccess(FieldAccess { expr, field }),
+            Suffix::Projection(index) => ExpressionKind::Projection(Projection { expr, index }),
+            Suffix::Index(index) => ExpressionKind::Index(Index {
+                expr,
+                index: Box::new(index),
+            }),
+            Suffix::Slice(start, end) => ExpressionKind::Slice(Slice {
+                expr,
+                start: start.map(Box::new),
+                end: end.map(Box::new),
+            }),
+        }
+    }
+}
+
+fn suffix(i: &str, ptr: Pointer) -> Result<(&str, Pointer, Suffix), ParseError> {
+    arguments(i, ptr)
+        .map(|res| (res.0, res.1, Suffix::Call(res.2)))
+        .or_next(|| {
+            dot(i, ptr).and_then(|(i, ptr, _dot)| {
+                ident(i, ptr)
+                    .map(|res| (res.0, res.1, Suffix::Field(res.2)))
+                    .or_next(|| projection(i, ptr))
+            })
+        })
+        .or_next(|| index_or_slice(i, ptr))
+}
+
+fn projection(i: &str, ptr: Pointer) -> Result<(&str, Pointer, Suffix), ParseError> {
+    let (i, ptr, index) = digits(i, ptr)?;
+    let index = index
+        .iter()
+        .try_fold(0usize, |acc, digit| {
+            acc.checked_mul(10)?.checked_add(*digit as usize)
+        })
+        .ok_or(ParseError::None)?;
+    Ok((i, ptr, Suffix::Projection(index)))
+}
+
+fn index_or_slice(i: &str, ptr: Pointer) -> Result<(&str, Pointer, Suffix), ParseError> {
+    let (i, ptr, _bracket_open) = brace_square_open(i, ptr)?;
+    let (i, ptr, start) = optionally(i, ptr, &expression);
+    let (i, ptr, found) = match colon(i, ptr) {
+        Ok((i, ptr, _colon)) => {
+            let (i, ptr, end) = optionally(i, ptr, &expression);
+            (i, ptr, Suffix::Slice(start, end))
+        }
+        Err(err) => (i, ptr, Suffix::Index(start.ok_or(err)?)),
+    };
+    let (i, ptr, _bracket_close) = brace_square_close(i, ptr)?;
+    Ok((i, ptr, found))
+}
+
+/// `complex.field`
+#[derive(Debug, Clone, PartialEq)]
+pub struct FieldAccess {
+    pub expr: Box<Expression>,
+    pub field: Identifier,
+}
+
+/// `tuple.0`, the index is always known statically.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Projection {
+    pub expr: Box<Expression>,
+    pub index: usize,
+}
+
+/// `group[index]`
+#[derive(Debug, Clone, PartialEq)]
+pub struct Index {
+    pub expr: Box<Expression>,
+    pub index: Box<Expression>,
+}
+
+/// `group[start:end]`, from `start` up to but not including `end`. Either end can be left off to
+/// slice from the start or to the end of the group.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Slice {
+    pub expr: Box<Expression>,
+    pub start: Option<Box<Expression>>,
+    pub end: Option<Box<Expression>>,
+}
@@ -4,44 +4,54 @@ use crate::{
     parse_error::ParseError,
     pointer::Pointer,
     tokens::{
-        binary_operator_add, binary_operator_div, binary_operator_eq, binary_operator_gt,
-        binary_operator_gte, binary_operator_lt, binary_operator_lte, binary_operator_mul,
-        binary_operator_neq, binary_operator_sub, BinaryOperatorAdd, BinaryOperatorDiv,
-        BinaryOperatorEq, BinaryOperatorGt, BinaryOperatorGte, BinaryOperatorLt, BinaryOperatorLte,
-        BinaryOperatorMul, BinaryOperatorNeq, BinaryOperatorSub,
+        binary_operator_add, binary_operator_and, binary_operator_concat, binary_operator_div,
+        binary_operator_eq, binary_operator_gt, binary_operator_gte, binary_operator_lt,
+        binary_operator_lte, binary_operator_mul, binary_operator_neq, binary_operator_or,
+        binary_operator_range, binary_operator_rem, binary_operator_sub, BinaryOperatorAdd,
+        BinaryOperatorAnd, BinaryOperatorConcat, BinaryOperatorDiv, BinaryOperatorEq,
+        BinaryOperatorGt, BinaryOperatorGte, BinaryOperatorLt, BinaryOperatorLte,
+        BinaryOperatorMul, BinaryOperatorNeq, BinaryOperatorOr, BinaryOperatorRange,
+        BinaryOperatorRem, BinaryOperatorSub,
     },
 };
 
 pub(crate) fn binary_operation(
     i: &str,
     ptr: Pointer,
-) -> Result<(&str, Pointer, Expression), ParseError> {
+) -> Result<(&str, Pointer, BinaryOperation), ParseError> {
     let (i, ptr, operand) = binary_operator(i, ptr)?;
     let (i, ptr, first) = expression(i, ptr)?;
     let (i, ptr, second) = expression(i, ptr)?;
     Ok((
         i,
         ptr,
-        Expression::BinaryOperation(BinaryOperation {
+        BinaryOperation {
             operand,
             first: Box::new(first),
             second: Box::new(second),
-        }),
+        },
     ))
 }
 
 fn binary_operator(i: &str, ptr: Pointer) -> Result<(&str, Pointer, BinaryOperator), ParseError> {
-    binary_operator_add(i.clone(), ptr.clone())
+    // `++` has to come before `+`
+    binary_operator_concat(i, ptr)
         .map(op_to_op_enum)
-        .or(binary_operator_sub(i.clone(), ptr.clone()).map(op_to_op_enum))
-        .or(binary_operator_mul(i.clone(), ptr.clone()).map(op_to_op_enum))
-        .or(binary_operator_div(i.clone(), ptr.clone()).map(op_to_op_enum))
-        .or(binary_operator_gt(i.clone(), ptr.clone()).map(op_to_op_enum))
-        .or(binary_operator_lt(i.clone(), ptr.clone()).map(op_to_op_enum))
-        .or(binary_operator_gte(i.clone(), ptr.clone()).map(op_to_op_enum))
-        .or(binary_operator_lte(i.clone(), ptr.clone()).map(op_to_op_enum))
-        .or(binary_operator_eq(i.clone(), ptr.clone()).map(op_to_op_enum))
-        .or(binary_operator_neq(i.clone(), ptr.clone()).map(op_to_op_enum))
+        .or_else(|_| binary_operator_add(i, ptr).map(op_to_op_enum))
+        .or_else(|_| binary_operator_sub(i, ptr).map(op_to_op_enum))
+        .or_else(|_| binary_operator_mul(i, ptr).map(op_to_op_enum))
+        .or_else(|_| binary_operator_div(i, ptr).map(op_to_op_enum))
+        .or_else(|_| binary_operator_rem(i, ptr).map(op_to_op_enum))
+        // `>=` and `<=` have to come before `>` and `<`, otherwise they'd never match
+        .or_else(|_| binary_operator_gte(i, ptr).map(op_to_op_enum))
+        .or_else(|_| binary_operator_lte(i, ptr).map(op_to_op_enum))
+        .or_else(|_| binary_operator_gt(i, ptr).map(op_to_op_enum))
+        .or_else(|_| binary_operator_lt(i, ptr).map(op_to_op_enum))
+        .or_else(|_| binary_operator_eq(i, ptr).map(op_to_op_enum))
+        .or_else(|_| binary_operator_neq(i, ptr).map(op_to_op_enum))
+        .or_else(|_| binary_operator_and(i, ptr).map(op_to_op_enum))
+        .or_else(|_| binary_operator_or(i, ptr).map(op_to_op_enum))
+        .or_else(|_| binary_operator_range(i, ptr).map(op_to_op_enum))
 }
 
 fn op_to_op_enum(
@@ -50,23 +60,34 @@ fn op_to_op_enum(
     (res.0, res.1, res.2.into())
 }
 
+#[derive(Debug, Clone, PartialEq)]
 pub struct BinaryOperation {
-    operand: BinaryOperator,
-    first: Box<Expression>,
-    second: Box<Expression>,
+    pub operand: BinaryOperator,
+    pub first: Box<Expression>,
+    pub second: Box<Expression>,
 }
 
-pub(crate) enum BinaryOperator {
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum BinaryOperator {
     Add(BinaryOperatorAdd),
     Sub(BinaryOperatorSub),
     Mul(BinaryOperatorMul),
     Div(BinaryOperatorDiv),
+    Rem(BinaryOperatorRem),
     Gt(BinaryOperatorGt),
     Lt(BinaryOperatorLt),
     Gte(BinaryOperatorGte),
     Lte(BinaryOperatorLte),
     Eq(BinaryOperatorEq),
     Neq(BinaryOperatorNeq),
+    /// Short-circuiting, `second` is only evaluated if `first` is `true`.
+    And(BinaryOperatorAnd),
+    /// Short-circuiting, `second` is only evaluated if `first` is `false`.
+    Or(BinaryOperatorOr),
+    /// Joins the text of two printable values into a `str`.
+    Concat(BinaryOperatorConcat),
+    /// The group of `num`s from `first` up to but not including `second`, counting up by 1.
+    Range(BinaryOperatorRange),
 }
 
 // impl<T: Into<BinaryOperator>> From<(&str, Pointer, T)> for (&str, Pointer, BinaryOperator) {
@@ -81,6 +102,18 @@ impl From<BinaryOperatorAdd> for BinaryOperator {
     }
 }
 
+impl From<BinaryOperatorConcat> for BinaryOperator {
+    fn from(op: BinaryOperatorConcat) -> Self {
+        BinaryOperator::Concat(op)
+    }
+}
+
+impl From<BinaryOperatorRange> for BinaryOperator {
+    fn from(op: BinaryOperatorRange) -> Self {
+        BinaryOperator::Range(op)
+    }
+}
+
 impl From<BinaryOperatorSub> for BinaryOperator {
     fn from(op: BinaryOperatorSub) -> Self {
         BinaryOperator::Sub(op)
@@ -99,6 +132,12 @@ impl From<BinaryOperatorDiv> for BinaryOperator {
     }
 }
 
+impl From<BinaryOperatorRem> for BinaryOperator {
+    fn from(op: BinaryOperatorRem) -> Self {
+        BinaryOperator::Rem(op)
+    }
+}
+
 impl From<BinaryOperatorGt> for BinaryOperator {
     fn from(op: BinaryOperatorGt) -> Self {
         BinaryOperator::Gt(op)
@@ -134,3 +173,15 @@ impl From<BinaryOperatorNeq> for BinaryOperator {
         BinaryOperator::Neq(op)
     }
 }
+
+impl From<BinaryOperatorAnd> for BinaryOperator {
+    fn from(op: BinaryOperatorAnd) -> Self {
+        BinaryOperator::And(op)
+    }
+}
+
+impl From<BinaryOperatorOr> for BinaryOperator {
+    fn from(op: BinaryOperatorOr) -> Self {
+        BinaryOperator::Or(op)
+    }
+}
@@ -0,0 +1,64 @@
+use crate::{
+    combinators::optionally,
+    expression::{expression, Expression},
+    parse_error::ParseError,
+    pointer::Pointer,
+    statement::{statement, Statement},
+    tokens::{brace_curly_close, brace_curly_open, keyword_return, semicolon},
+};
+
+/// ```yaupl
+/// {
+///     a <- 1;
+///     b <- + a 1;
+///     * a b
+/// }
+/// ```
+///
+/// The statements are separated the same way as they are in a [`Program`](crate::program::Program),
+/// and the value of the block is the value of the last one. Any bindings made in the block go out
+/// of scope at the end of it.
+pub(crate) fn block(i: &str, ptr: Pointer) -> Result<(&str, Pointer, Block), ParseError> {
+    let (mut i, mut ptr, _brace_open) = brace_curly_open(i, ptr)?;
+    let mut statements = vec![];
+    loop {
+        if let Ok((i, ptr, _brace_close)) = brace_curly_close(i, ptr) {
+            return Ok((i, ptr, Block { statements }));
+        }
+        let (new_i, new_ptr, found) = statement(i, ptr)?;
+        statements.push(found);
+        let (new_i, new_ptr, _semicolon) = optionally(new_i, new_ptr, &semicolon);
+        i = new_i;
+        ptr = new_ptr;
+    }
+}
+
+/// ```yaupl
+/// return + a 1
+/// ```
+///
+/// Returns from the enclosing function. A bare `return` returns `___`.
+pub(crate) fn return_expression(
+    i: &str,
+    ptr: Pointer,
+) -> Result<(&str, Pointer, Return), ParseError> {
+    let (i, ptr, _return) = keyword_return(i, ptr)?;
+    let (i, ptr, expr) = optionally(i, ptr, &expression);
+    Ok((
+        i,
+        ptr,
+        Return {
+            expr: expr.map(Box::new),
+        },
+    ))
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Block {
+    pub statements: Vec<Statement>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Return {
+    pub expr: Option<Box<Expression>>,
+}
@@ -0,0 +1,37 @@
+use crate::{
+    combinators::csv,
+    expression::{expression, Expression},
+    ident,
+    parse_error::ParseError,
+    pointer::Pointer,
+    tokens::{colon, tesla_close, tesla_open},
+    Identifier,
+};
+
+/// ```yaupl
+/// |- name: "yaupl", version: 1 -|
+/// ```
+///
+/// The type of a complex literal is the complex type with the same field names, mapped to the
+/// types of the field's values, `|-name: str, version: num-|` for the example above.
+pub(crate) fn complex_literal(
+    i: &str,
+    ptr: Pointer,
+) -> Result<(&str, Pointer, ComplexLiteral), ParseError> {
+    let (i, ptr, _bracket) = tesla_open(i, ptr)?;
+    let (i, ptr, fields) = csv(i, ptr, &field)?;
+    let (i, ptr, _bracket) = tesla_close(i, ptr)?;
+    Ok((i, ptr, ComplexLiteral(fields)))
+}
+
+fn field(i: &str, ptr: Pointer) -> Result<(&str, Pointer, (Identifier, Expression)), ParseError> {
+    let (i, ptr, name) = ident(i, ptr)?;
+    let (i, ptr, _colon) = colon(i, ptr)?;
+    let (i, ptr, value) = expression(i, ptr)?;
+    Ok((i, ptr, (name, value)))
+}
+
+/// The fields are kept in the order they were written in, so that they're evaluated in that order
+/// and duplicates can be reported.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ComplexLiteral(pub Vec<(Identifier, Expression)>);
@@ -0,0 +1,43 @@
+use crate::{
+    expression::{expression, Expression},
+    parse_error::ParseError,
+    pointer::Pointer,
+    tokens::{brace_square_close, brace_square_open, comma, keyword_if},
+};
+
+/// ```yaupl
+/// if[[condition, then, else]]
+/// ```
+///
+/// Looks like a function call, but only the branch that's chosen is evaluated.
+pub(crate) fn conditional(
+    i: &str,
+    ptr: Pointer,
+) -> Result<(&str, Pointer, Conditional), ParseError> {
+    let (i, ptr, _if) = keyword_if(i, ptr)?;
+    let (i, ptr, _bracket_open) = brace_square_open(i, ptr)?;
+    let (i, ptr, _bracket_open) = brace_square_open(i, ptr)?;
+    let (i, ptr, condition) = expression(i, ptr)?;
+    let (i, ptr, _comma) = comma(i, ptr)?;
+    let (i, ptr, then) = expression(i, ptr)?;
+    let (i, ptr, _comma) = comma(i, ptr)?;
+    let (i, ptr, otherwise) = expression(i, ptr)?;
+    let (i, ptr, _bracket_close) = brace_square_close(i, ptr)?;
+    let (i, ptr, _bracket_close) = brace_square_close(i, ptr)?;
+    Ok((
+        i,
+        ptr,
+        Conditional {
+            condition: Box::new(condition),
+            then: Box::new(then),
+            otherwise: Box::new(otherwise),
+        },
+    ))
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Conditional {
+    pub condition: Box<Expression>,
+    pub then: Box<Expression>,
+    pub otherwise: Box<Expression>,
+}
@@ -0,0 +1,41 @@
+use crate::{
+    expression::{expression, Expression},
+    parse_error::ParseError,
+    pointer::Pointer,
+    tokens::{brace_square_close, brace_square_open, keyword_byte, keyword_int, keyword_num},
+    types::primitive::PrimitiveType,
+};
+
+/// ```yaupl
+/// u8[[255]]
+/// num[[count]]
+/// byte[[+ 1u8 2u8]]
+/// ```
+///
+/// Converts between `num`, the integer types and `byte`. Converting a value that the type can't
+/// represent, like a `num` with a fractional part or an integer that doesn't fit, is an error
+/// when it's evaluated.
+pub(crate) fn conversion(i: &str, ptr: Pointer) -> Result<(&str, Pointer, Conversion), ParseError> {
+    let (i, ptr, to) = keyword_num(i, ptr)
+        .or_else(|_| keyword_int(i, ptr))
+        .or_else(|_| keyword_byte(i, ptr))?;
+    let (i, ptr, _bracket_open) = brace_square_open(i, ptr)?;
+    let (i, ptr, _bracket_open) = brace_square_open(i, ptr)?;
+    let (i, ptr, expr) = expression(i, ptr)?;
+    let (i, ptr, _bracket_close) = brace_square_close(i, ptr)?;
+    let (i, ptr, _bracket_close) = brace_square_close(i, ptr)?;
+    Ok((
+        i,
+        ptr,
+        Conversion {
+            to,
+            expr: Box::new(expr),
+        },
+    ))
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Conversion {
+    pub to: PrimitiveType,
+    pub expr: Box<Expression>,
+}
@@ -1,12 +1,32 @@
-use crate::{expression::Expression, parse_error::ParseError, pointer::Pointer, Identifier};
+use crate::{
+    combinators::csv,
+    expression::{expression, Expression},
+    parse_error::ParseError,
+    pointer::Pointer,
+    tokens::{brace_square_close, brace_square_open},
+};
 
-pub(crate) fn function_call(
+/// The arguments of a call, which come after the function being called.
+///
+/// ```yaupl
+/// name[[arg, arg]]
+/// module.name[[arg]]
+/// make_adder[[1]][[2]]
+/// ```
+pub(crate) fn arguments(
     i: &str,
     ptr: Pointer,
-) -> Result<(&str, Pointer, FunctionCall), ParseError> {
-    todo!()
+) -> Result<(&str, Pointer, Vec<Expression>), ParseError> {
+    let (i, ptr, _bracket_open) = brace_square_open(i, ptr)?;
+    let (i, ptr, _bracket_open) = brace_square_open(i, ptr)?;
+    let (i, ptr, args) = csv(i, ptr, &expression)?;
+    let (i, ptr, _bracket_close) = brace_square_close(i, ptr)?;
+    let (i, ptr, _bracket_close) = brace_square_close(i, ptr)?;
+    Ok((i, ptr, args))
 }
+
+#[derive(Debug, Clone, PartialEq)]
 pub struct FunctionCall {
-    name: Identifier,
-    args: Vec<Expression>,
+    pub callee: Box<Expression>,
+    pub args: Vec<Expression>,
 }
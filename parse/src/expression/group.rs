@@ -0,0 +1,27 @@
+use crate::{
+    combinators::csv,
+    expression::{expression, Expression},
+    parse_error::ParseError,
+    pointer::Pointer,
+    tokens::{brace_group_close, brace_group_open},
+};
+
+/// ```yaupl
+/// (| 1, 2, 3 |)
+/// ```
+///
+/// Every element of a group has to have the same type, `T`, and the group is then a `T@`. The
+/// element type of an empty group can't be worked out from it's elements, so it has to come from
+/// somewhere else, like the annotation on a binding or the parameter it's passed to.
+pub(crate) fn group_literal(
+    i: &str,
+    ptr: Pointer,
+) -> Result<(&str, Pointer, GroupLiteral), ParseError> {
+    let (i, ptr, _bracket) = brace_group_open(i, ptr)?;
+    let (i, ptr, elements) = csv(i, ptr, &expression)?;
+    let (i, ptr, _bracket) = brace_group_close(i, ptr)?;
+    Ok((i, ptr, GroupLiteral(elements)))
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct GroupLiteral(pub Vec<Expression>);
@@ -0,0 +1,67 @@
+use crate::{
+    combinators::{csv, optionally},
+    expression::{expression, Expression},
+    parse_error::ParseError,
+    pointer::{Pointer, Span},
+    spanned_ident,
+    tokens::{arrow_right, brace_square_close, brace_square_open, colon},
+    types::{type_parameters, yaupl_type, Type},
+    Identifier,
+};
+
+/// ```yaupl
+/// [a: num, b: num] -> + a b
+/// <T>[a: T, b: T] -> (| a, b |)
+/// [a, b] -> + a b
+/// ```
+///
+/// The type of a lambda is a function type made from the types of it's parameters and the type of
+/// it's body, `[num, num]=>num` and `<T>[T, T]=>T@` for the first two examples above. The types of
+/// parameters without one written out are inferred from how they're used in the body.
+pub(crate) fn lambda(i: &str, ptr: Pointer) -> Result<(&str, Pointer, Lambda), ParseError> {
+    let (i, ptr, type_parameters) = optionally(i, ptr, &type_parameters);
+    let type_parameters = type_parameters.unwrap_or_default();
+    let (i, ptr, _bracket_open) = brace_square_open(i, ptr)?;
+    let (i, ptr, parameters) = csv(i, ptr, &parameter)?;
+    let (i, ptr, _bracket_close) = brace_square_close(i, ptr)?;
+    let (i, ptr, _arrow) = arrow_right(i, ptr)?;
+    let (i, ptr, body) = expression(i, ptr)?;
+    Ok((
+        i,
+        ptr,
+        Lambda {
+            parameters: parameters
+                .into_iter()
+                .map(|(name, found, span)| {
+                    let found = found.map(|found| found.with_variables(&type_parameters));
+                    (name, found, span)
+                })
+                .collect(),
+            type_parameters,
+            body: Box::new(body),
+        },
+    ))
+}
+
+type Parameter = (Identifier, Option<Type>, Span);
+
+/// `a: num`, or just `a` to have it's type inferred.
+fn parameter(i: &str, ptr: Pointer) -> Result<(&str, Pointer, Parameter), ParseError> {
+    let (i, ptr, (name, span)) = spanned_ident(i, ptr)?;
+    let (i, ptr, found) = optionally(i, ptr, &|i, ptr| {
+        let (i, ptr, _colon) = colon(i, ptr)?;
+        yaupl_type(i, ptr)
+    });
+    Ok((i, ptr, (name, found, span)))
+}
+
+/// A function literal. Any bindings that are in scope where the lambda is written can be used in
+/// it's body, and they keep the values they had when the lambda was made.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Lambda {
+    pub type_parameters: Vec<Identifier>,
+    /// The name and type of each parameter, and where the name was written. The type is `None`
+    /// if it wasn't written out.
+    pub parameters: Vec<(Identifier, Option<Type>, Span)>,
+    pub body: Box<Expression>,
+}
@@ -1,78 +1,204 @@
-use std::marker::PhantomData;
+use types::{Number, FRACTIONAL_DIGITS};
 
 use crate::{
-    combinators::one_or_more,
-    combinators::optionally,
-    digit,
-    expression::Expression,
+    combinators::{optionally, OrNext},
+    expression::{
+        binary_operations::{BinaryOperation, BinaryOperator},
+        expression, spanned, Expression, ExpressionKind,
+    },
     parse_error::ParseError,
-    pointer::Pointer,
-    tokens::KeywordTrue,
-    tokens::{binary_operator_sub, decimal, keyword_emp, keyword_false, keyword_true, quote},
+    pointer::{Pointer, Span},
+    tokens::{
+        brace_curly_close, brace_curly_open, decimal, keyword_byte, keyword_emp, keyword_false,
+        keyword_int, keyword_true, quote, unary_operator_neg, BinaryOperatorConcat,
+    },
+    types::primitive::{IntType, PrimitiveType},
+    whitespace::whitespace,
     Digit,
 };
 
 pub(crate) fn literal(i: &str, ptr: Pointer) -> Result<(&str, Pointer, Expression), ParseError> {
-    todo!()
+    spanned(
+        i,
+        ptr,
+        |i, ptr| {
+            numeric(i, ptr)
+                .map(|res| (res.0, res.1, Literal::Num(res.2)))
+                .or_next(|| boolean(i, ptr).map(|res| (res.0, res.1, Literal::Bln(res.2))))
+                .or_next(|| empty(i, ptr).map(|res| (res.0, res.1, Literal::Emp(res.2))))
+        },
+        ExpressionKind::Literal,
+    )
+    .or_next(|| string(i, ptr))
 }
 
+/// Unlike the other parsers, this doesn't skip any leading whitespace: `1 2` is two numbers, not
+/// `12`, and `- 1` is the negation of `1`, not the literal `-1`.
+pub(crate) fn digits(i: &str, ptr: Pointer) -> Result<(&str, Pointer, Vec<Digit>), ParseError> {
+    let end_location = i.find(|ch: char| !ch.is_ascii_digit()).unwrap_or(i.len());
+    if end_location == 0 {
+        return Err(ParseError::ExpectedDigit);
+    }
+    Ok((
+        &i[end_location..],
+        ptr.add_col(end_location),
+        i[..end_location].chars().map(Digit::from).collect(),
+    ))
+}
+
+/// A number can be followed directly by the name of an integer type or `byte`, like `255u8`, to
+/// make it a value of that type instead of a `num`. It has to be a whole number that fits in the
+/// type.
 fn numeric(i: &str, ptr: Pointer) -> Result<(&str, Pointer, Num), ParseError> {
-    let (i, ptr, sign) = optionally(i, ptr, &binary_operator_sub);
-    let (i, ptr, integer) = one_or_more(i, ptr, &digit)?;
-    if let Ok((i, ptr, _decimal_point)) = decimal(i, ptr) {
-        let (i, ptr, decimal) = one_or_more(i, ptr, &digit)?;
-        Ok((
-            i,
-            ptr,
-            Num {
-                positive: sign.is_some(),
-                integer,
-                decimal,
-            },
-        ))
+    let (i, ptr) = whitespace(i, ptr);
+    let (i, ptr, sign) = optionally(i, ptr, &unary_operator_neg);
+    let (i, ptr, integer) = digits(i, ptr)?;
+    let (i, ptr, decimal) = match decimal(i, ptr) {
+        Ok((i, ptr, _decimal_point)) => digits(i, ptr)?,
+        Err(_) => (i, ptr, vec![]),
+    };
+    let (i, ptr, suffix) = if i.starts_with(|ch: char| ch.is_ascii_alphabetic()) {
+        optionally(i, ptr, &|i, ptr| {
+            keyword_int(i, ptr).or_else(|_| keyword_byte(i, ptr))
+        })
     } else {
-        Ok((
-            i,
-            ptr,
-            Num {
-                positive: sign.is_some(),
-                integer,
-                decimal: vec![],
-            },
-        ))
+        (i, ptr, None)
+    };
+    let num = Num {
+        positive: sign.is_none(),
+        integer,
+        decimal,
+        suffix,
+    };
+    match &num.suffix {
+        Some(suffix) if !num.decimal.is_empty() || !fits(suffix, num.whole()) => {
+            Err(ParseError::InvalidIntegerLiteral)
+        }
+        _ => Ok((i, ptr, num)),
+    }
+}
+
+fn fits(suffix: &PrimitiveType, value: Option<i128>) -> bool {
+    match (suffix, value) {
+        (PrimitiveType::Int(int), Some(value)) => int.fits(value),
+        (PrimitiveType::Byte, Some(value)) => IntType::U8.fits(value),
+        _ => false,
     }
 }
 
 fn boolean(i: &str, ptr: Pointer) -> Result<(&str, Pointer, Bln), ParseError> {
     keyword_true(i, ptr)
         .map(|res| (res.0, res.1, Bln(true)))
-        .or(keyword_false(i, ptr).map(|res| (res.0, res.1, Bln(false))))
+        .or_else(|_| keyword_false(i, ptr).map(|res| (res.0, res.1, Bln(false))))
 }
 
 fn empty(i: &str, ptr: Pointer) -> Result<(&str, Pointer, Emp), ParseError> {
     keyword_emp(i, ptr).map(|res| (res.0, res.1, Emp(())))
 }
 
-fn string(i: &str, ptr: Pointer) -> Result<(&str, Pointer, Str), ParseError> {
-    let (i, ptr, quote_open) = quote(i, ptr)?;
-    // let found_string = i.chars().take_while(|ch| *ch != '"' || *ch != '\r' || *ch != '\n').collect::<String>();
-    let mut found_string = String::new();
-    while let Some(ch) = i.chars().next() {
-        match ch {
-            '"' => break,
-            a @ '\n' | a @ '\r' => return Err(ParseError::UnterminatedStringLiteral),
-            a => found_string.push(a),
+/// ```yaupl
+/// "plain"
+/// "interpolated {name}, {+ 1 2}"
+/// ```
+///
+/// Any expressions in `{ }` are joined onto the text around them with `++`, so the second
+/// example is `++ ++ ++ "interpolated " name ", " + 1 2`. `{{` and `}}` are a literal `{` and
+/// `}`. Each piece of text has the span it was written in, and each `++` spans from the start of
+/// the string to the end of what it joins on, or to the closing quote for the last one, so that no
+/// two expressions have the same span. The `""` in front of a string that starts with an
+/// interpolation has the span of the opening quote.
+fn string(i: &str, ptr: Pointer) -> Result<(&str, Pointer, Expression), ParseError> {
+    let (_, start) = whitespace(i, ptr);
+    let (mut i, mut ptr, _quote_open) = quote(i, ptr)?;
+    let mut parts = vec![];
+    let mut text = String::new();
+    let mut text_start = ptr;
+    loop {
+        if text.is_empty() {
+            text_start = ptr;
+        }
+        let mut chars = i.chars();
+        match (chars.next(), chars.next()) {
+            (Some('"'), _) => break,
+            (None, _) | (Some('\n'), _) | (Some('\r'), _) => {
+                return Err(ParseError::UnterminatedStringLiteral)
+            }
+            (Some('{'), Some('{')) | (Some('}'), Some('}')) => {
+                text.push(i.chars().next().unwrap());
+                i = &i[2..];
+                ptr = ptr.add_col(2);
+            }
+            (Some('}'), _) => return Err(ParseError::UnmatchedBrace),
+            (Some('{'), _) => {
+                let (rest, rest_ptr, _brace_open) = brace_curly_open(i, ptr)?;
+                let (rest, rest_ptr, embedded) = expression(rest, rest_ptr)
+                    .and_then(|(rest, rest_ptr, embedded)| {
+                        let (rest, rest_ptr, _brace_close) = brace_curly_close(rest, rest_ptr)?;
+                        Ok((rest, rest_ptr, embedded))
+                    })
+                    .map_err(|error| ParseError::InInterpolation {
+                        ptr,
+                        error: Box::new(error),
+                    })?;
+                if !text.is_empty() {
+                    let text = std::mem::take(&mut text);
+                    parts.push(string_literal(text, Span::new(text_start, ptr)));
+                }
+                parts.push(embedded);
+                i = rest;
+                ptr = rest_ptr;
+            }
+            (Some(ch), _) => {
+                text.push(ch);
+                i = &i[ch.len_utf8()..];
+                ptr = ptr.add_col(1);
+            }
         }
     }
-    let (i, ptr, quote_close) = quote(i, ptr)?;
+    let text_span = Span::new(text_start, ptr);
+    let (i, ptr, _quote_close) = quote(i, ptr)?;
+    let span = Span::new(start, ptr);
 
+    if parts.is_empty() {
+        return Ok((i, ptr, string_literal(text, span)));
+    }
+    if !text.is_empty() {
+        parts.push(string_literal(text, text_span));
+    }
+    // `"{a}"` is `++ "" a`, so that it's always a `str`
+    if !matches!(parts[0].kind, ExpressionKind::Literal(Literal::Str(_))) {
+        let quote_span = Span::new(start, start.add_col(1));
+        parts.insert(0, string_literal(String::new(), quote_span));
+    }
+    let last = parts.len() - 1;
+    let mut parts = parts.into_iter().enumerate();
+    let (_, first) = parts.next().unwrap();
     Ok((
-        &i[..found_string.len()],
-        ptr.add_col(found_string.len()),
-        Str(found_string),
+        i,
+        ptr,
+        parts.fold(first, |first, (ind, second)| {
+            let end = if ind == last { ptr } else { second.span.end };
+            concat(first, second, Span::new(start, end))
+        }),
     ))
 }
 
+fn string_literal(text: String, span: Span) -> Expression {
+    Expression::new(ExpressionKind::Literal(Literal::Str(Str(text))), span)
+}
+
+fn concat(first: Expression, second: Expression, span: Span) -> Expression {
+    Expression::new(
+        ExpressionKind::BinaryOperation(BinaryOperation {
+            operand: BinaryOperator::Concat(BinaryOperatorConcat),
+            first: Box::new(first),
+            second: Box::new(second),
+        }),
+        span,
+    )
+}
+
+#[derive(Debug, Clone, PartialEq)]
 pub enum Literal {
     Bln(Bln),
     Str(Str),
@@ -80,16 +206,159 @@ pub enum Literal {
     Emp(Emp),
 }
 
-// TODO: Implement `Str` and `Num` properly
-
-pub struct Bln(bool);
+#[derive(Debug, Clone, PartialEq)]
+pub struct Bln(pub bool);
 
-pub struct Str(String);
+#[derive(Debug, Clone, PartialEq)]
+pub struct Str(pub String);
 
+#[derive(Debug, Clone, PartialEq)]
 pub struct Num {
     positive: bool,
     integer: Vec<Digit>,
     decimal: Vec<Digit>,
+    /// The type of the literal, if it isn't `num`.
+    pub suffix: Option<PrimitiveType>,
 }
 
+impl Num {
+    /// The value of the integer part, if it fits in an `i128`.
+    pub(crate) fn whole(&self) -> Option<i128> {
+        let integral = self.integer.iter().try_fold(0i128, |acc, digit| {
+            acc.checked_mul(10)?.checked_add(*digit as i128)
+        })?;
+        Some(if self.positive { integral } else { -integral })
+    }
+
+    /// Converts the digits into a [`Number`], saturating to the infinities if the integral part
+    /// doesn't fit. Anything past the last representable decimal place is truncated.
+    pub(crate) fn value(&self) -> Number {
+        let integral = self.integer.iter().try_fold(0i128, |acc, digit| {
+            acc.checked_mul(10)?.checked_add(*digit as i128)
+        });
+        let number = match integral {
+            Some(integral) => Number::from_parts(
+                integral,
+                self.decimal
+                    .iter()
+                    .take(FRACTIONAL_DIGITS)
+                    .enumerate()
+                    .map(|(ind, digit)| {
+                        *digit as u128 * 10u128.pow((FRACTIONAL_DIGITS - ind - 1) as u32)
+                    })
+                    .sum(),
+            ),
+            None => Number::Infinity,
+        };
+        if self.positive {
+            number
+        } else {
+            -number
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
 pub struct Emp(());
+
+#[cfg(test)]
+mod test_literal {
+    use crate::{
+        pointer::{without_spans, Span},
+        Identifier,
+    };
+
+    use super::*;
+
+    #[test]
+    fn test_numeric() {
+        let (i, _, num) = numeric("  -12.5 3", Pointer::new(0, 0)).unwrap();
+        assert_eq!(i, " 3");
+        assert_eq!(
+            num.value(),
+            Number::from_parts(-13, 5 * 10u128.pow(FRACTIONAL_DIGITS as u32 - 1))
+        );
+        // a separated minus sign is negation, not part of the literal
+        assert!(numeric("- 12", Pointer::new(0, 0)).is_err());
+    }
+
+    #[test]
+    fn test_suffix() {
+        let (i, _, num) = numeric("-128i8 3", Pointer::new(0, 0)).unwrap();
+        assert_eq!(i, " 3");
+        assert_eq!(num.suffix, Some(PrimitiveType::Int(IntType::I8)));
+        assert_eq!(num.whole(), Some(-128));
+        let (_, _, num) = numeric("255byte", Pointer::new(0, 0)).unwrap();
+        assert_eq!(num.suffix, Some(PrimitiveType::Byte));
+        assert!(matches!(
+            numeric("256u8", Pointer::new(0, 0)),
+            Err(ParseError::InvalidIntegerLiteral)
+        ));
+        assert!(matches!(
+            numeric("1.5u32", Pointer::new(0, 0)),
+            Err(ParseError::InvalidIntegerLiteral)
+        ));
+    }
+
+    #[test]
+    fn test_string() {
+        let (i, ptr, found) = string(r#" "hello there" rest"#, Pointer::new(0, 0)).unwrap();
+        assert_eq!(i, " rest");
+        assert_eq!(ptr, Pointer::new(0, 14));
+        assert_eq!(
+            found,
+            string_literal(
+                "hello there".into(),
+                Span::new(Pointer::new(0, 1), Pointer::new(0, 14))
+            )
+        );
+        assert!(matches!(
+            string(r#""hello"#, Pointer::new(0, 0)),
+            Err(ParseError::UnterminatedStringLiteral)
+        ));
+        assert!(matches!(
+            string(r#""a } b""#, Pointer::new(0, 0)),
+            Err(ParseError::UnmatchedBrace)
+        ));
+    }
+
+    #[test]
+    fn test_interpolation() {
+        let (i, ptr, found) = string(r#""{{a}} {b}!" rest"#, Pointer::new(0, 0)).unwrap();
+        assert_eq!(i, " rest");
+        assert_eq!(ptr, Pointer::new(0, 12));
+        let identifier =
+            |span| Expression::new(ExpressionKind::Identifier(Identifier("b".into())), span);
+        let anywhere = Span::default();
+        assert_eq!(
+            without_spans(&found),
+            without_spans(&concat(
+                concat(
+                    string_literal("{a} ".into(), anywhere),
+                    identifier(anywhere),
+                    anywhere
+                ),
+                string_literal("!".into(), anywhere),
+                anywhere
+            ))
+        );
+        // every `++` has a different span, so that each expression can be told apart by it's span
+        match &found.kind {
+            ExpressionKind::BinaryOperation(operation) => assert_eq!(
+                (found.span.end, operation.first.span.end),
+                (Pointer::new(0, 12), Pointer::new(0, 9))
+            ),
+            _ => unreachable!(),
+        }
+        let (_, _, found) = string(r#""{b}""#, Pointer::new(0, 0)).unwrap();
+        let whole = Span::new(Pointer::new(0, 0), Pointer::new(0, 5));
+        assert_eq!(
+            found,
+            concat(
+                string_literal("".into(), Span::new(Pointer::new(0, 0), Pointer::new(0, 1))),
+                identifier(Span::new(Pointer::new(0, 2), Pointer::new(0, 3))),
+                whole
+            )
+        );
+    }
+}
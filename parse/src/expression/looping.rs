@@ -0,0 +1,55 @@
+use crate::{
+    expression::{expression, Expression, ExpressionKind},
+    parse_error::ParseError,
+    pattern::{pattern, Pattern},
+    pointer::Pointer,
+    tokens::{
+        arrow_left, brace_square_close, brace_square_open, comma, keyword_break, keyword_continue,
+        keyword_for,
+    },
+};
+
+/// ```yaupl
+/// for[[element <- group, body]]
+/// ```
+///
+/// Evaluates `body` once for each element of the group, with the element bound to the pattern,
+/// and collects the results into a new group. `continue` leaves that element's result out, and
+/// `break` stops the loop with the results collected so far.
+pub(crate) fn for_loop(i: &str, ptr: Pointer) -> Result<(&str, Pointer, For), ParseError> {
+    let (i, ptr, _for) = keyword_for(i, ptr)?;
+    let (i, ptr, _bracket_open) = brace_square_open(i, ptr)?;
+    let (i, ptr, _bracket_open) = brace_square_open(i, ptr)?;
+    let (i, ptr, found) = pattern(i, ptr)?;
+    let (i, ptr, _arrow) = arrow_left(i, ptr)?;
+    let (i, ptr, group) = expression(i, ptr)?;
+    let (i, ptr, _comma) = comma(i, ptr)?;
+    let (i, ptr, body) = expression(i, ptr)?;
+    let (i, ptr, _bracket_close) = brace_square_close(i, ptr)?;
+    let (i, ptr, _bracket_close) = brace_square_close(i, ptr)?;
+    Ok((
+        i,
+        ptr,
+        For {
+            pattern: found,
+            group: Box::new(group),
+            body: Box::new(body),
+        },
+    ))
+}
+
+pub(crate) fn break_expression(
+    i: &str,
+    ptr: Pointer,
+) -> Result<(&str, Pointer, ExpressionKind), ParseError> {
+    keyword_break(i, ptr)
+        .map(|res| (res.0, res.1, ExpressionKind::Break))
+        .or_else(|_| keyword_continue(i, ptr).map(|res| (res.0, res.1, ExpressionKind::Continue)))
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct For {
+    pub pattern: Pattern,
+    pub group: Box<Expression>,
+    pub body: Box<Expression>,
+}
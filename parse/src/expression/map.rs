@@ -0,0 +1,72 @@
+use crate::{
+    combinators::csv,
+    expression::{expression, Expression},
+    parse_error::ParseError,
+    pointer::Pointer,
+    tokens::{
+        brace_square_close, brace_square_open, colon, comma, keyword_insert, map_close, map_open,
+    },
+};
+
+/// ```yaupl
+/// {| "one": 1, "two": 2 |}
+/// ```
+///
+/// Every key has to have the same type, `K`, and every value the same type, `V`, and the map is
+/// then a `map<K, V>`. Like an empty group, the types of an empty map have to come from
+/// somewhere else. If a key is given twice, the last value for it is the one that's kept.
+pub(crate) fn map_literal(
+    i: &str,
+    ptr: Pointer,
+) -> Result<(&str, Pointer, MapLiteral), ParseError> {
+    let (i, ptr, _bracket) = map_open(i, ptr)?;
+    let (i, ptr, entries) = csv(i, ptr, &entry)?;
+    let (i, ptr, _bracket) = map_close(i, ptr)?;
+    Ok((i, ptr, MapLiteral(entries)))
+}
+
+fn entry(i: &str, ptr: Pointer) -> Result<(&str, Pointer, (Expression, Expression)), ParseError> {
+    let (i, ptr, key) = expression(i, ptr)?;
+    let (i, ptr, _colon) = colon(i, ptr)?;
+    let (i, ptr, value) = expression(i, ptr)?;
+    Ok((i, ptr, (key, value)))
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct MapLiteral(pub Vec<(Expression, Expression)>);
+
+/// ```yaupl
+/// insert[[ages, "ann", 31]]
+/// ```
+///
+/// A copy of the map with the key set to the value, replacing the value that was there if there
+/// was one. Values are looked up by indexing, `ages["ann"]`, which gives a `V?` since the key
+/// might not be there.
+pub(crate) fn insert(i: &str, ptr: Pointer) -> Result<(&str, Pointer, Insert), ParseError> {
+    let (i, ptr, _insert) = keyword_insert(i, ptr)?;
+    let (i, ptr, _bracket_open) = brace_square_open(i, ptr)?;
+    let (i, ptr, _bracket_open) = brace_square_open(i, ptr)?;
+    let (i, ptr, map) = expression(i, ptr)?;
+    let (i, ptr, _comma) = comma(i, ptr)?;
+    let (i, ptr, key) = expression(i, ptr)?;
+    let (i, ptr, _comma) = comma(i, ptr)?;
+    let (i, ptr, value) = expression(i, ptr)?;
+    let (i, ptr, _bracket_close) = brace_square_close(i, ptr)?;
+    let (i, ptr, _bracket_close) = brace_square_close(i, ptr)?;
+    Ok((
+        i,
+        ptr,
+        Insert {
+            map: Box::new(map),
+            key: Box::new(key),
+            value: Box::new(value),
+        },
+    ))
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Insert {
+    pub map: Box<Expression>,
+    pub key: Box<Expression>,
+    pub value: Box<Expression>,
+}
@@ -0,0 +1,46 @@
+use crate::{
+    combinators::csv,
+    expression::{expression, Expression},
+    parse_error::ParseError,
+    pattern::{pattern, Pattern},
+    pointer::Pointer,
+    tokens::{arrow_right, brace_square_close, brace_square_open, comma, keyword_match},
+};
+
+/// ```yaupl
+/// match[[value, pattern -> expression, pattern -> expression]]
+/// ```
+///
+/// The arms are tried in order, and the value of the first one that matches is the value of the
+/// whole `match`. Any names bound by the pattern are only in scope in that arm.
+pub(crate) fn matching(i: &str, ptr: Pointer) -> Result<(&str, Pointer, Match), ParseError> {
+    let (i, ptr, _match) = keyword_match(i, ptr)?;
+    let (i, ptr, _bracket_open) = brace_square_open(i, ptr)?;
+    let (i, ptr, _bracket_open) = brace_square_open(i, ptr)?;
+    let (i, ptr, expr) = expression(i, ptr)?;
+    let (i, ptr, _comma) = comma(i, ptr)?;
+    let (i, ptr, arms) = csv(i, ptr, &arm)?;
+    let (i, ptr, _bracket_close) = brace_square_close(i, ptr)?;
+    let (i, ptr, _bracket_close) = brace_square_close(i, ptr)?;
+    Ok((
+        i,
+        ptr,
+        Match {
+            expr: Box::new(expr),
+            arms,
+        },
+    ))
+}
+
+fn arm(i: &str, ptr: Pointer) -> Result<(&str, Pointer, (Pattern, Expression)), ParseError> {
+    let (i, ptr, found) = pattern(i, ptr)?;
+    let (i, ptr, _arrow) = arrow_right(i, ptr)?;
+    let (i, ptr, body) = expression(i, ptr)?;
+    Ok((i, ptr, (found, body)))
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Match {
+    pub expr: Box<Expression>,
+    pub arms: Vec<(Pattern, Expression)>,
+}
@@ -1,24 +1,163 @@
 use crate::{
-    expression::{binary_operations::binary_operation, literal::literal, function_call::function_call},
+    combinators::OrNext,
     expression::{
-        binary_operations::BinaryOperation, function_call::FunctionCall, literal::Literal,
+        access::postfix,
+        binary_operations::binary_operation,
+        block::{block, return_expression},
+        complex::complex_literal,
+        conditional::conditional,
+        conversion::conversion,
+        group::group_literal,
+        lambda::lambda,
+        literal::literal,
+        looping::{break_expression, for_loop},
+        map::{insert, map_literal},
+        matching::matching,
+        pipeline::pipeline,
+        tuple::tuple_literal,
+        unary_operations::unary_operation,
+        variant::{is_expression, variant_literal},
     },
+    expression::{
+        access::{FieldAccess, Index, Projection, Slice},
+        binary_operations::BinaryOperation,
+        block::{Block, Return},
+        complex::ComplexLiteral,
+        conditional::Conditional,
+        conversion::Conversion,
+        function_call::FunctionCall,
+        group::GroupLiteral,
+        lambda::Lambda,
+        literal::Literal,
+        looping::For,
+        map::{Insert, MapLiteral},
+        matching::Match,
+        pipeline::Pipeline,
+        tuple::TupleLiteral,
+        unary_operations::UnaryOperation,
+        variant::{Is, VariantLiteral},
+    },
+    ident,
     parse_error::ParseError,
-    pointer::Pointer,
+    pointer::{Pointer, Span},
+    whitespace::whitespace,
+    Identifier,
 };
 
+pub(crate) mod access;
 pub(crate) mod binary_operations;
+pub(crate) mod block;
+pub(crate) mod complex;
+pub(crate) mod conditional;
+pub(crate) mod conversion;
 pub(crate) mod function_call;
+pub(crate) mod group;
+pub(crate) mod lambda;
 pub(crate) mod literal;
+pub(crate) mod looping;
+pub(crate) mod map;
+pub(crate) mod matching;
+pub(crate) mod pipeline;
+pub(crate) mod tuple;
+pub(crate) mod unary_operations;
+pub(crate) mod variant;
+
+/// An expression, and where it was written.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Expression {
+    pub(crate) kind: ExpressionKind,
+    pub(crate) span: Span,
+}
+
+impl Expression {
+    pub(crate) fn new(kind: ExpressionKind, span: Span) -> Self {
+        Self { kind, span }
+    }
+
+    pub fn kind(&self) -> &ExpressionKind {
+        &self.kind
+    }
 
-pub enum Expression {
+    pub fn span(&self) -> Span {
+        self.span
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ExpressionKind {
     BinaryOperation(BinaryOperation),
+    UnaryOperation(UnaryOperation),
     FunctionCall(FunctionCall),
+    Lambda(Lambda),
     Literal(Literal),
+    Tuple(TupleLiteral),
+    Complex(ComplexLiteral),
+    Group(GroupLiteral),
+    Map(MapLiteral),
+    /// Sets a key of a map.
+    Insert(Insert),
+    Variant(VariantLiteral),
+    /// Checks which variant a union value is.
+    Is(Is),
+    /// A reference to a binding.
+    Identifier(Identifier),
+    Conditional(Conditional),
+    /// Converts between the number types.
+    Conversion(Conversion),
+    Match(Match),
+    Pipeline(Pipeline),
+    Block(Block),
+    Return(Return),
+    For(For),
+    /// Stops the innermost loop.
+    Break,
+    /// Skips to the next element of the innermost loop.
+    Continue,
+    FieldAccess(FieldAccess),
+    Projection(Projection),
+    Index(Index),
+    Slice(Slice),
 }
 
 pub(crate) fn expression(i: &str, ptr: Pointer) -> Result<(&str, Pointer, Expression), ParseError> {
+    primary(i, ptr)
+        .and_then(|res| postfix(res.0, res.1, res.2))
+        .map(|res| pipeline(res.0, res.1, res.2))
+        .or_next(|| spanned(i, ptr, return_expression, ExpressionKind::Return))
+        .or_next(|| spanned(i, ptr, break_expression, |kind| kind))
+        .or_next(|| spanned(i, ptr, binary_operation, ExpressionKind::BinaryOperation))
+        .or_next(|| spanned(i, ptr, unary_operation, ExpressionKind::UnaryOperation))
+}
+
+/// Everything that can have a field access, index, etc. directly after it.
+pub(crate) fn primary(i: &str, ptr: Pointer) -> Result<(&str, Pointer, Expression), ParseError> {
+    // literals first, so that `-1` is a number and not the negation of one
     literal(i, ptr)
-        .or(binary_operation(i, ptr))
-        .or(function_call(i, ptr).map(|res| (res.0, res.1, Expression::FunctionCall(res.2))))
+        .or_next(|| spanned(i, ptr, tuple_literal, ExpressionKind::Tuple))
+        .or_next(|| spanned(i, ptr, complex_literal, ExpressionKind::Complex))
+        .or_next(|| spanned(i, ptr, group_literal, ExpressionKind::Group))
+        .or_next(|| spanned(i, ptr, map_literal, ExpressionKind::Map))
+        .or_next(|| spanned(i, ptr, insert, ExpressionKind::Insert))
+        .or_next(|| spanned(i, ptr, variant_literal, ExpressionKind::Variant))
+        .or_next(|| spanned(i, ptr, is_expression, ExpressionKind::Is))
+        .or_next(|| spanned(i, ptr, matching, ExpressionKind::Match))
+        .or_next(|| spanned(i, ptr, for_loop, ExpressionKind::For))
+        .or_next(|| spanned(i, ptr, block, ExpressionKind::Block))
+        .or_next(|| spanned(i, ptr, conditional, ExpressionKind::Conditional))
+        .or_next(|| spanned(i, ptr, conversion, ExpressionKind::Conversion))
+        .or_next(|| spanned(i, ptr, lambda, ExpressionKind::Lambda))
+        .or_next(|| spanned(i, ptr, ident, ExpressionKind::Identifier))
+}
+
+/// Parses something with `parser` and turns it into an expression with `kind`, which spans from
+/// after any whitespace before it to the end of what was parsed.
+pub(crate) fn spanned<'a, T>(
+    i: &'a str,
+    ptr: Pointer,
+    parser: impl Fn(&'a str, Pointer) -> Result<(&'a str, Pointer, T), ParseError>,
+    kind: impl Fn(T) -> ExpressionKind,
+) -> Result<(&'a str, Pointer, Expression), ParseError> {
+    let (_, start) = whitespace(i, ptr);
+    let (i, end, found) = parser(i, ptr)?;
+    Ok((i, end, Expression::new(kind(found), Span::new(start, end))))
 }
@@ -0,0 +1,65 @@
+use crate::{
+    expression::{
+        access::postfix, function_call::FunctionCall, primary, Expression, ExpressionKind,
+    },
+    parse_error::ParseError,
+    pointer::{Pointer, Span},
+    tokens::arrow_right_curly,
+    whitespace::whitespace,
+};
+
+/// Parses any pipeline stages after `expr`, so `x ~> f ~> g` is `g[[f[[x]]]]`.
+///
+/// ```yaupl
+/// x ~> f
+/// x ~> f[[extra, args]]
+/// ```
+///
+/// The piped value is passed as the first argument, before any that are written out. Like the
+/// other suffixes, `~>` binds as tightly as it can, so `- x ~> f` is `- f[[x]]`.
+pub(crate) fn pipeline(i: &str, ptr: Pointer, expr: Expression) -> (&str, Pointer, Expression) {
+    let (mut i, mut ptr, mut stages) = (i, ptr, vec![]);
+    while let Ok((new_i, new_ptr, found)) = stage(i, ptr) {
+        i = new_i;
+        ptr = new_ptr;
+        stages.push(found);
+    }
+    if stages.is_empty() {
+        (i, ptr, expr)
+    } else {
+        let span = Span::new(expr.span.start, ptr);
+        let kind = ExpressionKind::Pipeline(Pipeline {
+            expr: Box::new(expr),
+            stages,
+        });
+        (i, ptr, Expression::new(kind, span))
+    }
+}
+
+fn stage(i: &str, ptr: Pointer) -> Result<(&str, Pointer, Stage), ParseError> {
+    let (i, ptr, _arrow) = arrow_right_curly(i, ptr)?;
+    let (i, ptr) = whitespace(i, ptr);
+    let (i, end_ptr, found) = primary(i, ptr).and_then(|res| postfix(res.0, res.1, res.2))?;
+    let call = match found.kind {
+        ExpressionKind::FunctionCall(call) => call,
+        _ => FunctionCall {
+            callee: Box::new(found),
+            args: vec![],
+        },
+    };
+    Ok((i, end_ptr, Stage { ptr, call }))
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Pipeline {
+    pub expr: Box<Expression>,
+    pub stages: Vec<Stage>,
+}
+
+/// A call with the piped value missing from the start of it's arguments.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Stage {
+    /// Where the stage starts, so that errors can point at the stage they happened in.
+    pub ptr: Pointer,
+    pub call: FunctionCall,
+}
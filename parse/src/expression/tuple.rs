@@ -0,0 +1,30 @@
+use crate::{
+    combinators::csv,
+    expression::{expression, Expression},
+    parse_error::ParseError,
+    pointer::Pointer,
+    tokens::{tuple_close, tuple_open},
+};
+
+/// ```yaupl
+/// [| 1, "one", true |]
+/// ```
+///
+/// The type of a tuple literal is the tuple of the types of it's elements, `[|num, str, bln|]`
+/// for the example above.
+pub(crate) fn tuple_literal(
+    i: &str,
+    ptr: Pointer,
+) -> Result<(&str, Pointer, TupleLiteral), ParseError> {
+    let (i, ptr, _bracket) = tuple_open(i, ptr)?;
+    let (i, ptr, elements) = csv(i, ptr, &expression)?;
+    let (i, ptr, _bracket) = tuple_close(i, ptr)?;
+    // the same as the tuple type, the empty tuple is forbidden
+    if elements.is_empty() {
+        return Err(ParseError::EmptyTuple);
+    }
+    Ok((i, ptr, TupleLiteral(elements)))
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct TupleLiteral(pub Vec<Expression>);
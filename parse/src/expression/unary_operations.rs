@@ -0,0 +1,61 @@
+use crate::{
+    expression::expression,
+    expression::Expression,
+    parse_error::ParseError,
+    pointer::Pointer,
+    tokens::{unary_operator_neg, unary_operator_not, UnaryOperatorNeg, UnaryOperatorNot},
+};
+
+/// Unary operators are prefix operators just like the binary ones, so `- x y` is subtraction
+/// and `- x` is negation; the binary operation is always tried first.
+pub(crate) fn unary_operation(
+    i: &str,
+    ptr: Pointer,
+) -> Result<(&str, Pointer, UnaryOperation), ParseError> {
+    let (i, ptr, operand) = unary_operator(i, ptr)?;
+    let (i, ptr, expr) = expression(i, ptr)?;
+    Ok((
+        i,
+        ptr,
+        UnaryOperation {
+            operand,
+            expr: Box::new(expr),
+        },
+    ))
+}
+
+fn unary_operator(i: &str, ptr: Pointer) -> Result<(&str, Pointer, UnaryOperator), ParseError> {
+    unary_operator_neg(i, ptr)
+        .map(op_to_op_enum)
+        .or_else(|_| unary_operator_not(i, ptr).map(op_to_op_enum))
+}
+
+fn op_to_op_enum(res: (&str, Pointer, impl Into<UnaryOperator>)) -> (&str, Pointer, UnaryOperator) {
+    (res.0, res.1, res.2.into())
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct UnaryOperation {
+    pub operand: UnaryOperator,
+    pub expr: Box<Expression>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum UnaryOperator {
+    /// `- num`
+    Neg(UnaryOperatorNeg),
+    /// `! bln`
+    Not(UnaryOperatorNot),
+}
+
+impl From<UnaryOperatorNeg> for UnaryOperator {
+    fn from(op: UnaryOperatorNeg) -> Self {
+        UnaryOperator::Neg(op)
+    }
+}
+
+impl From<UnaryOperatorNot> for UnaryOperator {
+    fn from(op: UnaryOperatorNot) -> Self {
+        UnaryOperator::Not(op)
+    }
+}
@@ -0,0 +1,79 @@
+use crate::{
+    combinators::optionally,
+    expression::{expression, Expression},
+    ident,
+    parse_error::ParseError,
+    pointer::Pointer,
+    tokens::{
+        brace_square_close, brace_square_open, colon, comma, keyword_is, variant_close,
+        variant_open,
+    },
+    Identifier,
+};
+
+/// ```yaupl
+/// <| ok: 1 |>
+/// <| none |>
+/// ```
+///
+/// Makes a value of a union type. On it's own it's type is the union with just the one variant,
+/// `<| ok: num |>` for the first example above, which can be used wherever a union with that
+/// variant is expected.
+pub(crate) fn variant_literal(
+    i: &str,
+    ptr: Pointer,
+) -> Result<(&str, Pointer, VariantLiteral), ParseError> {
+    let (i, ptr, _bracket) = variant_open(i, ptr)?;
+    let (i, ptr, name) = ident(i, ptr)?;
+    let (i, ptr, value) = optionally(i, ptr, &|i, ptr| {
+        let (i, ptr, _colon) = colon(i, ptr)?;
+        expression(i, ptr)
+    });
+    let (i, ptr, _bracket) = variant_close(i, ptr)?;
+    Ok((
+        i,
+        ptr,
+        VariantLiteral {
+            name,
+            value: value.map(Box::new),
+        },
+    ))
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct VariantLiteral {
+    pub name: Identifier,
+    /// `None` for a variant that doesn't carry anything, which carries `emp`.
+    pub value: Option<Box<Expression>>,
+}
+
+/// ```yaupl
+/// is[[result, ok]]
+/// ```
+///
+/// Whether a union value is the named variant. If `result` is a name, then inside the branches of
+/// an `if` on this it's known to be `ok`, or to not be `ok`.
+pub(crate) fn is_expression(i: &str, ptr: Pointer) -> Result<(&str, Pointer, Is), ParseError> {
+    let (i, ptr, _is) = keyword_is(i, ptr)?;
+    let (i, ptr, _bracket_open) = brace_square_open(i, ptr)?;
+    let (i, ptr, _bracket_open) = brace_square_open(i, ptr)?;
+    let (i, ptr, expr) = expression(i, ptr)?;
+    let (i, ptr, _comma) = comma(i, ptr)?;
+    let (i, ptr, variant) = ident(i, ptr)?;
+    let (i, ptr, _bracket_close) = brace_square_close(i, ptr)?;
+    let (i, ptr, _bracket_close) = brace_square_close(i, ptr)?;
+    Ok((
+        i,
+        ptr,
+        Is {
+            expr: Box::new(expr),
+            variant,
+        },
+    ))
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Is {
+    pub expr: Box<Expression>,
+    pub variant: Identifier,
+}
@@ -1,12 +1,58 @@
 #![feature(or_patterns, trait_alias, associated_type_bounds)]
 use std::fmt::Debug;
 
-use crate::types::{yaupl_type, Type};
+use crate::types::{diff::diff, yaupl_type};
+
+pub use self::{
+    check::typed::Typed,
+    eval::Value,
+    expression::{
+        access::{FieldAccess, Index, Projection, Slice},
+        binary_operations::{BinaryOperation, BinaryOperator},
+        block::{Block, Return},
+        complex::ComplexLiteral,
+        conditional::Conditional,
+        conversion::Conversion,
+        function_call::FunctionCall,
+        group::GroupLiteral,
+        lambda::Lambda,
+        literal::{Bln, Emp, Literal, Num, Str},
+        looping::For,
+        map::{Insert, MapLiteral},
+        matching::Match,
+        pipeline::{Pipeline, Stage},
+        tuple::TupleLiteral,
+        unary_operations::{UnaryOperation, UnaryOperator},
+        variant::{Is, VariantLiteral},
+        Expression, ExpressionKind,
+    },
+    module::{Loader, ModuleError},
+    pattern::{GroupPattern, Pattern},
+    pointer::{Pointer, Span},
+    program::Program,
+    resolve::{Definition, DefinitionId, DefinitionKind, Resolution, ResolveError, ResolveWarning},
+    statement::{binding::Binding, type_declaration::TypeDeclaration, with::With, Statement},
+    types::{
+        complex::Complex,
+        diff::{Difference, Step, TypeDiff},
+        function::Function,
+        interner::{TypeId, TypeInterner},
+        map::Map,
+        operator::Operator,
+        primitive::{IntType, PrimitiveType},
+        tuple::Tuple,
+        union::Union,
+        Group, Named, Type,
+    },
+};
 
 use self::{
+    check::{check_program, declared_types, typed::check_typed, TypeError},
     combinators::{not, one_of},
+    eval::{eval_program, EvalError},
     parse_error::ParseError,
-    pointer::Pointer,
+    program::program,
+    resolve::resolve_program,
     tokens::token::Token,
     tokens::*,
     whitespace::whitespace,
@@ -15,18 +61,63 @@ use self::{
 pub fn parse(i: &str) -> Result<(&str, Pointer, Type), ParseError> {
     yaupl_type(i, Pointer::new(0, 0))
 }
+
+pub fn parse_program(i: &str) -> Result<Program, ParseError> {
+    program(i, Pointer::new(0, 0)).map(|res| res.2)
+}
+
+/// Finds what every name in the program refers to, along with any names that aren't defined,
+/// are defined twice in the same place, or are never used.
+pub fn resolve(program: &Program) -> Resolution {
+    resolve_program(program)
+}
+
+/// Type checks the program, returning the type of it's last statement.
+pub fn check(program: &Program) -> Result<Type, TypeError> {
+    check_program(program)
+}
+
+/// Type checks the whole program, finding the type of every expression in it along with every
+/// type error, rather than stopping at the first one.
+///
+/// Like [`check`], this doesn't load anything the program imports with `with`, use
+/// [`Loader::check_all`] for programs that import other modules.
+pub fn check_all(program: &Program) -> Typed<'_> {
+    check_typed(program, &[])
+}
+
+/// Finds the parts of `found` that aren't the same as `expected`, which can be written out as one
+/// line for each of them. Any named types are unfolded with the types the program declares.
+pub fn diff_types(program: &Program, expected: &Type, found: &Type) -> TypeDiff {
+    diff(expected, found, &mut declared_types(program, &[]))
+}
+
+/// Runs the program, returning the value of it's last statement.
+///
+/// The program should be [`check`]ed first.
+pub fn eval(program: &Program) -> Result<Value, EvalError> {
+    eval_program(program)
+}
 // use crate::ast::defs::{types::*, *};
 
+pub(crate) mod check;
 pub(crate) mod combinators;
+pub(crate) mod eval;
 pub(crate) mod expression;
+pub(crate) mod module;
 pub(crate) mod parse_error;
+pub(crate) mod pattern;
 pub(crate) mod pointer;
+pub(crate) mod program;
+pub(crate) mod resolve;
+pub(crate) mod scope;
+pub(crate) mod statement;
 pub(crate) mod tokens;
 pub(crate) mod types;
 pub(crate) mod utils;
 pub(crate) mod whitespace;
 
-#[derive(Debug, Ord, PartialOrd, Eq, PartialEq)]
+#[derive(Debug, Ord, PartialOrd, Eq, PartialEq, Copy, Clone)]
 pub(crate) enum Digit {
     Zero,
     One,
@@ -97,8 +188,26 @@ pub(crate) fn digit(i: &str, ptr: Pointer) -> Result<(&str, Pointer, Digit), Par
     }
 }
 
-#[derive(Debug, Ord, PartialOrd, Eq, PartialEq)]
+#[derive(Debug, Ord, PartialOrd, Eq, PartialEq, Clone, Hash)]
 pub struct Identifier(String);
+impl Identifier {
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+impl From<&str> for Identifier {
+    fn from(name: &str) -> Self {
+        Identifier(name.into())
+    }
+}
+
+impl From<String> for Identifier {
+    fn from(name: String) -> Self {
+        Identifier(name)
+    }
+}
+
 impl Token for Identifier {
     fn token(&self) -> &str {
         &*self.0
@@ -106,29 +215,18 @@ impl Token for Identifier {
 }
 fn ident(i: &str, ptr: Pointer) -> Result<(&str, Pointer, Identifier), ParseError> {
     let (i, ptr) = whitespace(i, ptr);
-    let mut ch_inds = i.char_indices();
-    let mut prev_ind = 0;
-    let end_location = loop {
-        match ch_inds.next() {
-            Some((ind, 'a'..='z' | 'A'..='Z' | '_')) => {
-                prev_ind = ind;
-                continue;
-            }
-            Some((ind, _)) => break ind,
-            None => {
-                if prev_ind > 0 {
-                    break prev_ind;
-                } else {
-                    return Err(ParseError::None);
-                }
-            }
-        };
-    };
-
-    not((&i[end_location..]).clone(), ptr.clone(), &infinity)?;
+    let end_location = i
+        .find(|ch: char| !(ch.is_ascii_alphabetic() || ch == '_'))
+        .unwrap_or(i.len());
+    // an identifier needs at least one letter, `___` on it's own is the empty type
+    if !i[..end_location].contains(|ch: char| ch.is_ascii_alphabetic()) {
+        return Err(ParseError::None);
+    }
+
+    not((&i[..end_location]).clone(), ptr.clone(), &infinity)?;
     // this one's not technically possible, but it's here for posterity
     not(
-        (&i[end_location..]).clone(),
+        (&i[..end_location]).clone(),
         ptr.clone(),
         &negative_infinity,
     )?;
@@ -138,10 +236,21 @@ fn ident(i: &str, ptr: Pointer) -> Result<(&str, Pointer, Identifier), ParseErro
     not((&i[..end_location]).clone(), ptr.clone(), &keyword_return)?;
     not((&i[..end_location]).clone(), ptr.clone(), &keyword_with)?;
     not((&i[..end_location]).clone(), ptr.clone(), &keyword_as)?;
+    not((&i[..end_location]).clone(), ptr.clone(), &keyword_type)?;
+    not((&i[..end_location]).clone(), ptr.clone(), &keyword_if)?;
+    not((&i[..end_location]).clone(), ptr.clone(), &keyword_match)?;
+    not((&i[..end_location]).clone(), ptr.clone(), &keyword_for)?;
+    not((&i[..end_location]).clone(), ptr.clone(), &keyword_is)?;
+    not((&i[..end_location]).clone(), ptr.clone(), &keyword_break)?;
+    not((&i[..end_location]).clone(), ptr.clone(), &keyword_continue)?;
     not((&i[..end_location]).clone(), ptr.clone(), &keyword_str)?;
     not((&i[..end_location]).clone(), ptr.clone(), &keyword_num)?;
+    not((&i[..end_location]).clone(), ptr.clone(), &keyword_int)?;
+    not((&i[..end_location]).clone(), ptr.clone(), &keyword_byte)?;
+    not((&i[..end_location]).clone(), ptr.clone(), &keyword_insert)?;
     not((&i[..end_location]).clone(), ptr.clone(), &keyword_bln)?;
     not((&i[..end_location]).clone(), ptr.clone(), &keyword_emp)?;
+    not((&i[..end_location]).clone(), ptr.clone(), &keyword_nvr)?;
 
     Ok((
         &i[end_location..],
@@ -149,3 +258,10 @@ fn ident(i: &str, ptr: Pointer) -> Result<(&str, Pointer, Identifier), ParseErro
         Identifier(i[..end_location].into()),
     ))
 }
+
+/// An [`ident`], along with where it was written.
+fn spanned_ident(i: &str, ptr: Pointer) -> Result<(&str, Pointer, (Identifier, Span)), ParseError> {
+    let (i, start) = whitespace(i, ptr);
+    let (i, end, name) = ident(i, start)?;
+    Ok((i, end, (name, Span::new(start, end))))
+}
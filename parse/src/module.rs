@@ -0,0 +1,720 @@
+use std::{
+    collections::HashMap,
+    fmt, fs, io,
+    path::{Path, PathBuf},
+};
+
+use crate::{
+    check::{check_module, declared_types, typed::check_typed, Interface, TypeError},
+    eval::{eval_module, EvalError, Value},
+    parse_error::ParseError,
+    pointer::Span,
+    program::Program,
+    resolve::{resolve_program, ResolveError, ResolveWarning},
+    statement::Statement,
+    types::{
+        diff::{diff, TypeDiff},
+        Type,
+    },
+    Identifier, Typed,
+};
+
+/// Loads a module and everything it imports with `with`, parsing each file only once no matter
+/// how many modules import it.
+///
+/// A `with` path is looked for relative to the directory of the file that imports it first, and
+/// then relative to each of the search paths in the order they were added.
+#[derive(Debug, Default)]
+pub struct Loader {
+    search_paths: Vec<PathBuf>,
+    modules: HashMap<PathBuf, Module>,
+    /// The modules that are part way through being loaded, each one imported by the one before it.
+    loading: Vec<PathBuf>,
+}
+
+#[derive(Debug)]
+struct Module {
+    program: Program,
+    /// The alias and the resolved path of each import.
+    imports: Vec<(Identifier, PathBuf)>,
+}
+
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum ModuleError {
+    Io {
+        path: PathBuf,
+        error: io::Error,
+    },
+    Parse {
+        path: PathBuf,
+        error: ParseError,
+    },
+    /// A `with` path that wasn't in the importing module's directory or any of the search paths.
+    NotFound {
+        path: PathBuf,
+        searched: Vec<PathBuf>,
+    },
+    /// A module that imports itself, directly or through other modules. The first and last paths
+    /// are the same.
+    Cycle(Vec<PathBuf>),
+    /// Names that are used but not defined, or defined twice, which are all found before the
+    /// module is type checked.
+    Resolve {
+        path: PathBuf,
+        errors: Vec<ResolveError>,
+    },
+    /// `span` is where the expression the error is about is, or where the statement or type
+    /// declaration it's in is if it isn't about one expression.
+    ///
+    /// If the error is a mismatch, `diff` is the parts of the two types that are different,
+    /// found with the module's types in scope so that named types can be unfolded.
+    Type {
+        path: PathBuf,
+        span: Option<Span>,
+        error: Box<TypeError>,
+        diff: Option<TypeDiff>,
+    },
+    Eval {
+        path: PathBuf,
+        error: Box<EvalError>,
+    },
+}
+
+impl fmt::Display for ModuleError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ModuleError::Io { path, error } => write!(f, "{}: {}", path.display(), error),
+            ModuleError::Parse { path, error } => write!(f, "{}: {}", path.display(), error),
+            ModuleError::NotFound { path, searched } => {
+                write!(f, "module {} not found, looked in:", path.display())?;
+                for dir in searched {
+                    write!(f, "\n    {}", dir.display())?;
+                }
+                Ok(())
+            }
+            ModuleError::Cycle(cycle) => {
+                write!(f, "import cycle: ")?;
+                for (ind, path) in cycle.iter().enumerate() {
+                    if ind > 0 {
+                        write!(f, " -> ")?;
+                    }
+                    write!(f, "{}", path.display())?;
+                }
+                Ok(())
+            }
+            ModuleError::Resolve { path, errors } => {
+                for (ind, error) in errors.iter().enumerate() {
+                    if ind > 0 {
+                        writeln!(f)?;
+                    }
+                    write!(f, "{}:{}", path.display(), error)?;
+                }
+                Ok(())
+            }
+            ModuleError::Type {
+                path,
+                span,
+                error,
+                diff,
+            } => {
+                match span {
+                    Some(span) => write!(f, "{}:{}: ", path.display(), span.start())?,
+                    None => write!(f, "{}: ", path.display())?,
+                }
+                type_error(f, error, diff.as_ref())
+            }
+            ModuleError::Eval { path, error } => write!(f, "{}: {}", path.display(), error),
+        }
+    }
+}
+
+/// Mismatches are written out as just the parts of the two types that are different, since
+/// both of them in full can be too big to read.
+fn type_error(
+    f: &mut fmt::Formatter<'_>,
+    error: &TypeError,
+    diff: Option<&TypeDiff>,
+) -> fmt::Result {
+    match (error, diff) {
+        (TypeError::Mismatch { .. }, Some(diff)) => {
+            write!(f, "mismatched types")?;
+            for line in diff.to_string().lines() {
+                write!(f, "\n    {}", line)?;
+            }
+            Ok(())
+        }
+        (TypeError::InStage { ptr, error }, diff) => {
+            write!(f, "in the pipeline stage at {}: ", ptr)?;
+            type_error(f, error, diff)
+        }
+        (error, _) => write!(f, "{}", error),
+    }
+}
+
+/// The two types of a mismatch, even if it's inside a pipeline stage.
+fn mismatch(error: &TypeError) -> Option<(&Type, &Type)> {
+    match error {
+        TypeError::Mismatch { expected, found } => Some((expected, found)),
+        TypeError::InStage { error, .. } => mismatch(error),
+        _ => None,
+    }
+}
+
+impl std::error::Error for ModuleError {}
+
+impl Loader {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn add_search_path(&mut self, path: impl Into<PathBuf>) -> &mut Self {
+        self.search_paths.push(path.into());
+        self
+    }
+
+    /// Loads the module at `path` along with everything it imports, returning the canonical path
+    /// that the module is known by.
+    pub fn load(&mut self, path: impl AsRef<Path>) -> Result<PathBuf, ModuleError> {
+        let path = path.as_ref();
+        let path = fs::canonicalize(path).map_err(|error| ModuleError::Io {
+            path: path.to_path_buf(),
+            error,
+        })?;
+        self.load_canonical(path)
+    }
+
+    fn load_canonical(&mut self, path: PathBuf) -> Result<PathBuf, ModuleError> {
+        if self.modules.contains_key(&path) {
+            return Ok(path);
+        }
+        if let Some(start) = self.loading.iter().position(|found| *found == path) {
+            let mut cycle = self.loading[start..].to_vec();
+            cycle.push(path);
+            return Err(ModuleError::Cycle(cycle));
+        }
+
+        let source = fs::read_to_string(&path).map_err(|error| ModuleError::Io {
+            path: path.clone(),
+            error,
+        })?;
+        let program = crate::parse_program(&source).map_err(|error| ModuleError::Parse {
+            path: path.clone(),
+            error,
+        })?;
+
+        self.loading.push(path.clone());
+        let imports = program
+            .imports
+            .iter()
+            .map(|with| {
+                let resolved = self.resolve(&path, &with.path)?;
+                Ok((with.alias.clone(), self.load_canonical(resolved)?))
+            })
+            .collect::<Result<Vec<_>, _>>();
+        self.loading.pop();
+
+        self.modules.insert(
+            path.clone(),
+            Module {
+                program,
+                imports: imports?,
+            },
+        );
+        Ok(path)
+    }
+
+    /// Finds the file `with` refers to when it's used in the module at `from`.
+    fn resolve(&self, from: &Path, with: &Path) -> Result<PathBuf, ModuleError> {
+        let dirs = from
+            .parent()
+            .into_iter()
+            .chain(self.search_paths.iter().map(PathBuf::as_path))
+            .collect::<Vec<_>>();
+        dirs.iter()
+            .find_map(|dir| fs::canonicalize(dir.join(with)).ok())
+            .ok_or_else(|| ModuleError::NotFound {
+                path: with.to_path_buf(),
+                searched: dirs.iter().map(|dir| dir.to_path_buf()).collect(),
+            })
+    }
+
+    /// Type checks a loaded module and everything it imports, returning the type of the module's
+    /// last statement.
+    pub fn check(&self, path: &Path) -> Result<Type, ModuleError> {
+        self.check_with(path, &mut HashMap::new())
+            .map(|(found, _)| found)
+    }
+
+    /// Type checks a loaded module and everything it imports, returning the type of each name
+    /// the module binds at the top level, in the order they're first bound. Names that are bound
+    /// more than once have the type of the last binding.
+    pub fn binding_types(&self, path: &Path) -> Result<Vec<(Identifier, Type)>, ModuleError> {
+        let (_, interface) = self.check_with(path, &mut HashMap::new())?;
+        let mut names = vec![];
+        for statement in &self.module(path).program.statements {
+            if let Statement::Binding(binding) = statement {
+                for name in binding.pattern.names() {
+                    if !names.contains(&name) {
+                        names.push(name);
+                    }
+                }
+            }
+        }
+        Ok(names
+            .into_iter()
+            .filter_map(|name| {
+                let found = interface
+                    .exports
+                    .0
+                    .get(name)
+                    .or_else(|| interface.private.0.get(name))?;
+                Some((name.clone(), found.clone()))
+            })
+            .collect())
+    }
+
+    /// Type checks a loaded module like [`check_all`](crate::check_all), after checking
+    /// everything it imports. Only errors in the modules it imports are returned as a
+    /// [`ModuleError`], the errors in the module itself are kept in the [`Typed`].
+    pub fn check_all(&self, path: &Path) -> Result<Typed<'_>, ModuleError> {
+        let imports = self.check_imports(path, &mut HashMap::new())?;
+        Ok(check_typed(&self.module(path).program, &imports))
+    }
+
+    /// Every binding, parameter and import that's never used, in the module at `path` and in
+    /// everything it imports, along with the path of the module each one is in. Each module's
+    /// warnings are only given once, however many modules import it.
+    pub fn warnings(&self, path: &Path) -> Vec<(PathBuf, ResolveWarning)> {
+        let mut warnings = vec![];
+        let mut seen = vec![];
+        let mut paths = vec![path.to_path_buf()];
+        while let Some(path) = paths.pop() {
+            if seen.contains(&path) {
+                continue;
+            }
+            let module = self.module(&path);
+            warnings.extend(
+                resolve_program(&module.program)
+                    .warnings
+                    .into_iter()
+                    .map(|warning| (path.clone(), warning)),
+            );
+            paths.extend(
+                module
+                    .imports
+                    .iter()
+                    .rev()
+                    .map(|(_, import)| import.clone()),
+            );
+            seen.push(path);
+        }
+        warnings
+    }
+
+    /// `checked` has the type of each module that's already been checked, so that modules that
+    /// are imported more than once are only checked once.
+    fn check_with(
+        &self,
+        path: &Path,
+        checked: &mut HashMap<PathBuf, Interface>,
+    ) -> Result<(Type, Interface), ModuleError> {
+        let imports = self.check_imports(path, checked)?;
+        let program = &self.module(path).program;
+        let errors = resolve_program(program).errors;
+        if !errors.is_empty() {
+            return Err(ModuleError::Resolve {
+                path: path.to_path_buf(),
+                errors,
+            });
+        }
+        check_module(program, &imports).map_err(|error| ModuleError::Type {
+            path: path.to_path_buf(),
+            // checking it again keeping every type finds where the error is
+            span: check_typed(program, &imports)
+                .errors
+                .into_iter()
+                .find(|(_, found)| *found == error)
+                .map(|(span, _)| span),
+            diff: mismatch(&error).map(|(expected, found)| {
+                diff(expected, found, &mut declared_types(program, &imports))
+            }),
+            error: Box::new(error),
+        })
+    }
+
+    /// The interface of each module the module at `path` imports, along with it's alias.
+    fn check_imports(
+        &self,
+        path: &Path,
+        checked: &mut HashMap<PathBuf, Interface>,
+    ) -> Result<Vec<(Identifier, Interface)>, ModuleError> {
+        let mut imports = vec![];
+        for (alias, import) in &self.module(path).imports {
+            let found = match checked.get(import) {
+                Some(found) => found.clone(),
+                None => {
+                    let (_, found) = self.check_with(import, checked)?;
+                    checked.insert(import.clone(), found.clone());
+                    found
+                }
+            };
+            imports.push((alias.clone(), found));
+        }
+        Ok(imports)
+    }
+
+    /// Runs a loaded module, after running everything it imports, returning the value of the
+    /// module's last statement. Each module is only run once, however many modules import it.
+    ///
+    /// The module should be [`check`](Loader::check)ed first.
+    pub fn eval(&self, path: &Path) -> Result<Value, ModuleError> {
+        self.eval_with(path, &mut HashMap::new())
+            .map(|(value, _)| value)
+    }
+
+    fn eval_with(
+        &self,
+        path: &Path,
+        evaluated: &mut HashMap<PathBuf, Value>,
+    ) -> Result<(Value, Value), ModuleError> {
+        let module = self.module(path);
+        let mut imports = vec![];
+        for (alias, import) in &module.imports {
+            let value = match evaluated.get(import) {
+                Some(value) => value.clone(),
+                None => {
+                    let (_, value) = self.eval_with(import, evaluated)?;
+                    evaluated.insert(import.clone(), value.clone());
+                    value
+                }
+            };
+            imports.push((alias.clone(), value));
+        }
+        eval_module(&module.program, &imports).map_err(|error| ModuleError::Eval {
+            path: path.to_path_buf(),
+            error: Box::new(error),
+        })
+    }
+
+    fn module(&self, path: &Path) -> &Module {
+        self.modules
+            .get(path)
+            .expect("only the paths of loaded modules are checked or evaluated")
+    }
+}
+
+#[cfg(test)]
+mod test_module {
+    use types::Number;
+
+    use crate::{
+        pointer::Span,
+        types::{primitive::PrimitiveType, Group, Named},
+    };
+
+    use super::*;
+
+    /// A new empty directory for the test to write it's modules in.
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("yaupl-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    fn write(dir: &Path, path: &str, source: &str) -> PathBuf {
+        let path = dir.join(path);
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(&path, source).unwrap();
+        path
+    }
+
+    #[test]
+    fn test_imports() {
+        let dir = temp_dir("imports");
+        write(
+            &dir,
+            "lib/maths.yaupl",
+            "with ../shared/base.yaupl as base\nexport double <- [x: num] -> * base.two x",
+        );
+        write(&dir, "shared/base.yaupl", "export two <- 2");
+        let main = write(
+            &dir,
+            "main.yaupl",
+            "with lib/maths.yaupl as maths\nwith shared/base.yaupl as b\nmaths.double[[b.two]]",
+        );
+        let mut loader = Loader::new();
+        let root = loader.load(&main).unwrap();
+        // `base.yaupl` is only loaded once, even though it's imported twice
+        assert_eq!(loader.modules.len(), 3);
+        assert_eq!(
+            loader.check(&root).unwrap(),
+            Type::Primitive(PrimitiveType::Num)
+        );
+        assert_eq!(
+            loader.eval(&root).unwrap(),
+            Value::Num(Number::from_parts(4, 0))
+        );
+    }
+
+    #[test]
+    fn test_binding_types() {
+        let dir = temp_dir("binding-types");
+        write(&dir, "maths.yaupl", "export double <- [x] -> * 2 x");
+        let main = write(
+            &dir,
+            "main.yaupl",
+            "with maths.yaupl as maths\nid <- [x] -> x\nn <- maths.double[[id[[1]]]]\nid[[n]]",
+        );
+        let mut loader = Loader::new();
+        let root = loader.load(&main).unwrap();
+        let found = loader
+            .binding_types(&root)
+            .unwrap()
+            .into_iter()
+            .map(|(name, found)| format!("{}: {}", name, found))
+            .collect::<Vec<_>>();
+        assert_eq!(found, vec!["id: <A>[A]=>A", "n: num"]);
+    }
+
+    #[test]
+    fn test_check_all() {
+        let dir = temp_dir("check-all");
+        write(
+            &dir,
+            "lib.yaupl",
+            "export one <- 1
+type Hidden <- num",
+        );
+        let main = write(
+            &dir,
+            "main.yaupl",
+            "with lib.yaupl as l
+x <- + l.one 1
+y: l.Hidden <- 2
++ x true",
+        );
+        let mut loader = Loader::new();
+        let root = loader.load(&main).unwrap();
+        let typed = loader.check_all(&root).unwrap();
+        let errors = typed
+            .errors
+            .iter()
+            .map(|(_, error)| error)
+            .collect::<Vec<_>>();
+        assert_eq!(
+            errors,
+            vec![
+                &TypeError::NotExported {
+                    module: Identifier("l".into()),
+                    name: Identifier("Hidden".into()),
+                },
+                &TypeError::Mismatch {
+                    expected: Box::new(Type::Primitive(PrimitiveType::Num)),
+                    found: Box::new(Type::Primitive(PrimitiveType::Bln)),
+                },
+            ]
+        );
+        let (_, found) = typed.statements().next().unwrap();
+        assert_eq!(
+            typed.interner().get(found),
+            Type::Primitive(PrimitiveType::Emp)
+        );
+    }
+
+    #[test]
+    fn test_search_paths() {
+        let dir = temp_dir("search-paths");
+        write(&dir, "std/strings.yaupl", r#"export greeting <- "hi""#);
+        let main = write(
+            &dir,
+            "app/main.yaupl",
+            "with strings.yaupl as s\ns.greeting",
+        );
+        let mut loader = Loader::new();
+        let err = loader.load(&main).unwrap_err();
+        assert!(matches!(err, ModuleError::NotFound { searched, .. } if searched.len() == 1));
+        loader.add_search_path(dir.join("std"));
+        let root = loader.load(&main).unwrap();
+        assert_eq!(loader.eval(&root).unwrap(), Value::Str("hi".into()));
+    }
+
+    #[test]
+    fn test_cycle() {
+        let dir = temp_dir("cycle");
+        write(&dir, "a.yaupl", "with b.yaupl as b\n1");
+        write(&dir, "b.yaupl", "with a.yaupl as a\n2");
+        let err = Loader::new().load(dir.join("a.yaupl")).unwrap_err();
+        let names = match &err {
+            ModuleError::Cycle(cycle) => cycle
+                .iter()
+                .map(|path| path.file_name().unwrap().to_str().unwrap())
+                .collect::<Vec<_>>(),
+            _ => panic!("expected a cycle, found {:?}", err),
+        };
+        assert_eq!(names, vec!["a.yaupl", "b.yaupl", "a.yaupl"]);
+        assert!(err.to_string().contains("b.yaupl -> "));
+    }
+
+    #[test]
+    fn test_visibility() {
+        let dir = temp_dir("visibility");
+        write(
+            &dir,
+            "counter.yaupl",
+            "step <- 1\nexport next <- [n: num] -> + n step",
+        );
+        let check = |source: &str| {
+            let main = write(&dir, "main.yaupl", source);
+            let mut loader = Loader::new();
+            let root = loader.load(&main).unwrap();
+            loader.check(&root)
+        };
+        assert!(check("with counter.yaupl as c\nc.next[[1]]").is_ok());
+        assert!(matches!(
+            check("with counter.yaupl as c\nc.step"),
+            Err(ModuleError::Type { error, .. }) if *error == TypeError::NotExported {
+                module: Identifier("c".into()),
+                name: Identifier("step".into()),
+            }
+        ));
+        // names that aren't bound at all are still just missing
+        assert!(matches!(
+            check("with counter.yaupl as c\nc.missing"),
+            Err(ModuleError::Type { error, .. }) if matches!(*error, TypeError::NoField { .. })
+        ));
+        // the module doesn't have to be named by it's alias
+        assert!(matches!(
+            check("with counter.yaupl as c\nm <- c\n{m}.step"),
+            Err(ModuleError::Type { error, .. }) if matches!(*error, TypeError::NotExported { .. })
+        ));
+        // once the alias is shadowed it's nothing to do with the module
+        assert!(matches!(
+            check("with counter.yaupl as c\nc <- |- a: 1 -|\nc.step"),
+            Err(ModuleError::Type { error, .. }) if matches!(*error, TypeError::NoField { .. })
+        ));
+    }
+
+    #[test]
+    fn test_types() {
+        let dir = temp_dir("types");
+        write(
+            &dir,
+            "shapes.yaupl",
+            "type Scale <- num
+            export type Point <- |- x: Scale, y: Scale -|
+            export type Path <- |- point: Point, rest: Path@ -|
+            export origin: Point <- |- x: 0, y: 0 -|",
+        );
+        let check = |source: &str| {
+            let main = write(&dir, "main.yaupl", source);
+            let mut loader = Loader::new();
+            let root = loader.load(&main).unwrap();
+            loader.check(&root)
+        };
+        // the importing module's own `Point` doesn't get mixed up with the imported one
+        assert_eq!(
+            check(
+                "with shapes.yaupl as s
+                type Point <- str
+                path: s.Path <- |- point: s.origin, rest: (| |) -|
+                p: Point <- \"p\"
+                path.rest"
+            )
+            .unwrap(),
+            Type::Group(Group(Box::new(Type::Named(Named {
+                name: Identifier("s.Path".into()),
+                arguments: vec![],
+                span: Span::default(),
+            }))))
+        );
+        assert!(matches!(
+            check("with shapes.yaupl as s\nx: s.Scale <- 1"),
+            Err(ModuleError::Type { error, .. }) if *error == TypeError::NotExported {
+                module: Identifier("s".into()),
+                name: Identifier("Scale".into()),
+            }
+        ));
+    }
+
+    #[test]
+    fn test_resolve() {
+        let dir = temp_dir("resolve");
+        write(&dir, "lib.yaupl", "unused <- 1\nexport x <- 2");
+        let main = write(
+            &dir,
+            "main.yaupl",
+            "with lib.yaupl as lib\nwith lib.yaupl as again\nf <- [a, b] -> a\nf[[lib.x, 1]]",
+        );
+        let mut loader = Loader::new();
+        let root = loader.load(&main).unwrap();
+        assert_eq!(
+            loader
+                .warnings(&root)
+                .into_iter()
+                .map(|(path, warning)| format!(
+                    "{}:{}",
+                    path.file_name().unwrap().to_str().unwrap(),
+                    warning
+                ))
+                .collect::<Vec<_>>(),
+            vec![
+                "main.yaupl:2:19: again is never used",
+                "main.yaupl:3:10: b is never used",
+                "lib.yaupl:1:1: unused is never used",
+            ]
+        );
+        assert!(loader.check(&root).is_ok());
+
+        let main = write(&dir, "undefined.yaupl", "a <- 1\n+ a b");
+        let root = loader.load(&main).unwrap();
+        let error = loader.check(&root).unwrap_err();
+        assert!(matches!(error, ModuleError::Resolve { .. }));
+        assert!(error
+            .to_string()
+            .ends_with("undefined.yaupl:2:5: b isn't defined"));
+    }
+
+    #[test]
+    fn test_errors_name_the_module() {
+        let dir = temp_dir("errors");
+        write(&dir, "bad.yaupl", "export x <- + 1 true");
+        let main = write(&dir, "main.yaupl", "with bad.yaupl as bad\nbad.x");
+        let mut loader = Loader::new();
+        let root = loader.load(&main).unwrap();
+        let error = loader.check(&root).unwrap_err();
+        assert!(matches!(
+            &error,
+            ModuleError::Type { path, .. } if path.ends_with("bad.yaupl")
+        ));
+        // with where the error is in the module
+        assert!(error
+            .to_string()
+            .ends_with("bad.yaupl:1:17: mismatched types\n    expected num, found bln"));
+
+        let main = write(&dir, "unclosed.yaupl", "x <- \"a {b\"");
+        let error = loader.load(&main).unwrap_err().to_string();
+        assert!(error.ends_with("unclosed.yaupl: in the interpolation at 1:9: expected `}`"));
+    }
+
+    #[test]
+    fn test_mismatch_diff() {
+        let dir = temp_dir("mismatch");
+        let main = write(
+            &dir,
+            "main.yaupl",
+            "type Shape <- |- name: str, area: [num, num]=>num -|
+            area <- [shape: |- name: str, area: [num, str]=>num -|] -> shape.area
+            f: [Shape]=>[num, num]=>num <- area",
+        );
+        let mut loader = Loader::new();
+        let root = loader.load(&main).unwrap();
+        let error = loader.check(&root).unwrap_err().to_string();
+        assert!(error.ends_with(
+            "main.yaupl:3:44: mismatched types\n    \
+             param 1 -> field area -> param 2: expected num, found str\n    \
+             return -> param 2: expected num, found str"
+        ));
+    }
+}
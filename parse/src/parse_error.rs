@@ -1,3 +1,5 @@
+use std::fmt;
+
 use crate::{pointer::Pointer, tokens::token::Token};
 
 #[derive(Debug)]
@@ -12,6 +14,76 @@ pub enum ParseError {
     OneOrMoe,
     ExpectedDigit,
     UnterminatedStringLiteral,
+    /// A `}` in a string that isn't part of `}}` or the end of an interpolation.
+    UnmatchedBrace,
+    /// An error in the expression interpolated into a string at `ptr`, or a missing `}` after
+    /// it.
+    InInterpolation {
+        ptr: Pointer,
+        error: Box<ParseError>,
+    },
+    EmptyTuple,
+    /// A number with an integer or `byte` suffix that isn't a whole number, or doesn't fit in
+    /// the type.
+    InvalidIntegerLiteral,
+    /// A union type with no variants, `<| |>`.
+    EmptyUnion,
+    /// A `with` path that doesn't name a `.yaupl` file.
+    InvalidModulePath,
+    /// `export` on something that isn't a binding.
+    InvalidExport,
+}
+
+impl ParseError {
+    /// Whether the parser that failed had already seen enough to know it was the right one, so
+    /// that there's no point trying anything else in the same place. A string with an unmatched
+    /// `}`, or a number with a suffix it doesn't fit, isn't going to parse as anything else
+    /// either.
+    pub(crate) fn is_committed(&self) -> bool {
+        matches!(
+            self,
+            ParseError::UnterminatedStringLiteral
+                | ParseError::UnmatchedBrace
+                | ParseError::InInterpolation { .. }
+                | ParseError::InvalidIntegerLiteral
+        )
+    }
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParseError::Expected(token) => write!(f, "expected `{}`", token.token()),
+            ParseError::ExpectedOneOf(tokens) => {
+                write!(f, "expected one of ")?;
+                for (ind, token) in tokens.iter().enumerate() {
+                    if ind > 0 {
+                        write!(f, ", ")?;
+                    }
+                    write!(f, "`{}`", token.token())?;
+                }
+                Ok(())
+            }
+            ParseError::UnexpectedKeyword(token) => write!(f, "unexpected `{}`", token.token()),
+            ParseError::None | ParseError::OneOf => write!(f, "couldn't parse what's next"),
+            ParseError::OneOrMoe => write!(f, "expected at least one"),
+            ParseError::ExpectedDigit => write!(f, "expected a digit"),
+            ParseError::UnterminatedStringLiteral => write!(f, "unterminated string"),
+            ParseError::UnmatchedBrace => {
+                write!(f, "unmatched `}}` in a string, `}}}}` is a literal `}}`")
+            }
+            ParseError::InInterpolation { ptr, error } => {
+                write!(f, "in the interpolation at {}: {}", ptr, error)
+            }
+            ParseError::EmptyTuple => write!(f, "tuples can't be empty"),
+            ParseError::InvalidIntegerLiteral => {
+                write!(f, "the number isn't whole or doesn't fit in it's type")
+            }
+            ParseError::EmptyUnion => write!(f, "unions can't be empty"),
+            ParseError::InvalidModulePath => write!(f, "a with path has to name a .yaupl file"),
+            ParseError::InvalidExport => write!(f, "only bindings can be exported"),
+        }
+    }
 }
 
 impl<T: Token + 'static> From<(&str, Pointer, T)> for ParseError {
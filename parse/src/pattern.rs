@@ -0,0 +1,180 @@
+use crate::{
+    combinators::{csv, optionally},
+    expression::{
+        literal::{literal, Literal},
+        ExpressionKind,
+    },
+    ident,
+    parse_error::ParseError,
+    pointer::{Pointer, Span},
+    spanned_ident,
+    tokens::{
+        brace_group_close, brace_group_open, colon, dot_dot, tesla_close, tesla_open, tuple_close,
+        tuple_open, underscore, variant_close, variant_open,
+    },
+    Identifier,
+};
+
+/// The left hand side of a binding, or one of the arms of a `match`. Patterns are written the
+/// same way as the literals they take apart.
+///
+/// ```yaupl
+/// _
+/// name
+/// 1
+/// [| first, _ |]
+/// |- name: n, version: 1 -|
+/// (| head, ..tail |)
+/// <| ok: value |>
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub enum Pattern {
+    /// `_`, matches anything without binding it.
+    Wildcard,
+    /// Matches anything, and binds it to the name.
+    Binding(Identifier, Span),
+    /// Only matches values equal to the literal.
+    Literal(Literal),
+    Tuple(Vec<Pattern>),
+    /// Any fields that aren't mentioned can have any value.
+    Complex(Vec<(Identifier, Pattern)>),
+    Group(GroupPattern),
+    /// Only matches the variant with that name, and matches what it carries against the pattern.
+    Variant(Identifier, Box<Pattern>),
+}
+
+/// `(| a, b |)` only matches groups with exactly two elements, `(| a, b, ..rest |)` matches any
+/// group with at least two, binding the rest of the group to `rest`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct GroupPattern {
+    pub elements: Vec<Pattern>,
+    /// `None` if there's no `..`, `Some(None)` for a `..` without a name.
+    pub rest: Option<Option<(Identifier, Span)>>,
+}
+
+pub(crate) fn pattern(i: &str, ptr: Pointer) -> Result<(&str, Pointer, Pattern), ParseError> {
+    literal(i, ptr)
+        .and_then(|(i, ptr, found)| match found.kind {
+            ExpressionKind::Literal(found) => Ok((i, ptr, Pattern::Literal(found))),
+            _ => Err(ParseError::None),
+        })
+        .or_else(|_| underscore(i, ptr).map(|res| (res.0, res.1, Pattern::Wildcard)))
+        .or_else(|_| {
+            spanned_ident(i, ptr)
+                .map(|(i, ptr, (name, span))| (i, ptr, Pattern::Binding(name, span)))
+        })
+        .or_else(|_| tuple_pattern(i, ptr))
+        .or_else(|_| complex_pattern(i, ptr))
+        .or_else(|_| group_pattern(i, ptr))
+        .or_else(|_| variant_pattern(i, ptr))
+}
+
+fn tuple_pattern(i: &str, ptr: Pointer) -> Result<(&str, Pointer, Pattern), ParseError> {
+    let (i, ptr, _bracket) = tuple_open(i, ptr)?;
+    let (i, ptr, elements) = csv(i, ptr, &pattern)?;
+    let (i, ptr, _bracket) = tuple_close(i, ptr)?;
+    if elements.is_empty() {
+        return Err(ParseError::EmptyTuple);
+    }
+    Ok((i, ptr, Pattern::Tuple(elements)))
+}
+
+fn complex_pattern(i: &str, ptr: Pointer) -> Result<(&str, Pointer, Pattern), ParseError> {
+    let (i, ptr, _bracket) = tesla_open(i, ptr)?;
+    let (i, ptr, fields) = csv(i, ptr, &field)?;
+    let (i, ptr, _bracket) = tesla_close(i, ptr)?;
+    Ok((i, ptr, Pattern::Complex(fields)))
+}
+
+fn field(i: &str, ptr: Pointer) -> Result<(&str, Pointer, (Identifier, Pattern)), ParseError> {
+    let (i, ptr, name) = ident(i, ptr)?;
+    let (i, ptr, _colon) = colon(i, ptr)?;
+    let (i, ptr, found) = pattern(i, ptr)?;
+    Ok((i, ptr, (name, found)))
+}
+
+fn group_pattern(i: &str, ptr: Pointer) -> Result<(&str, Pointer, Pattern), ParseError> {
+    let (i, ptr, _bracket) = brace_group_open(i, ptr)?;
+    let (i, ptr, elements) = csv(i, ptr, &pattern)?;
+    let (i, ptr, rest) = optionally(i, ptr, &rest);
+    let (i, ptr, _bracket) = brace_group_close(i, ptr)?;
+    Ok((i, ptr, Pattern::Group(GroupPattern { elements, rest })))
+}
+
+/// `<| none |>` is short for `<| none: _ |>`.
+fn variant_pattern(i: &str, ptr: Pointer) -> Result<(&str, Pointer, Pattern), ParseError> {
+    let (i, ptr, _bracket) = variant_open(i, ptr)?;
+    let (i, ptr, name) = ident(i, ptr)?;
+    let (i, ptr, found) = optionally(i, ptr, &|i, ptr| {
+        let (i, ptr, _colon) = colon(i, ptr)?;
+        pattern(i, ptr)
+    });
+    let (i, ptr, _bracket) = variant_close(i, ptr)?;
+    Ok((
+        i,
+        ptr,
+        Pattern::Variant(name, Box::new(found.unwrap_or(Pattern::Wildcard))),
+    ))
+}
+
+type Rest = Option<(Identifier, Span)>;
+
+/// `..rest` or `..`, which can only be the last thing in a group pattern.
+fn rest(i: &str, ptr: Pointer) -> Result<(&str, Pointer, Rest), ParseError> {
+    let (i, ptr, _dot_dot) = dot_dot(i, ptr)?;
+    Ok(optionally(i, ptr, &spanned_ident))
+}
+
+impl Pattern {
+    /// Every name bound by the pattern, in the order they're written.
+    pub(crate) fn names(&self) -> Vec<&Identifier> {
+        self.bindings().into_iter().map(|(name, _)| name).collect()
+    }
+
+    /// Every name bound by the pattern and where it was written, in the order they're written.
+    pub(crate) fn bindings(&self) -> Vec<(&Identifier, Span)> {
+        match self {
+            Pattern::Wildcard | Pattern::Literal(_) => vec![],
+            Pattern::Binding(name, span) => vec![(name, *span)],
+            Pattern::Tuple(elements) => elements.iter().flat_map(Pattern::bindings).collect(),
+            Pattern::Complex(fields) => fields
+                .iter()
+                .flat_map(|(_, found)| found.bindings())
+                .collect(),
+            Pattern::Group(group) => group
+                .elements
+                .iter()
+                .flat_map(Pattern::bindings)
+                .chain(
+                    group
+                        .rest
+                        .iter()
+                        .flatten()
+                        .map(|(name, span)| (name, *span)),
+                )
+                .collect(),
+            Pattern::Variant(_, found) => found.bindings(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test_pattern {
+    use super::*;
+
+    #[test]
+    fn test_pattern() {
+        let (i, _, found) = pattern(
+            "[| _, |- a: first, b: 1 -|, (| x, ..rest |) |] <-",
+            Pointer::new(0, 0),
+        )
+        .unwrap();
+        assert_eq!(i, " <-");
+        let name = |name: &str| Identifier(name.into());
+        assert_eq!(
+            found.names(),
+            vec![&name("first"), &name("x"), &name("rest")]
+        );
+        assert!(matches!(found, Pattern::Tuple(elements) if elements.len() == 3));
+    }
+}
@@ -1,4 +1,6 @@
-#[derive(Debug, Copy, Clone, Eq, PartialEq, Ord, PartialOrd)]
+use std::fmt;
+
+#[derive(Debug, Copy, Clone, Default, Eq, PartialEq, Ord, PartialOrd)]
 pub struct Pointer {
     pub(crate) row: usize,
     pub(crate) col: usize,
@@ -22,6 +24,13 @@ impl Pointer {
     }
 }
 
+/// Written as `row:col`, counting both from 1 like editors do.
+impl fmt::Display for Pointer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:{}", self.row + 1, self.col + 1)
+    }
+}
+
 impl From<(usize, usize)> for Pointer {
     fn from(tuple: (usize, usize)) -> Self {
         Self::new(tuple.0, tuple.1)
@@ -33,3 +42,70 @@ impl From<Pointer> for (usize, usize) {
         (ptr.row, ptr.col)
     }
 }
+
+/// The part of the parsed code that something was written in, from `start` up to but not
+/// including `end`.
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Span {
+    pub(crate) start: Pointer,
+    pub(crate) end: Pointer,
+}
+
+impl Span {
+    pub fn new(start: Pointer, end: Pointer) -> Self {
+        Self { start, end }
+    }
+
+    pub fn start(&self) -> Pointer {
+        self.start
+    }
+
+    pub fn end(&self) -> Pointer {
+        self.end
+    }
+
+    /// Whether `ptr` is inside the span.
+    pub fn contains(&self, ptr: Pointer) -> bool {
+        self.start <= ptr && ptr < self.end
+    }
+}
+
+/// The `Debug` output of `value` with every span in it left out, for comparing syntax in tests
+/// without spelling out where each part of it was written.
+#[cfg(test)]
+pub(crate) fn without_spans<T: std::fmt::Debug>(value: &T) -> String {
+    let debug = format!("{:?}", value);
+    let mut out = String::with_capacity(debug.len());
+    let mut rest = debug.as_str();
+    while let Some(at) = rest.find("Span {") {
+        out.push_str(&rest[..at + "Span".len()]);
+        let mut depth = 0;
+        let end = rest[at..]
+            .char_indices()
+            .find(|&(_, c)| {
+                match c {
+                    '{' => depth += 1,
+                    '}' => depth -= 1,
+                    _ => return false,
+                }
+                depth == 0
+            })
+            .map_or(rest.len(), |(i, _)| at + i + 1);
+        rest = &rest[end..];
+    }
+    out.push_str(rest);
+    out
+}
+
+#[cfg(test)]
+mod test_pointer {
+    use super::*;
+
+    #[test]
+    fn test_without_spans() {
+        let span = Span::new(Pointer::new(1, 2), Pointer::new(1, 5));
+        assert_ne!(span, Span::default());
+        assert_eq!(without_spans(&span), without_spans(&Span::default()));
+        assert_eq!(without_spans(&(1, span, "a")), r#"(1, Span, "a")"#);
+    }
+}
@@ -0,0 +1,157 @@
+use std::collections::BTreeSet;
+
+use crate::{
+    combinators::{optionally, OrNext},
+    parse_error::ParseError,
+    pointer::{Pointer, Span},
+    statement::{
+        statement,
+        type_declaration::type_declaration,
+        with::{with, With},
+        Statement,
+    },
+    tokens::{keyword_export, semicolon},
+    whitespace::whitespace,
+    Identifier,
+};
+
+/// ```yaupl
+/// with maths.yaupl as maths
+/// type Count <- num
+/// a <- 1
+/// export b: Count <- + a 2;
+/// - b
+/// ```
+///
+/// Statements can optionally be ended with a `;`, which is only really needed to stop a `-` from
+/// being read as a subtraction: `- a; b` is the negation of `a` followed by `b`. Any imports have
+/// to come before the first statement.
+///
+/// Top level bindings and type declarations are private to the module unless they're marked with
+/// `export`, in which case they can be used by any module that imports this one.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Program {
+    pub imports: Vec<With>,
+    pub statements: Vec<Statement>,
+    /// Where each of the statements is.
+    pub spans: Vec<Span>,
+    /// Every name bound by an `export`ed binding.
+    pub exports: BTreeSet<Identifier>,
+    /// Every `export`ed type declaration.
+    pub exported_types: BTreeSet<Identifier>,
+}
+
+pub(crate) fn program(i: &str, ptr: Pointer) -> Result<(&str, Pointer, Program), ParseError> {
+    let (mut i, mut ptr, mut imports) = (i, ptr, vec![]);
+    while let Ok((new_i, new_ptr, found)) = with(i, ptr) {
+        imports.push(found);
+        let (new_i, new_ptr, _semicolon) = optionally(new_i, new_ptr, &semicolon);
+        i = new_i;
+        ptr = new_ptr;
+    }
+    let (mut statements, mut spans, mut exports, mut exported_types) =
+        (vec![], vec![], BTreeSet::new(), BTreeSet::new());
+    loop {
+        let (rest, rest_ptr) = whitespace(i, ptr);
+        if rest.is_empty() {
+            return Ok((
+                rest,
+                rest_ptr,
+                Program {
+                    imports,
+                    statements,
+                    spans,
+                    exports,
+                    exported_types,
+                },
+            ));
+        }
+        let (new_i, new_ptr, export) = optionally(i, ptr, &keyword_export);
+        let (new_i, new_ptr, found) = type_declaration(new_i, new_ptr)
+            .map(|res| (res.0, res.1, Statement::TypeDeclaration(res.2)))
+            .or_next(|| statement(new_i, new_ptr))?;
+        if export.is_some() {
+            match &found {
+                Statement::Binding(binding) => {
+                    exports.extend(binding.pattern.names().into_iter().cloned())
+                }
+                Statement::TypeDeclaration(declaration) => {
+                    exported_types.insert(declaration.name.clone());
+                }
+                Statement::Expression(_) => return Err(ParseError::InvalidExport),
+            }
+        }
+        statements.push(found);
+        spans.push(Span::new(rest_ptr, new_ptr));
+        let (new_i, new_ptr, _semicolon) = optionally(new_i, new_ptr, &semicolon);
+        i = new_i;
+        ptr = new_ptr;
+    }
+}
+
+impl Program {
+    /// Every name bound by a top level binding that isn't `export`ed.
+    pub(crate) fn private_names(&self) -> BTreeSet<&Identifier> {
+        self.statements
+            .iter()
+            .flat_map(|statement| match statement {
+                Statement::Binding(binding) => binding.pattern.names(),
+                Statement::Expression(_) | Statement::TypeDeclaration(_) => vec![],
+            })
+            .filter(|name| !self.exports.contains(*name))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod test_program {
+    use super::*;
+
+    #[test]
+    fn test_exports() {
+        let (_, _, found) = program(
+            "a <- 1\nexport [| b, c |] <- [| a, a |]\nexport d <- b",
+            Pointer::new(0, 0),
+        )
+        .unwrap();
+        let name = |name: &str| Identifier(name.into());
+        assert_eq!(
+            found.exports,
+            vec![name("b"), name("c"), name("d")].into_iter().collect()
+        );
+        assert_eq!(
+            found.private_names(),
+            vec![&name("a")].into_iter().collect()
+        );
+        assert!(matches!(
+            program("export + 1 2", Pointer::new(0, 0)),
+            Err(ParseError::InvalidExport)
+        ));
+    }
+
+    #[test]
+    fn test_committed_errors() {
+        let parse = |i| program(i, Pointer::new(0, 0));
+        assert!(matches!(
+            crate::parse_program("x <- 300u8"),
+            Err(ParseError::InvalidIntegerLiteral)
+        ));
+        assert!(matches!(
+            parse("[| 1, + 2 1.5byte |]"),
+            Err(ParseError::InvalidIntegerLiteral)
+        ));
+        assert!(matches!(
+            parse("x <- \"a } b\""),
+            Err(ParseError::UnmatchedBrace)
+        ));
+        assert!(matches!(
+            parse("x <- \"{+ 1}\""),
+            Err(ParseError::InInterpolation { ptr, .. }) if ptr == Pointer::new(0, 6)
+        ));
+        assert!(matches!(
+            parse("f[[\"{a\"]]"),
+            Err(ParseError::InInterpolation { error, .. })
+                if matches!(*error, ParseError::Expected(_))
+        ));
+    }
+}
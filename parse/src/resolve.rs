@@ -0,0 +1,570 @@
+//! Connecting every name that's used to the binding, parameter, import or type declaration it
+//! refers to.
+//!
+//! Names follow the same scoping rules as when the program is checked and run: a binding is
+//! visible from the statement after it to the end of the enclosing block or module, parameters
+//! and the names bound by the patterns of `match` arms and `for` loops are only visible in the
+//! body, and type declarations are visible everywhere in the module they're declared in.
+
+use std::{cell::RefCell, collections::BTreeMap, fmt};
+
+use crate::{
+    check::named::type_slot,
+    expression::{Expression, ExpressionKind},
+    pattern::Pattern,
+    pointer::{Pointer, Span},
+    program::Program,
+    scope::Scope,
+    statement::{binding::Binding, Statement},
+    types::{Named, Type},
+    Identifier,
+};
+
+/// The result of resolving a module. Resolving doesn't stop at the first error, so every error and
+/// warning in the module is found.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Resolution {
+    definitions: Vec<Definition>,
+    /// Where each name was used, and what it refers to, in the order they're written.
+    uses: Vec<(Span, DefinitionId)>,
+    pub errors: Vec<ResolveError>,
+    pub warnings: Vec<ResolveWarning>,
+}
+
+/// Which of the [`Resolution`]'s definitions something refers to.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub struct DefinitionId(usize);
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Definition {
+    pub name: Identifier,
+    pub kind: DefinitionKind,
+    /// Where the name was written where it was defined.
+    pub span: Span,
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum DefinitionKind {
+    /// A name bound by the pattern of a binding, a `match` arm or a `for` loop.
+    Binding,
+    /// A parameter of a lambda.
+    Parameter,
+    /// The alias of a module imported with `with`.
+    Import,
+    /// A type declared with `type`.
+    Type,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+#[non_exhaustive]
+pub enum ResolveError {
+    /// A name was used that isn't defined anywhere it can be seen from.
+    Undefined { name: Identifier, span: Span },
+    /// The same name was defined twice in one pattern, parameter list, set of imports or module's
+    /// type declarations. Bindings in separate statements can shadow each other, so they aren't
+    /// duplicates.
+    Duplicate {
+        name: Identifier,
+        first: Span,
+        second: Span,
+    },
+}
+
+#[derive(Debug, Clone, PartialEq)]
+#[non_exhaustive]
+pub enum ResolveWarning {
+    /// A binding, parameter or import that's never used. Names starting with `_`, and names that
+    /// are `export`ed, aren't warned about.
+    Unused { name: Identifier, span: Span },
+}
+
+impl fmt::Display for ResolveError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ResolveError::Undefined { name, span } => {
+                write!(f, "{}: {} isn't defined", span.start, name)
+            }
+            ResolveError::Duplicate {
+                name,
+                first,
+                second,
+            } => write!(
+                f,
+                "{}: {} is already defined at {}",
+                second.start, name, first.start
+            ),
+        }
+    }
+}
+
+impl fmt::Display for ResolveWarning {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ResolveWarning::Unused { name, span } => {
+                write!(f, "{}: {} is never used", span.start, name)
+            }
+        }
+    }
+}
+
+impl Resolution {
+    pub fn definition(&self, id: DefinitionId) -> &Definition {
+        &self.definitions[id.0]
+    }
+
+    /// Every definition in the module, in the order they're found.
+    pub fn definitions(&self) -> impl Iterator<Item = (DefinitionId, &Definition)> {
+        self.definitions
+            .iter()
+            .enumerate()
+            .map(|(id, definition)| (DefinitionId(id), definition))
+    }
+
+    /// Everywhere the definition is used.
+    pub fn uses(&self, id: DefinitionId) -> impl Iterator<Item = Span> + '_ {
+        self.uses
+            .iter()
+            .filter(move |(_, found)| *found == id)
+            .map(|(span, _)| *span)
+    }
+
+    /// The definition of the name at `ptr`, which can be either a use of the name or where it's
+    /// defined.
+    pub fn definition_at(&self, ptr: Pointer) -> Option<DefinitionId> {
+        self.uses
+            .iter()
+            .find(|(span, _)| span.contains(ptr))
+            .map(|(_, id)| *id)
+            .or_else(|| {
+                self.definitions()
+                    .find(|(_, definition)| definition.span.contains(ptr))
+                    .map(|(id, _)| id)
+            })
+    }
+}
+
+/// Resolves every name in a module.
+pub(crate) fn resolve_program(program: &Program) -> Resolution {
+    let mut resolver = Resolver::default();
+    let mut scope = Scope::new();
+    let mut imports = BTreeMap::new();
+    for with in &program.imports {
+        resolver.unique(&mut imports, &with.alias, with.span);
+        let id = resolver.define(&with.alias, DefinitionKind::Import, with.span);
+        scope.bind(with.alias.clone(), id);
+    }
+    let mut types = BTreeMap::new();
+    for statement in &program.statements {
+        if let Statement::TypeDeclaration(declaration) = statement {
+            resolver.unique(&mut types, &declaration.name, declaration.span);
+            let id = resolver.define(&declaration.name, DefinitionKind::Type, declaration.span);
+            scope.bind(type_slot(&declaration.name), id);
+        }
+    }
+    for statement in &program.statements {
+        resolver.statement(statement, &mut scope);
+    }
+    for name in &program.exports {
+        if let Some(id) = scope.get(name) {
+            resolver.used[id.0] = true;
+        }
+    }
+    resolver.finish()
+}
+
+#[derive(Debug, Default)]
+struct Resolver {
+    definitions: Vec<Definition>,
+    used: Vec<bool>,
+    uses: Vec<(Span, DefinitionId)>,
+    errors: Vec<ResolveError>,
+    /// The type parameters of the lambdas being resolved, which aren't in the table since where
+    /// they're written isn't kept.
+    type_parameters: Vec<Identifier>,
+}
+
+impl Resolver {
+    fn define(&mut self, name: &Identifier, kind: DefinitionKind, span: Span) -> DefinitionId {
+        self.definitions.push(Definition {
+            name: name.clone(),
+            kind,
+            span,
+        });
+        self.used.push(false);
+        DefinitionId(self.definitions.len() - 1)
+    }
+
+    /// Reports the name if it's already in `seen`, which is everything defined alongside it.
+    fn unique(&mut self, seen: &mut BTreeMap<Identifier, Span>, name: &Identifier, span: Span) {
+        if let Some(first) = seen.insert(name.clone(), span) {
+            self.errors.push(ResolveError::Duplicate {
+                name: name.clone(),
+                first,
+                second: span,
+            });
+        }
+    }
+
+    /// Records a use of `slot`, which is reported as `name` if it isn't defined.
+    fn use_name(
+        &mut self,
+        slot: &Identifier,
+        name: &Identifier,
+        span: Span,
+        scope: &Scope<DefinitionId>,
+    ) {
+        match scope.get(slot) {
+            Some(id) => {
+                self.used[id.0] = true;
+                self.uses.push((span, *id));
+            }
+            None => self.errors.push(ResolveError::Undefined {
+                name: name.clone(),
+                span,
+            }),
+        }
+    }
+
+    /// Defines every name bound by the pattern.
+    fn pattern(&mut self, pattern: &Pattern, scope: &mut Scope<DefinitionId>) {
+        let mut seen = BTreeMap::new();
+        for (name, span) in pattern.bindings() {
+            self.unique(&mut seen, name, span);
+            let id = self.define(name, DefinitionKind::Binding, span);
+            scope.bind(name.clone(), id);
+        }
+    }
+
+    fn statement(&mut self, statement: &Statement, scope: &mut Scope<DefinitionId>) {
+        match statement {
+            Statement::Binding(found) => self.binding(found, scope),
+            Statement::Expression(expr) => self.expression(expr, scope),
+            // the type parameters are already type variables in the definition
+            Statement::TypeDeclaration(declaration) => {
+                self.type_names(&declaration.definition, scope)
+            }
+        }
+    }
+
+    /// The names bound by the pattern can't be used in the expression, so they're defined after
+    /// it's resolved.
+    fn binding(&mut self, binding: &Binding, scope: &mut Scope<DefinitionId>) {
+        if let Some(annotation) = &binding.annotation {
+            self.type_names(annotation, scope);
+        }
+        self.expression(&binding.expr, scope);
+        self.pattern(&binding.pattern, scope);
+    }
+
+    /// Records every named type used in `found`. A name from an imported module, `alias.Name`,
+    /// is a use of `alias`.
+    fn type_names(&mut self, found: &Type, scope: &Scope<DefinitionId>) {
+        let named = RefCell::new(vec![]);
+        found.replace(&|found| {
+            if let Type::Named(Named { name, span, .. }) = found {
+                named.borrow_mut().push((name.clone(), *span));
+            }
+            None
+        });
+        for (name, span) in named.into_inner() {
+            match name.0.split_once('.') {
+                Some((alias, _)) => {
+                    let alias = Identifier(alias.into());
+                    self.use_name(&alias, &alias, span, scope)
+                }
+                None if self.type_parameters.contains(&name) => {}
+                None => self.use_name(&type_slot(&name), &name, span, scope),
+            }
+        }
+    }
+
+    fn expression(&mut self, expr: &Expression, scope: &mut Scope<DefinitionId>) {
+        match &expr.kind {
+            ExpressionKind::Identifier(name) => self.use_name(name, name, expr.span, scope),
+            ExpressionKind::Literal(_) | ExpressionKind::Break | ExpressionKind::Continue => {}
+            ExpressionKind::UnaryOperation(operation) => self.expression(&operation.expr, scope),
+            ExpressionKind::BinaryOperation(operation) => {
+                self.expression(&operation.first, scope);
+                self.expression(&operation.second, scope);
+            }
+            ExpressionKind::FunctionCall(call) => {
+                self.expression(&call.callee, scope);
+                self.expressions(&call.args, scope);
+            }
+            ExpressionKind::Lambda(lambda) => {
+                scope.push();
+                let outer = self.type_parameters.len();
+                self.type_parameters
+                    .extend(lambda.type_parameters.iter().cloned());
+                let mut seen = BTreeMap::new();
+                for (name, found, span) in &lambda.parameters {
+                    if let Some(found) = found {
+                        self.type_names(found, scope);
+                    }
+                    self.unique(&mut seen, name, *span);
+                    let id = self.define(name, DefinitionKind::Parameter, *span);
+                    scope.bind(name.clone(), id);
+                }
+                self.expression(&lambda.body, scope);
+                self.type_parameters.truncate(outer);
+                scope.pop();
+            }
+            ExpressionKind::Tuple(tuple) => self.expressions(&tuple.0, scope),
+            ExpressionKind::Complex(complex) => {
+                for (_, value) in &complex.0 {
+                    self.expression(value, scope);
+                }
+            }
+            ExpressionKind::Group(group) => self.expressions(&group.0, scope),
+            ExpressionKind::Map(literal) => {
+                for (key, value) in &literal.0 {
+                    self.expression(key, scope);
+                    self.expression(value, scope);
+                }
+            }
+            ExpressionKind::Insert(insert) => {
+                self.expression(&insert.map, scope);
+                self.expression(&insert.key, scope);
+                self.expression(&insert.value, scope);
+            }
+            ExpressionKind::Variant(variant) => {
+                if let Some(value) = &variant.value {
+                    self.expression(value, scope);
+                }
+            }
+            ExpressionKind::Is(is) => self.expression(&is.expr, scope),
+            ExpressionKind::Conditional(conditional) => {
+                self.expression(&conditional.condition, scope);
+                self.expression(&conditional.then, scope);
+                self.expression(&conditional.otherwise, scope);
+            }
+            ExpressionKind::Conversion(conversion) => self.expression(&conversion.expr, scope),
+            ExpressionKind::Match(matching) => {
+                self.expression(&matching.expr, scope);
+                for (pattern, body) in &matching.arms {
+                    scope.push();
+                    self.pattern(pattern, scope);
+                    self.expression(body, scope);
+                    scope.pop();
+                }
+            }
+            ExpressionKind::Pipeline(pipeline) => {
+                self.expression(&pipeline.expr, scope);
+                for stage in &pipeline.stages {
+                    self.expression(&stage.call.callee, scope);
+                    self.expressions(&stage.call.args, scope);
+                }
+            }
+            ExpressionKind::Block(block) => {
+                scope.push();
+                for statement in &block.statements {
+                    self.statement(statement, scope);
+                }
+                scope.pop();
+            }
+            ExpressionKind::Return(found) => {
+                if let Some(expr) = &found.expr {
+                    self.expression(expr, scope);
+                }
+            }
+            ExpressionKind::For(found) => {
+                self.expression(&found.group, scope);
+                scope.push();
+                self.pattern(&found.pattern, scope);
+                self.expression(&found.body, scope);
+                scope.pop();
+            }
+            ExpressionKind::FieldAccess(access) => self.expression(&access.expr, scope),
+            ExpressionKind::Projection(projection) => self.expression(&projection.expr, scope),
+            ExpressionKind::Index(index) => {
+                self.expression(&index.expr, scope);
+                self.expression(&index.index, scope);
+            }
+            ExpressionKind::Slice(slice) => {
+                self.expression(&slice.expr, scope);
+                for bound in slice.start.iter().chain(&slice.end) {
+                    self.expression(bound, scope);
+                }
+            }
+        }
+    }
+
+    fn expressions(&mut self, exprs: &[Expression], scope: &mut Scope<DefinitionId>) {
+        for expr in exprs {
+            self.expression(expr, scope);
+        }
+    }
+
+    fn finish(self) -> Resolution {
+        let warnings = self
+            .definitions
+            .iter()
+            .zip(&self.used)
+            .filter(|(definition, used)| {
+                !**used
+                    && definition.kind != DefinitionKind::Type
+                    && !definition.name.0.starts_with('_')
+            })
+            .map(|(definition, _)| ResolveWarning::Unused {
+                name: definition.name.clone(),
+                span: definition.span,
+            })
+            .collect();
+        Resolution {
+            definitions: self.definitions,
+            uses: self.uses,
+            errors: self.errors,
+            warnings,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test_resolve {
+    use crate::parse_program;
+
+    use super::*;
+
+    fn resolve_str(i: &str) -> Resolution {
+        resolve_program(&parse_program(i).unwrap())
+    }
+
+    fn name(name: &str) -> Identifier {
+        Identifier(name.into())
+    }
+
+    fn span(start: (usize, usize), end: (usize, usize)) -> Span {
+        Span::new(start.into(), end.into())
+    }
+
+    fn uses_of(resolution: &Resolution, id: DefinitionId) -> Vec<Span> {
+        resolution.uses(id).collect()
+    }
+
+    #[test]
+    fn test_def_use() {
+        let found = resolve_str(
+            "a <- 1
+             f <- [b: num] -> + a b
+             a <- f[[a]]
+             a",
+        );
+        assert_eq!(found.errors, vec![]);
+        assert_eq!(found.warnings, vec![]);
+        let definitions = found
+            .definitions()
+            .map(|(_, definition)| (definition.name.0.as_str(), definition.kind))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            definitions,
+            vec![
+                ("a", DefinitionKind::Binding),
+                ("b", DefinitionKind::Parameter),
+                ("f", DefinitionKind::Binding),
+                ("a", DefinitionKind::Binding),
+            ]
+        );
+        let first_a = found.definition_at(Pointer::new(0, 0)).unwrap();
+        assert_eq!(
+            uses_of(&found, first_a),
+            vec![span((1, 32), (1, 33)), span((2, 21), (2, 22))]
+        );
+        // the last use is of the second `a`, which shadows the first
+        let last_a = found.definition_at(Pointer::new(3, 13)).unwrap();
+        assert_eq!(found.definition(last_a).span, span((2, 13), (2, 14)));
+        let b = found.definition_at(Pointer::new(1, 34)).unwrap();
+        assert_eq!(found.definition(b).kind, DefinitionKind::Parameter);
+    }
+
+    #[test]
+    fn test_scopes() {
+        let found = resolve_str(
+            "x <- { y <- 1; y }
+             match[[x, 1 -> z, z -> z]]
+             for[[w <- (| 1 |), w]]
+             [| y, w |]",
+        );
+        let undefined = |name_: &str, start: (usize, usize)| ResolveError::Undefined {
+            name: name(name_),
+            span: span(start, (start.0, start.1 + 1)),
+        };
+        assert_eq!(
+            found.errors,
+            vec![
+                undefined("z", (1, 28)),
+                undefined("y", (3, 16)),
+                undefined("w", (3, 19)),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_duplicates() {
+        let found = resolve_str(
+            "type A <- num
+             type A <- str
+             [| a, a |] <- [| 1, 2 |]
+             [a: num, a: num] -> 1",
+        );
+        let duplicate =
+            |name_: &str, first: (usize, usize), second: (usize, usize)| ResolveError::Duplicate {
+                name: name(name_),
+                first: span(first, (first.0, first.1 + 1)),
+                second: span(second, (second.0, second.1 + 1)),
+            };
+        assert_eq!(
+            found.errors,
+            vec![
+                duplicate("A", (0, 5), (1, 18)),
+                duplicate("a", (2, 16), (2, 19)),
+                duplicate("a", (3, 14), (3, 22)),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_types() {
+        let found = resolve_str(
+            "with shapes.yaupl as s
+             type List<T> <- |- head: T, tail: List<T>? -|
+             f <- <T>[xs: List<T>] -> { ys: List<T> <- xs; p: s.Point <- ys; p }
+             g: Missing <- f; g",
+        );
+        assert_eq!(found.warnings, vec![]);
+        assert_eq!(
+            found.errors,
+            vec![ResolveError::Undefined {
+                name: name("Missing"),
+                span: span((3, 16), (3, 23)),
+            }]
+        );
+        let list = found.definition_at(Pointer::new(1, 18)).unwrap();
+        assert_eq!(found.definition(list).kind, DefinitionKind::Type);
+        assert_eq!(uses_of(&found, list).len(), 3);
+        let import = found.definition_at(Pointer::new(0, 21)).unwrap();
+        assert_eq!(uses_of(&found, import), vec![span((2, 62), (2, 69))]);
+    }
+
+    #[test]
+    fn test_unused() {
+        let found = resolve_str(
+            "with maths.yaupl as m
+             a <- 1
+             _b <- 2
+             export c <- 3
+             [x: num, y: num] -> x",
+        );
+        let unused = |name_: &str, start: (usize, usize)| ResolveWarning::Unused {
+            name: name(name_),
+            span: span(start, (start.0, start.1 + 1)),
+        };
+        assert_eq!(
+            found.warnings,
+            vec![
+                unused("m", (0, 20)),
+                unused("a", (1, 13)),
+                unused("y", (4, 22)),
+            ]
+        );
+    }
+}
@@ -0,0 +1,87 @@
+use crate::Identifier;
+
+/// A stack of lexical scopes, mapping names to whatever is being tracked for them (their type
+/// while checking, their value while evaluating).
+///
+/// A binding shadows any earlier binding of the same name, whether that's in the same scope or
+/// an enclosing one. Shadowed bindings aren't changed, they just can't be named until the
+/// shadowing binding goes out of scope.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct Scope<T> {
+    bindings: Vec<(Identifier, T)>,
+    /// The length of `bindings` when each of the currently open scopes was entered.
+    frames: Vec<usize>,
+}
+
+impl<T> Scope<T> {
+    pub(crate) fn new() -> Self {
+        Self {
+            bindings: vec![],
+            frames: vec![],
+        }
+    }
+
+    pub(crate) fn bind(&mut self, name: Identifier, value: T) {
+        self.bindings.push((name, value));
+    }
+
+    pub(crate) fn get(&self, name: &Identifier) -> Option<&T> {
+        self.bindings
+            .iter()
+            .rev()
+            .find(|(bound, _)| bound == name)
+            .map(|(_, value)| value)
+    }
+
+    pub(crate) fn get_mut(&mut self, name: &Identifier) -> Option<&mut T> {
+        self.bindings
+            .iter_mut()
+            .rev()
+            .find(|(bound, _)| bound == name)
+            .map(|(_, value)| value)
+    }
+
+    /// Everything that's bound, including anything that's shadowed.
+    pub(crate) fn values(&self) -> impl Iterator<Item = &T> {
+        self.bindings.iter().map(|(_, value)| value)
+    }
+
+    /// Opens a new scope, everything bound after this is dropped by the matching [`Scope::pop`].
+    pub(crate) fn push(&mut self) {
+        self.frames.push(self.bindings.len());
+    }
+
+    pub(crate) fn pop(&mut self) {
+        let len = self
+            .frames
+            .pop()
+            .expect("popped a scope that was never pushed");
+        self.bindings.truncate(len);
+    }
+}
+
+#[cfg(test)]
+mod test_scope {
+    use super::*;
+
+    fn name(name: &str) -> Identifier {
+        Identifier(name.into())
+    }
+
+    #[test]
+    fn test_shadowing() {
+        let mut scope = Scope::new();
+        scope.bind(name("a"), 1);
+        scope.bind(name("a"), 2);
+        assert_eq!(scope.get(&name("a")), Some(&2));
+
+        scope.push();
+        scope.bind(name("a"), 3);
+        scope.bind(name("b"), 4);
+        assert_eq!(scope.get(&name("a")), Some(&3));
+        scope.pop();
+
+        assert_eq!(scope.get(&name("a")), Some(&2));
+        assert_eq!(scope.get(&name("b")), None);
+    }
+}
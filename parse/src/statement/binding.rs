@@ -0,0 +1,47 @@
+use crate::{
+    combinators::optionally,
+    expression::{expression, Expression},
+    parse_error::ParseError,
+    pattern::{pattern, Pattern},
+    pointer::Pointer,
+    tokens::{arrow_left, colon},
+    types::{yaupl_type, Type},
+};
+
+/// ```yaupl
+/// name <- expression
+/// name: type <- expression
+/// [| first, second |] <- expression
+/// ```
+///
+/// The pattern has to match every value of the expression's type.
+pub(crate) fn binding(i: &str, ptr: Pointer) -> Result<(&str, Pointer, Binding), ParseError> {
+    let (i, ptr, pattern) = pattern(i, ptr)?;
+    let (i, ptr, annotation) = optionally(i, ptr, &type_annotation);
+    let (i, ptr, _arrow) = arrow_left(i, ptr)?;
+    let (i, ptr, expr) = expression(i, ptr)?;
+    Ok((
+        i,
+        ptr,
+        Binding {
+            pattern,
+            annotation,
+            expr,
+        },
+    ))
+}
+
+fn type_annotation(i: &str, ptr: Pointer) -> Result<(&str, Pointer, Type), ParseError> {
+    let (i, ptr, _colon) = colon(i, ptr)?;
+    yaupl_type(i, ptr)
+}
+
+/// Binds the names in `pattern` to the parts of the value of `expr` for the rest of the enclosing
+/// scope, shadowing any earlier bindings with the same names.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Binding {
+    pub pattern: Pattern,
+    /// If this is present, `expr` has to be of this type.
+    pub annotation: Option<Type>,
+    pub expr: Expression,
+}
@@ -0,0 +1,28 @@
+use crate::{
+    combinators::OrNext,
+    expression::{expression, Expression},
+    parse_error::ParseError,
+    pointer::Pointer,
+    statement::{
+        binding::{binding, Binding},
+        type_declaration::TypeDeclaration,
+    },
+};
+
+pub(crate) mod binding;
+pub(crate) mod type_declaration;
+pub(crate) mod with;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Statement {
+    Binding(Binding),
+    Expression(Expression),
+    /// Only allowed at the top level of a module.
+    TypeDeclaration(TypeDeclaration),
+}
+
+pub(crate) fn statement(i: &str, ptr: Pointer) -> Result<(&str, Pointer, Statement), ParseError> {
+    binding(i, ptr)
+        .map(|res| (res.0, res.1, Statement::Binding(res.2)))
+        .or_next(|| expression(i, ptr).map(|res| (res.0, res.1, Statement::Expression(res.2))))
+}
@@ -0,0 +1,50 @@
+use crate::{
+    combinators::optionally,
+    parse_error::ParseError,
+    pointer::{Pointer, Span},
+    spanned_ident,
+    tokens::{arrow_left, keyword_type},
+    types::{type_parameters, yaupl_type, Type},
+    Identifier,
+};
+
+/// ```yaupl
+/// type Id <- num
+/// type Point <- |- x: num, y: num -|
+/// type Tree <- |- value: num, children: Tree@ -|
+/// type Pair<T> <- [| T, T |]
+/// ```
+///
+/// Names a type, which can then be used anywhere a type can be written. Type declarations can
+/// only be at the top level of a module, and they can all be used before the point they're
+/// declared at, so they can refer to themselves and each other.
+pub(crate) fn type_declaration(
+    i: &str,
+    ptr: Pointer,
+) -> Result<(&str, Pointer, TypeDeclaration), ParseError> {
+    let (i, ptr, _type) = keyword_type(i, ptr)?;
+    let (i, ptr, (name, span)) = spanned_ident(i, ptr)?;
+    let (i, ptr, parameters) = optionally(i, ptr, &type_parameters);
+    let parameters = parameters.unwrap_or_default();
+    let (i, ptr, _arrow) = arrow_left(i, ptr)?;
+    let (i, ptr, definition) = yaupl_type(i, ptr)?;
+    Ok((
+        i,
+        ptr,
+        TypeDeclaration {
+            name,
+            span,
+            definition: definition.with_variables(&parameters),
+            parameters,
+        },
+    ))
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct TypeDeclaration {
+    pub name: Identifier,
+    /// Where the name was written.
+    pub span: Span,
+    pub parameters: Vec<Identifier>,
+    pub definition: Type,
+}
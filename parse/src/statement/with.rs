@@ -0,0 +1,74 @@
+use std::path::PathBuf;
+
+use crate::{
+    parse_error::ParseError,
+    pointer::{Pointer, Span},
+    spanned_ident,
+    tokens::{keyword_as, keyword_with},
+    whitespace::whitespace,
+    Identifier,
+};
+
+/// ```yaupl
+/// with maths.yaupl as maths
+/// with ../shared/strings.yaupl as strings
+/// ```
+///
+/// Imports another module, making everything it binds at the top level available as fields of
+/// `alias`. The path is relative to the importing file, or to one of the loader's search paths.
+#[derive(Debug, Clone, PartialEq)]
+pub struct With {
+    pub path: PathBuf,
+    pub alias: Identifier,
+    /// Where the alias was written.
+    pub span: Span,
+}
+
+pub(crate) fn with(i: &str, ptr: Pointer) -> Result<(&str, Pointer, With), ParseError> {
+    let (i, ptr, _with) = keyword_with(i, ptr)?;
+    let (i, ptr, path) = module_path(i, ptr)?;
+    let (i, ptr, _as) = keyword_as(i, ptr)?;
+    let (i, ptr, (alias, span)) = spanned_ident(i, ptr)?;
+    Ok((i, ptr, With { path, alias, span }))
+}
+
+/// `/` separated segments of letters, digits, `_`, `-` and `.`, ending in `.yaupl`.
+fn module_path(i: &str, ptr: Pointer) -> Result<(&str, Pointer, PathBuf), ParseError> {
+    let (i, ptr) = whitespace(i, ptr);
+    let end_location = i
+        .find(|ch: char| !(ch.is_ascii_alphanumeric() || "_-./".contains(ch)))
+        .unwrap_or(i.len());
+    let path = &i[..end_location];
+    let file_name = path.rsplit('/').next().unwrap_or(path);
+    if !file_name.ends_with(".yaupl") || file_name == ".yaupl" {
+        return Err(ParseError::InvalidModulePath);
+    }
+    Ok((
+        &i[end_location..],
+        ptr.add_col(end_location),
+        PathBuf::from(path),
+    ))
+}
+
+#[cfg(test)]
+mod test_with {
+    use super::*;
+
+    #[test]
+    fn test_with() {
+        let (i, _, found) = with("with ../lib/maths.yaupl as m\nm", Pointer::new(0, 0)).unwrap();
+        assert_eq!(i, "\nm");
+        assert_eq!(
+            found,
+            With {
+                path: PathBuf::from("../lib/maths.yaupl"),
+                alias: Identifier("m".into()),
+                span: Span::new(Pointer::new(0, 27), Pointer::new(0, 28)),
+            }
+        );
+        assert!(matches!(
+            with("with maths as m", Pointer::new(0, 0)),
+            Err(ParseError::InvalidModulePath)
+        ));
+    }
+}
@@ -1,15 +1,27 @@
 // use crate::parse::{pointer::Pointer, ParseError, types::primitive::PrimitiveType};
 
-use crate::{parse_error::ParseError, pointer::Pointer, types::primitive::PrimitiveType};
+use crate::{
+    parse_error::ParseError,
+    pointer::Pointer,
+    types::primitive::{IntType, PrimitiveType},
+};
 
 use super::whitespace::*;
 pub(crate) mod token;
 use token::*;
+
+/// Checks that `i` starts with `keyword`, and that the keyword isn't just the start of a longer
+/// identifier (`true` is a keyword, `trueish` isn't).
+fn starts_with_keyword(i: &str, keyword: &str) -> bool {
+    i.starts_with(keyword)
+        && !i[keyword.len()..].starts_with(|ch: char| ch.is_ascii_alphabetic() || ch == '_')
+}
+
 /// ```yaupl
 /// +
 /// ```
-#[derive(Debug, Default, Copy, Clone)]
-pub(crate) struct BinaryOperatorAdd;
+#[derive(Debug, Default, Copy, Clone, PartialEq)]
+pub struct BinaryOperatorAdd;
 impl Token for BinaryOperatorAdd {
     fn token(&self) -> &str {
         "+"
@@ -27,11 +39,63 @@ pub(crate) fn binary_operator_add(
     }
 }
 
+/// ```yaupl
+/// ++
+/// ```
+#[derive(Debug, Default, Copy, Clone, PartialEq)]
+pub struct BinaryOperatorConcat;
+impl Token for BinaryOperatorConcat {
+    fn token(&self) -> &str {
+        "++"
+    }
+}
+pub(crate) fn binary_operator_concat(
+    i: &str,
+    ptr: Pointer,
+) -> Result<(&str, Pointer, BinaryOperatorConcat), ParseError> {
+    let (i, ptr) = whitespace(i, ptr);
+    if i.starts_with("++") {
+        Ok((
+            &i["++".len()..],
+            ptr.add_col("++".len()),
+            BinaryOperatorConcat,
+        ))
+    } else {
+        Err(ParseError::Expected(Box::new(BinaryOperatorConcat)))
+    }
+}
+
+/// ```yaupl
+/// ..
+/// ```
+#[derive(Debug, Default, Copy, Clone, PartialEq)]
+pub struct BinaryOperatorRange;
+impl Token for BinaryOperatorRange {
+    fn token(&self) -> &str {
+        ".."
+    }
+}
+pub(crate) fn binary_operator_range(
+    i: &str,
+    ptr: Pointer,
+) -> Result<(&str, Pointer, BinaryOperatorRange), ParseError> {
+    let (i, ptr) = whitespace(i, ptr);
+    if i.starts_with("..") {
+        Ok((
+            &i["..".len()..],
+            ptr.add_col("..".len()),
+            BinaryOperatorRange,
+        ))
+    } else {
+        Err(ParseError::Expected(Box::new(BinaryOperatorRange)))
+    }
+}
+
 /// ```yaupl
 /// -
 /// ```
-#[derive(Debug, Default, Copy, Clone)]
-pub(crate) struct BinaryOperatorSub;
+#[derive(Debug, Default, Copy, Clone, PartialEq)]
+pub struct BinaryOperatorSub;
 impl Token for BinaryOperatorSub {
     fn token(&self) -> &str {
         "-"
@@ -52,8 +116,8 @@ pub(crate) fn binary_operator_sub(
 /// ```yaupl
 /// *
 /// ```
-#[derive(Debug, Default, Copy, Clone)]
-pub(crate) struct BinaryOperatorMul;
+#[derive(Debug, Default, Copy, Clone, PartialEq)]
+pub struct BinaryOperatorMul;
 impl Token for BinaryOperatorMul {
     fn token(&self) -> &str {
         "*"
@@ -74,8 +138,8 @@ pub(crate) fn binary_operator_mul(
 /// ```yaupl
 /// /
 /// ```
-#[derive(Debug, Default, Copy, Clone)]
-pub(crate) struct BinaryOperatorDiv;
+#[derive(Debug, Default, Copy, Clone, PartialEq)]
+pub struct BinaryOperatorDiv;
 impl Token for BinaryOperatorDiv {
     fn token(&self) -> &str {
         "/"
@@ -96,8 +160,8 @@ pub(crate) fn binary_operator_div(
 /// ```yaupl
 /// >
 /// ```
-#[derive(Debug, Default, Copy, Clone)]
-pub(crate) struct BinaryOperatorGt;
+#[derive(Debug, Default, Copy, Clone, PartialEq)]
+pub struct BinaryOperatorGt;
 impl Token for BinaryOperatorGt {
     fn token(&self) -> &str {
         ">"
@@ -118,8 +182,8 @@ pub(crate) fn binary_operator_gt(
 /// ```yaupl
 /// <
 /// ```
-#[derive(Debug, Default, Copy, Clone)]
-pub(crate) struct BinaryOperatorLt;
+#[derive(Debug, Default, Copy, Clone, PartialEq)]
+pub struct BinaryOperatorLt;
 impl Token for BinaryOperatorLt {
     fn token(&self) -> &str {
         "<"
@@ -140,8 +204,8 @@ pub(crate) fn binary_operator_lt(
 /// ```yaupl
 /// >=
 /// ```
-#[derive(Debug, Default, Copy, Clone)]
-pub(crate) struct BinaryOperatorGte;
+#[derive(Debug, Default, Copy, Clone, PartialEq)]
+pub struct BinaryOperatorGte;
 impl Token for BinaryOperatorGte {
     fn token(&self) -> &str {
         ">="
@@ -162,8 +226,8 @@ pub(crate) fn binary_operator_gte(
 /// ```yaupl
 /// <=
 /// ```
-#[derive(Debug, Default, Copy, Clone)]
-pub(crate) struct BinaryOperatorLte;
+#[derive(Debug, Default, Copy, Clone, PartialEq)]
+pub struct BinaryOperatorLte;
 impl Token for BinaryOperatorLte {
     fn token(&self) -> &str {
         "<="
@@ -184,8 +248,8 @@ pub(crate) fn binary_operator_lte(
 /// ```yaupl
 /// ==
 /// ```
-#[derive(Debug, Default, Copy, Clone)]
-pub(crate) struct BinaryOperatorEq;
+#[derive(Debug, Default, Copy, Clone, PartialEq)]
+pub struct BinaryOperatorEq;
 impl Token for BinaryOperatorEq {
     fn token(&self) -> &str {
         "=="
@@ -206,8 +270,8 @@ pub(crate) fn binary_operator_eq(
 /// ```yaupl
 /// !=
 /// ```
-#[derive(Debug, Default, Copy, Clone)]
-pub(crate) struct BinaryOperatorNeq;
+#[derive(Debug, Default, Copy, Clone, PartialEq)]
+pub struct BinaryOperatorNeq;
 impl Token for BinaryOperatorNeq {
     fn token(&self) -> &str {
         "!="
@@ -225,6 +289,117 @@ pub(crate) fn binary_operator_neq(
     }
 }
 
+/// ```yaupl
+/// %
+/// ```
+#[derive(Debug, Default, Copy, Clone, PartialEq)]
+pub struct BinaryOperatorRem;
+impl Token for BinaryOperatorRem {
+    fn token(&self) -> &str {
+        "%"
+    }
+}
+pub(crate) fn binary_operator_rem(
+    i: &str,
+    ptr: Pointer,
+) -> Result<(&str, Pointer, BinaryOperatorRem), ParseError> {
+    let (i, ptr) = whitespace(i, ptr);
+    if i.starts_with("%") {
+        Ok((&i["%".len()..], ptr.add_col("%".len()), BinaryOperatorRem))
+    } else {
+        Err(ParseError::Expected(Box::new(BinaryOperatorRem)))
+    }
+}
+
+/// ```yaupl
+/// &&
+/// ```
+#[derive(Debug, Default, Copy, Clone, PartialEq)]
+pub struct BinaryOperatorAnd;
+impl Token for BinaryOperatorAnd {
+    fn token(&self) -> &str {
+        "&&"
+    }
+}
+pub(crate) fn binary_operator_and(
+    i: &str,
+    ptr: Pointer,
+) -> Result<(&str, Pointer, BinaryOperatorAnd), ParseError> {
+    let (i, ptr) = whitespace(i, ptr);
+    if i.starts_with("&&") {
+        Ok((&i["&&".len()..], ptr.add_col("&&".len()), BinaryOperatorAnd))
+    } else {
+        Err(ParseError::Expected(Box::new(BinaryOperatorAnd)))
+    }
+}
+
+/// ```yaupl
+/// ||
+/// ```
+#[derive(Debug, Default, Copy, Clone, PartialEq)]
+pub struct BinaryOperatorOr;
+impl Token for BinaryOperatorOr {
+    fn token(&self) -> &str {
+        "||"
+    }
+}
+pub(crate) fn binary_operator_or(
+    i: &str,
+    ptr: Pointer,
+) -> Result<(&str, Pointer, BinaryOperatorOr), ParseError> {
+    let (i, ptr) = whitespace(i, ptr);
+    if i.starts_with("||") {
+        Ok((&i["||".len()..], ptr.add_col("||".len()), BinaryOperatorOr))
+    } else {
+        Err(ParseError::Expected(Box::new(BinaryOperatorOr)))
+    }
+}
+
+/// ```yaupl
+/// -
+/// ```
+#[derive(Debug, Default, Copy, Clone, PartialEq)]
+pub struct UnaryOperatorNeg;
+impl Token for UnaryOperatorNeg {
+    fn token(&self) -> &str {
+        "-"
+    }
+}
+pub(crate) fn unary_operator_neg(
+    i: &str,
+    ptr: Pointer,
+) -> Result<(&str, Pointer, UnaryOperatorNeg), ParseError> {
+    let (i, ptr) = whitespace(i, ptr);
+    if i.starts_with("-") {
+        Ok((&i["-".len()..], ptr.add_col("-".len()), UnaryOperatorNeg))
+    } else {
+        Err(ParseError::Expected(Box::new(UnaryOperatorNeg)))
+    }
+}
+
+/// ```yaupl
+/// !
+/// ```
+#[derive(Debug, Default, Copy, Clone, PartialEq)]
+pub struct UnaryOperatorNot;
+impl Token for UnaryOperatorNot {
+    fn token(&self) -> &str {
+        "!"
+    }
+}
+pub(crate) fn unary_operator_not(
+    i: &str,
+    ptr: Pointer,
+) -> Result<(&str, Pointer, UnaryOperatorNot), ParseError> {
+    let (i, ptr) = whitespace(i, ptr);
+    // don't eat the start of `!=` or `!!#[`
+    if i.starts_with("!") && !i.starts_with("!=") && !i.starts_with("!!") {
+        Ok((&i["!".len()..], ptr.add_col("!".len()), UnaryOperatorNot))
+    } else {
+        Err(ParseError::Expected(Box::new(UnaryOperatorNot)))
+    }
+}
+
 /// ```yaupl
 /// oo
 /// ```
@@ -237,7 +412,7 @@ impl Token for Infinity {
 }
 pub(crate) fn infinity(i: &str, ptr: Pointer) -> Result<(&str, Pointer, Infinity), ParseError> {
     let (i, ptr) = whitespace(i, ptr);
-    if i.starts_with("oo") {
+    if starts_with_keyword(i, "oo") {
         Ok((&i["oo".len()..], ptr.add_col("oo".len()), Infinity))
     } else {
         Err(ParseError::Expected(Box::new(Infinity)))
@@ -285,7 +460,7 @@ pub(crate) fn keyword_true(
     ptr: Pointer,
 ) -> Result<(&str, Pointer, KeywordTrue), ParseError> {
     let (i, ptr) = whitespace(i, ptr);
-    if i.starts_with("true") {
+    if starts_with_keyword(i, "true") {
         Ok((&i["true".len()..], ptr.add_col("true".len()), KeywordTrue))
     } else {
         Err(ParseError::Expected(Box::new(KeywordTrue)))
@@ -307,7 +482,7 @@ pub(crate) fn keyword_false(
     ptr: Pointer,
 ) -> Result<(&str, Pointer, KeywordFalse), ParseError> {
     let (i, ptr) = whitespace(i, ptr);
-    if i.starts_with("false") {
+    if starts_with_keyword(i, "false") {
         Ok((
             &i["false".len()..],
             ptr.add_col("false".len()),
@@ -707,7 +882,7 @@ pub(crate) fn keyword_export(
     ptr: Pointer,
 ) -> Result<(&str, Pointer, KeywordExport), ParseError> {
     let (i, ptr) = whitespace(i, ptr);
-    if i.starts_with("export") {
+    if starts_with_keyword(i, "export") {
         Ok((
             &i["export".len()..],
             ptr.add_col("export".len()),
@@ -733,7 +908,7 @@ pub(crate) fn keyword_return(
     ptr: Pointer,
 ) -> Result<(&str, Pointer, KeywordReturn), ParseError> {
     let (i, ptr) = whitespace(i, ptr);
-    if i.starts_with("return") {
+    if starts_with_keyword(i, "return") {
         Ok((
             &i["return".len()..],
             ptr.add_col("return".len()),
@@ -759,13 +934,132 @@ pub(crate) fn keyword_with(
     ptr: Pointer,
 ) -> Result<(&str, Pointer, KeywordWith), ParseError> {
     let (i, ptr) = whitespace(i, ptr);
-    if i.starts_with("with") {
+    if starts_with_keyword(i, "with") {
         Ok((&i["with".len()..], ptr.add_col("with".len()), KeywordWith))
     } else {
         Err(ParseError::Expected(Box::new(KeywordWith)))
     }
 }
 
+/// ```yaupl
+/// if
+/// ```
+#[derive(Debug, Default, Copy, Clone)]
+pub(crate) struct KeywordIf;
+impl Token for KeywordIf {
+    fn token(&self) -> &str {
+        "if"
+    }
+}
+pub(crate) fn keyword_if(i: &str, ptr: Pointer) -> Result<(&str, Pointer, KeywordIf), ParseError> {
+    let (i, ptr) = whitespace(i, ptr);
+    if starts_with_keyword(i, "if") {
+        Ok((&i["if".len()..], ptr.add_col("if".len()), KeywordIf))
+    } else {
+        Err(ParseError::Expected(Box::new(KeywordIf)))
+    }
+}
+
+/// ```yaupl
+/// match
+/// ```
+#[derive(Debug, Default, Copy, Clone)]
+pub(crate) struct KeywordMatch;
+impl Token for KeywordMatch {
+    fn token(&self) -> &str {
+        "match"
+    }
+}
+pub(crate) fn keyword_match(
+    i: &str,
+    ptr: Pointer,
+) -> Result<(&str, Pointer, KeywordMatch), ParseError> {
+    let (i, ptr) = whitespace(i, ptr);
+    if starts_with_keyword(i, "match") {
+        Ok((
+            &i["match".len()..],
+            ptr.add_col("match".len()),
+            KeywordMatch,
+        ))
+    } else {
+        Err(ParseError::Expected(Box::new(KeywordMatch)))
+    }
+}
+
+/// ```yaupl
+/// for
+/// ```
+#[derive(Debug, Default, Copy, Clone)]
+pub(crate) struct KeywordFor;
+impl Token for KeywordFor {
+    fn token(&self) -> &str {
+        "for"
+    }
+}
+pub(crate) fn keyword_for(
+    i: &str,
+    ptr: Pointer,
+) -> Result<(&str, Pointer, KeywordFor), ParseError> {
+    let (i, ptr) = whitespace(i, ptr);
+    if starts_with_keyword(i, "for") {
+        Ok((&i["for".len()..], ptr.add_col("for".len()), KeywordFor))
+    } else {
+        Err(ParseError::Expected(Box::new(KeywordFor)))
+    }
+}
+
+/// ```yaupl
+/// break
+/// ```
+#[derive(Debug, Default, Copy, Clone)]
+pub(crate) struct KeywordBreak;
+impl Token for KeywordBreak {
+    fn token(&self) -> &str {
+        "break"
+    }
+}
+pub(crate) fn keyword_break(
+    i: &str,
+    ptr: Pointer,
+) -> Result<(&str, Pointer, KeywordBreak), ParseError> {
+    let (i, ptr) = whitespace(i, ptr);
+    if starts_with_keyword(i, "break") {
+        Ok((
+            &i["break".len()..],
+            ptr.add_col("break".len()),
+            KeywordBreak,
+        ))
+    } else {
+        Err(ParseError::Expected(Box::new(KeywordBreak)))
+    }
+}
+
+/// ```yaupl
+/// continue
+/// ```
+#[derive(Debug, Default, Copy, Clone)]
+pub(crate) struct KeywordContinue;
+impl Token for KeywordContinue {
+    fn token(&self) -> &str {
+        "continue"
+    }
+}
+pub(crate) fn keyword_continue(
+    i: &str,
+    ptr: Pointer,
+) -> Result<(&str, Pointer, KeywordContinue), ParseError> {
+    let (i, ptr) = whitespace(i, ptr);
+    if starts_with_keyword(i, "continue") {
+        Ok((
+            &i["continue".len()..],
+            ptr.add_col("continue".len()),
+            KeywordContinue,
+        ))
+    } else {
+        Err(ParseError::Expected(Box::new(KeywordContinue)))
+    }
+}
+
 /// ```yaupl
 /// as
 /// ```
@@ -778,13 +1072,222 @@ impl Token for KeywordAs {
 }
 pub(crate) fn keyword_as(i: &str, ptr: Pointer) -> Result<(&str, Pointer, KeywordAs), ParseError> {
     let (i, ptr) = whitespace(i, ptr);
-    if i.starts_with("as") {
+    if starts_with_keyword(i, "as") {
         Ok((&i["as".len()..], ptr.add_col("as".len()), KeywordAs))
     } else {
         Err(ParseError::Expected(Box::new(KeywordAs)))
     }
 }
 
+/// ```yaupl
+/// type
+/// ```
+#[derive(Debug, Default, Copy, Clone)]
+pub(crate) struct KeywordType;
+impl Token for KeywordType {
+    fn token(&self) -> &str {
+        "type"
+    }
+}
+pub(crate) fn keyword_type(
+    i: &str,
+    ptr: Pointer,
+) -> Result<(&str, Pointer, KeywordType), ParseError> {
+    let (i, ptr) = whitespace(i, ptr);
+    if starts_with_keyword(i, "type") {
+        Ok((&i["type".len()..], ptr.add_col("type".len()), KeywordType))
+    } else {
+        Err(ParseError::Expected(Box::new(KeywordType)))
+    }
+}
+
+/// ```yaupl
+/// <
+/// ```
+#[derive(Debug, Default, Copy, Clone)]
+pub(crate) struct AngleOpen;
+impl Token for AngleOpen {
+    fn token(&self) -> &str {
+        "<"
+    }
+}
+pub(crate) fn angle_open(i: &str, ptr: Pointer) -> Result<(&str, Pointer, AngleOpen), ParseError> {
+    let (i, ptr) = whitespace(i, ptr);
+    if i.starts_with("<") {
+        Ok((&i["<".len()..], ptr.add_col("<".len()), AngleOpen))
+    } else {
+        Err(ParseError::Expected(Box::new(AngleOpen)))
+    }
+}
+
+/// ```yaupl
+/// >
+/// ```
+#[derive(Debug, Default, Copy, Clone)]
+pub(crate) struct AngleClose;
+impl Token for AngleClose {
+    fn token(&self) -> &str {
+        ">"
+    }
+}
+pub(crate) fn angle_close(
+    i: &str,
+    ptr: Pointer,
+) -> Result<(&str, Pointer, AngleClose), ParseError> {
+    let (i, ptr) = whitespace(i, ptr);
+    if i.starts_with(">") {
+        Ok((&i[">".len()..], ptr.add_col(">".len()), AngleClose))
+    } else {
+        Err(ParseError::Expected(Box::new(AngleClose)))
+    }
+}
+
+/// ```yaupl
+/// <|
+/// ```
+#[derive(Debug, Default, Copy, Clone)]
+pub(crate) struct VariantOpen;
+impl Token for VariantOpen {
+    fn token(&self) -> &str {
+        "<|"
+    }
+}
+pub(crate) fn variant_open(
+    i: &str,
+    ptr: Pointer,
+) -> Result<(&str, Pointer, VariantOpen), ParseError> {
+    let (i, ptr) = whitespace(i, ptr);
+    if i.starts_with("<|") {
+        Ok((&i["<|".len()..], ptr.add_col("<|".len()), VariantOpen))
+    } else {
+        Err(ParseError::Expected(Box::new(VariantOpen)))
+    }
+}
+
+/// ```yaupl
+/// |>
+/// ```
+#[derive(Debug, Default, Copy, Clone)]
+pub(crate) struct VariantClose;
+impl Token for VariantClose {
+    fn token(&self) -> &str {
+        "|>"
+    }
+}
+pub(crate) fn variant_close(
+    i: &str,
+    ptr: Pointer,
+) -> Result<(&str, Pointer, VariantClose), ParseError> {
+    let (i, ptr) = whitespace(i, ptr);
+    if i.starts_with("|>") {
+        Ok((&i["|>".len()..], ptr.add_col("|>".len()), VariantClose))
+    } else {
+        Err(ParseError::Expected(Box::new(VariantClose)))
+    }
+}
+
+/// ```yaupl
+/// ?
+/// ```
+#[derive(Debug, Default, Copy, Clone)]
+pub(crate) struct Question;
+impl Token for Question {
+    fn token(&self) -> &str {
+        "?"
+    }
+}
+pub(crate) fn question(i: &str, ptr: Pointer) -> Result<(&str, Pointer, Question), ParseError> {
+    let (i, ptr) = whitespace(i, ptr);
+    if i.starts_with("?") {
+        Ok((&i["?".len()..], ptr.add_col("?".len()), Question))
+    } else {
+        Err(ParseError::Expected(Box::new(Question)))
+    }
+}
+
+/// ```yaupl
+/// is
+/// ```
+#[derive(Debug, Default, Copy, Clone)]
+pub(crate) struct KeywordIs;
+impl Token for KeywordIs {
+    fn token(&self) -> &str {
+        "is"
+    }
+}
+pub(crate) fn keyword_is(i: &str, ptr: Pointer) -> Result<(&str, Pointer, KeywordIs), ParseError> {
+    let (i, ptr) = whitespace(i, ptr);
+    if starts_with_keyword(i, "is") {
+        Ok((&i["is".len()..], ptr.add_col("is".len()), KeywordIs))
+    } else {
+        Err(ParseError::Expected(Box::new(KeywordIs)))
+    }
+}
+
+/// ```yaupl
+/// {|
+/// ```
+#[derive(Debug, Default, Copy, Clone)]
+pub(crate) struct MapOpen;
+impl Token for MapOpen {
+    fn token(&self) -> &str {
+        "{|"
+    }
+}
+pub(crate) fn map_open(i: &str, ptr: Pointer) -> Result<(&str, Pointer, MapOpen), ParseError> {
+    let (i, ptr) = whitespace(i, ptr);
+    if i.starts_with("{|") {
+        Ok((&i["{|".len()..], ptr.add_col("{|".len()), MapOpen))
+    } else {
+        Err(ParseError::Expected(Box::new(MapOpen)))
+    }
+}
+
+/// ```yaupl
+/// |}
+/// ```
+#[derive(Debug, Default, Copy, Clone)]
+pub(crate) struct MapClose;
+impl Token for MapClose {
+    fn token(&self) -> &str {
+        "|}"
+    }
+}
+pub(crate) fn map_close(i: &str, ptr: Pointer) -> Result<(&str, Pointer, MapClose), ParseError> {
+    let (i, ptr) = whitespace(i, ptr);
+    if i.starts_with("|}") {
+        Ok((&i["|}".len()..], ptr.add_col("|}".len()), MapClose))
+    } else {
+        Err(ParseError::Expected(Box::new(MapClose)))
+    }
+}
+
+/// ```yaupl
+/// insert
+/// ```
+#[derive(Debug, Default, Copy, Clone)]
+pub(crate) struct KeywordInsert;
+impl Token for KeywordInsert {
+    fn token(&self) -> &str {
+        "insert"
+    }
+}
+pub(crate) fn keyword_insert(
+    i: &str,
+    ptr: Pointer,
+) -> Result<(&str, Pointer, KeywordInsert), ParseError> {
+    let (i, ptr) = whitespace(i, ptr);
+    if starts_with_keyword(i, "insert") {
+        Ok((
+            &i["insert".len()..],
+            ptr.add_col("insert".len()),
+            KeywordInsert,
+        ))
+    } else {
+        Err(ParseError::Expected(Box::new(KeywordInsert)))
+    }
+}
+
 /// ```yaupl
 /// str
 /// ```
@@ -800,7 +1303,7 @@ pub(crate) fn keyword_str(
     ptr: Pointer,
 ) -> Result<(&str, Pointer, PrimitiveType), ParseError> {
     let (i, ptr) = whitespace(i, ptr);
-    if i.starts_with("str") {
+    if starts_with_keyword(i, "str") {
         Ok((
             &i["str".len()..],
             ptr.add_col("str".len()),
@@ -811,6 +1314,63 @@ pub(crate) fn keyword_str(
     }
 }
 
+/// ```yaupl
+/// i8
+/// u64
+/// ```
+///
+/// Unlike the other keywords, these can't be followed by a digit either, so that `i80` is a name.
+pub(crate) fn keyword_int(
+    i: &str,
+    ptr: Pointer,
+) -> Result<(&str, Pointer, PrimitiveType), ParseError> {
+    let (i, ptr) = whitespace(i, ptr);
+    for int in IntType::ALL {
+        let keyword = int.token();
+        if i.starts_with(keyword)
+            && !i[keyword.len()..].starts_with(|ch: char| ch.is_ascii_alphanumeric() || ch == '_')
+        {
+            return Ok((
+                &i[keyword.len()..],
+                ptr.add_col(keyword.len()),
+                PrimitiveType::Int(int),
+            ));
+        }
+    }
+    Err(ParseError::ExpectedOneOf(
+        IntType::ALL
+            .iter()
+            .map(|int| Box::new(*int) as Box<dyn Token>)
+            .collect(),
+    ))
+}
+
+/// ```yaupl
+/// byte
+/// ```
+#[derive(Debug, Default, Copy, Clone)]
+pub(crate) struct KeywordByte;
+impl Token for KeywordByte {
+    fn token(&self) -> &str {
+        "byte"
+    }
+}
+pub(crate) fn keyword_byte(
+    i: &str,
+    ptr: Pointer,
+) -> Result<(&str, Pointer, PrimitiveType), ParseError> {
+    let (i, ptr) = whitespace(i, ptr);
+    if starts_with_keyword(i, "byte") {
+        Ok((
+            &i["byte".len()..],
+            ptr.add_col("byte".len()),
+            PrimitiveType::Byte,
+        ))
+    } else {
+        Err(ParseError::Expected(Box::new(KeywordByte)))
+    }
+}
+
 /// ```yaupl
 /// bln
 /// ```
@@ -826,7 +1386,7 @@ pub(crate) fn keyword_bln(
     ptr: Pointer,
 ) -> Result<(&str, Pointer, PrimitiveType), ParseError> {
     let (i, ptr) = whitespace(i, ptr);
-    if i.starts_with("bln") {
+    if starts_with_keyword(i, "bln") {
         Ok((
             &i["bln".len()..],
             ptr.add_col("bln".len()),
@@ -852,7 +1412,7 @@ pub(crate) fn keyword_num(
     ptr: Pointer,
 ) -> Result<(&str, Pointer, PrimitiveType), ParseError> {
     let (i, ptr) = whitespace(i, ptr);
-    if i.starts_with("num") {
+    if starts_with_keyword(i, "num") {
         Ok((
             &i["num".len()..],
             ptr.add_col("num".len()),
@@ -889,6 +1449,32 @@ pub(crate) fn keyword_emp(
     }
 }
 
+/// ```yaupl
+/// nvr
+/// ```
+#[derive(Debug, Default, Copy, Clone)]
+pub(crate) struct KeywordNvr;
+impl Token for KeywordNvr {
+    fn token(&self) -> &str {
+        "nvr"
+    }
+}
+pub(crate) fn keyword_nvr(
+    i: &str,
+    ptr: Pointer,
+) -> Result<(&str, Pointer, PrimitiveType), ParseError> {
+    let (i, ptr) = whitespace(i, ptr);
+    if starts_with_keyword(i, "nvr") {
+        Ok((
+            &i["nvr".len()..],
+            ptr.add_col("nvr".len()),
+            PrimitiveType::Never,
+        ))
+    } else {
+        Err(ParseError::Expected(Box::new(KeywordNvr)))
+    }
+}
+
 /// ```yaupl
 /// :
 /// ```
@@ -908,6 +1494,44 @@ pub(crate) fn colon(i: &str, ptr: Pointer) -> Result<(&str, Pointer, Colon), Par
     }
 }
 
+/// ```yaupl
+/// ..
+/// ```
+#[derive(Debug, Default, Copy, Clone)]
+pub(crate) struct DotDot;
+impl Token for DotDot {
+    fn token(&self) -> &str {
+        ".."
+    }
+}
+pub(crate) fn dot_dot(i: &str, ptr: Pointer) -> Result<(&str, Pointer, DotDot), ParseError> {
+    let (i, ptr) = whitespace(i, ptr);
+    if i.starts_with("..") {
+        Ok((&i["..".len()..], ptr.add_col("..".len()), DotDot))
+    } else {
+        Err(ParseError::Expected(Box::new(DotDot)))
+    }
+}
+
+/// ```yaupl
+/// .
+/// ```
+#[derive(Debug, Default, Copy, Clone)]
+pub(crate) struct Dot;
+impl Token for Dot {
+    fn token(&self) -> &str {
+        "."
+    }
+}
+pub(crate) fn dot(i: &str, ptr: Pointer) -> Result<(&str, Pointer, Dot), ParseError> {
+    let (i, ptr) = whitespace(i, ptr);
+    if i.starts_with(".") {
+        Ok((&i[".".len()..], ptr.add_col(".".len()), Dot))
+    } else {
+        Err(ParseError::Expected(Box::new(Dot)))
+    }
+}
+
 /// ```yaupl
 /// @
 /// ```
@@ -947,7 +1571,7 @@ pub(crate) fn comma(i: &str, ptr: Pointer) -> Result<(&str, Pointer, Comma), Par
 }
 
 /// ```yaupl
-/// ,
+/// .
 /// ```
 #[derive(Debug, Default, Copy, Clone)]
 pub(crate) struct Decimal;
@@ -958,7 +1582,7 @@ impl Token for Decimal {
 }
 pub(crate) fn decimal(i: &str, ptr: Pointer) -> Result<(&str, Pointer, Decimal), ParseError> {
     let (i, ptr) = whitespace(i, ptr);
-    if i.starts_with(",") {
+    if i.starts_with(".") {
         Ok((&i[".".len()..], ptr.add_col(".".len()), Decimal))
     } else {
         Err(ParseError::Expected(Box::new(Decimal)))
@@ -985,7 +1609,7 @@ pub(crate) fn semicolon(i: &str, ptr: Pointer) -> Result<(&str, Pointer, Semicol
 }
 
 /// ```yaupl
-/// ;
+/// "
 /// ```
 #[derive(Debug, Default, Copy, Clone)]
 pub(crate) struct Quote;
@@ -996,10 +1620,28 @@ impl Token for Quote {
 }
 pub(crate) fn quote(i: &str, ptr: Pointer) -> Result<(&str, Pointer, Quote), ParseError> {
     let (i, ptr) = whitespace(i, ptr);
-    if i.starts_with(";") {
+    if i.starts_with("\"") {
         Ok((&i["\"".len()..], ptr.add_col("\"".len()), Quote))
     } else {
         Err(ParseError::Expected(Box::new(Quote)))
     }
 }
 
+/// ```yaupl
+/// _
+/// ```
+#[derive(Debug, Default, Copy, Clone)]
+pub(crate) struct Underscore;
+impl Token for Underscore {
+    fn token(&self) -> &str {
+        "_"
+    }
+}
+pub(crate) fn underscore(i: &str, ptr: Pointer) -> Result<(&str, Pointer, Underscore), ParseError> {
+    let (i, ptr) = whitespace(i, ptr);
+    if starts_with_keyword(i, "_") {
+        Ok((&i["_".len()..], ptr.add_col("_".len()), Underscore))
+    } else {
+        Err(ParseError::Expected(Box::new(Underscore)))
+    }
+}
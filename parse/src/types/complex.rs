@@ -10,8 +10,19 @@ use crate::{
 
 use super::Type;
 
-#[derive(Debug, Ord, PartialOrd, Eq, PartialEq)]
-pub struct Complex(BTreeMap<Identifier, Type>);
+#[derive(Debug, Ord, PartialOrd, Eq, PartialEq, Clone)]
+pub struct Complex(pub(crate) BTreeMap<Identifier, Type>);
+
+impl Complex {
+    /// Every field and it's type, in order of their names.
+    pub fn fields(&self) -> impl Iterator<Item = (&Identifier, &Type)> {
+        self.0.iter()
+    }
+
+    pub fn field(&self, name: &str) -> Option<&Type> {
+        self.0.get(&Identifier::from(name))
+    }
+}
 
 pub(crate) fn complex(i: &str, ptr: Pointer) -> Result<(&str, Pointer, Type), ParseError> {
     let mut map = BTreeMap::new();
@@ -0,0 +1,338 @@
+//! Finding where two types differ, so that a mismatch between two large types can be shown as
+//! just the parts of them that aren't the same.
+
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fmt,
+};
+
+use crate::{
+    check::{
+        named::{is_foldable, same, unfold},
+        Context,
+    },
+    types::{
+        interner::{Node, TypeId},
+        Type,
+    },
+    Identifier,
+};
+
+/// Every place where the found type isn't the same as the expected one.
+///
+/// Written out, it's one line per difference, like `field b -> param 2: expected num, found str`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TypeDiff(pub Vec<Difference>);
+
+/// A part of the found type that isn't the same as the part of the expected type in the same
+/// place. Either side is `None` if the other type doesn't have anything there, like a field that
+/// only one of two complex types has.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Difference {
+    pub path: Vec<Step>,
+    pub expected: Option<Type>,
+    pub found: Option<Type>,
+}
+
+/// One step into a type, from the outside in.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Step {
+    /// A field of a complex type.
+    Field(Identifier),
+    /// An element of a tuple, counted from 0 like a projection.
+    Element(usize),
+    /// The elements of a group.
+    Elements,
+    Key,
+    Value,
+    Variant(Identifier),
+    /// A parameter of a function, counted from 1.
+    Parameter(usize),
+    Return,
+    /// A type argument of a named type, counted from 1.
+    Argument(usize),
+}
+
+/// Finds the smallest parts of `found` that aren't the same as `expected`. Named types are
+/// unfolded with what they're declared as in `scope`, like [`same`] does, so a named type is
+/// compared with what it names unless both sides have the same name.
+pub(crate) fn diff(expected: &Type, found: &Type, scope: &mut Context) -> TypeDiff {
+    let (expected, found) = (scope.intern(expected), scope.intern(found));
+    let mut walk = Walk {
+        scope,
+        path: vec![],
+        assumed: vec![],
+        differences: vec![],
+    };
+    walk.walk(expected, found);
+    TypeDiff(walk.differences)
+}
+
+struct Walk<'a> {
+    scope: &'a mut Context,
+    path: Vec<Step>,
+    /// The pairs of types being unfolded further up, so that recursive types stop being
+    /// unfolded once they come round again.
+    assumed: Vec<(TypeId, TypeId)>,
+    differences: Vec<Difference>,
+}
+
+impl Walk<'_> {
+    fn walk(&mut self, expected: TypeId, found: TypeId) {
+        if same(expected, found, self.scope) {
+            return;
+        }
+        match (
+            self.scope.node(expected).clone(),
+            self.scope.node(found).clone(),
+        ) {
+            (Node::Named(name, arguments), Node::Named(found_name, found_arguments))
+                if name == found_name && arguments.len() == found_arguments.len() =>
+            {
+                for (ind, (expected, found)) in
+                    arguments.into_iter().zip(found_arguments).enumerate()
+                {
+                    self.step(Step::Argument(ind + 1), expected, found);
+                }
+            }
+            _ if is_foldable(expected, self.scope) || is_foldable(found, self.scope) => {
+                self.unfolded(expected, found)
+            }
+            (Node::Tuple(expected), Node::Tuple(found)) if expected.len() == found.len() => {
+                for (ind, (expected, found)) in expected.into_iter().zip(found).enumerate() {
+                    self.step(Step::Element(ind), expected, found);
+                }
+            }
+            (Node::Group(expected), Node::Group(found)) => {
+                self.step(Step::Elements, expected, found)
+            }
+            (Node::Map(key, value), Node::Map(found_key, found_value)) => {
+                self.step(Step::Key, key, found_key);
+                self.step(Step::Value, value, found_value);
+            }
+            (Node::Complex(expected), Node::Complex(found)) => {
+                self.fields(&expected, &found, Step::Field)
+            }
+            (Node::Union(expected), Node::Union(found)) => {
+                self.fields(&expected, &found, Step::Variant)
+            }
+            (
+                Node::Function {
+                    type_parameters,
+                    parameters,
+                    return_type,
+                },
+                Node::Function {
+                    type_parameters: found_type_parameters,
+                    parameters: found_parameters,
+                    return_type: found_return_type,
+                },
+            ) if type_parameters == found_type_parameters
+                && parameters.len() == found_parameters.len() =>
+            {
+                for (ind, (expected, found)) in
+                    parameters.into_iter().zip(found_parameters).enumerate()
+                {
+                    self.step(Step::Parameter(ind + 1), expected, found);
+                }
+                self.step(Step::Return, return_type, found_return_type);
+            }
+            _ => self.differ(Some(expected), Some(found)),
+        }
+    }
+
+    fn step(&mut self, step: Step, expected: TypeId, found: TypeId) {
+        self.path.push(step);
+        self.walk(expected, found);
+        self.path.pop();
+    }
+
+    /// Compares what the types name. If they're different all the way through, the difference is
+    /// written with the types as they were rather than what they name.
+    fn unfolded(&mut self, expected: TypeId, found: TypeId) {
+        let pair = (expected, found);
+        if self.assumed.contains(&pair) {
+            return;
+        }
+        let (unfolded_expected, unfolded_found) =
+            (unfold(expected, self.scope), unfold(found, self.scope));
+        // unknown names can't be unfolded, so there's nothing smaller to point at
+        if is_foldable(unfolded_expected, self.scope) || is_foldable(unfolded_found, self.scope) {
+            return self.differ(Some(expected), Some(found));
+        }
+        let before = self.differences.len();
+        self.assumed.push(pair);
+        self.walk(unfolded_expected, unfolded_found);
+        self.assumed.pop();
+        if let [difference] = &mut self.differences[before..] {
+            if difference.path == self.path {
+                difference.expected = Some(*self.scope.boxed(expected));
+                difference.found = Some(*self.scope.boxed(found));
+            }
+        }
+    }
+
+    /// The fields of two complex types, or the variants of two unions, which are the same if they
+    /// have the same names with the same types.
+    fn fields(
+        &mut self,
+        expected: &BTreeMap<Identifier, TypeId>,
+        found: &BTreeMap<Identifier, TypeId>,
+        step: fn(Identifier) -> Step,
+    ) {
+        let names = expected.keys().chain(found.keys()).collect::<BTreeSet<_>>();
+        for name in names {
+            self.path.push(step(name.clone()));
+            match (expected.get(name), found.get(name)) {
+                (Some(expected), Some(found)) => self.walk(*expected, *found),
+                (expected, found) => self.differ(expected.copied(), found.copied()),
+            }
+            self.path.pop();
+        }
+    }
+
+    fn differ(&mut self, expected: Option<TypeId>, found: Option<TypeId>) {
+        self.differences.push(Difference {
+            path: self.path.clone(),
+            expected: expected.map(|expected| *self.scope.boxed(expected)),
+            found: found.map(|found| *self.scope.boxed(found)),
+        });
+    }
+}
+
+impl fmt::Display for TypeDiff {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (ind, difference) in self.0.iter().enumerate() {
+            if ind > 0 {
+                writeln!(f)?;
+            }
+            write!(f, "{}", difference)?;
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for Difference {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (ind, step) in self.path.iter().enumerate() {
+            if ind > 0 {
+                write!(f, " -> ")?;
+            }
+            write!(f, "{}", step)?;
+        }
+        if !self.path.is_empty() {
+            write!(f, ": ")?;
+        }
+        match &self.expected {
+            Some(expected) => write!(f, "expected {}, ", expected)?,
+            None => write!(f, "expected nothing, ")?,
+        }
+        match &self.found {
+            Some(found) => write!(f, "found {}", found),
+            None => write!(f, "found nothing"),
+        }
+    }
+}
+
+impl fmt::Display for Step {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Step::Field(name) => write!(f, "field {}", name),
+            Step::Element(ind) => write!(f, "element {}", ind),
+            Step::Elements => write!(f, "elements"),
+            Step::Key => write!(f, "key"),
+            Step::Value => write!(f, "value"),
+            Step::Variant(name) => write!(f, "variant {}", name),
+            Step::Parameter(ind) => write!(f, "param {}", ind),
+            Step::Return => write!(f, "return"),
+            Step::Argument(ind) => write!(f, "argument {}", ind),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test_diff {
+    use crate::{
+        check::named::{declaration, type_slot},
+        types::fixtures::yaupl_type_str,
+    };
+
+    use super::*;
+
+    fn diff_str(expected: &str, found: &str) -> String {
+        let expected = yaupl_type_str(expected);
+        let found = yaupl_type_str(found);
+        diff(&expected, &found, &mut Context::new()).to_string()
+    }
+
+    #[test]
+    fn test_diff() {
+        assert_eq!(diff_str("num", "num"), "");
+        assert_eq!(diff_str("num", "str"), "expected num, found str");
+        assert_eq!(
+            diff_str(
+                "|- a: num, b: [num, num]=>bln, c: str@ -|",
+                "|- a: num, b: [num, str]=>bln, c: str@ -|",
+            ),
+            "field b -> param 2: expected num, found str"
+        );
+        assert_eq!(
+            diff_str(
+                "[| [| num, str |], map<str, num@> |]",
+                "[| [| bln, str |], map<str, str@> |]",
+            ),
+            "element 0 -> element 0: expected num, found bln\n\
+             element 1 -> value -> elements: expected num, found str"
+        );
+        assert_eq!(
+            diff_str("|- a: num, b: str -|", "|- a: num, c: str -|"),
+            "field b: expected str, found nothing\nfield c: expected nothing, found str"
+        );
+        assert_eq!(
+            diff_str("<| ok: num, err: str |>", "<| ok: num?, err: str |>"),
+            "variant ok: expected num, found num?"
+        );
+        assert_eq!(
+            diff_str("Pair<num>", "Pair<str>"),
+            "argument 1: expected num, found str"
+        );
+        // when the shapes are different there's nothing smaller to point at
+        assert_eq!(
+            diff_str("[| num, str |]", "[| num |]"),
+            "expected [| num, str |], found [| num |]"
+        );
+    }
+
+    #[test]
+    fn test_diff_named() {
+        let mut scope = Context::new();
+        for (name, definition) in &[
+            ("Shape", "|- name: str, area: [num, num]=>num -|"),
+            ("List", "<| end: emp, next: |- head: num, tail: List -| |>"),
+        ] {
+            let definition = yaupl_type_str(definition);
+            let declared = scope.intern(&declaration(&[], &definition));
+            scope.bind(type_slot(&Identifier(name.to_string())), declared);
+        }
+        let mut diff_str = |expected: &str, found: &str| {
+            let expected = yaupl_type_str(expected);
+            let found = yaupl_type_str(found);
+            diff(&expected, &found, &mut scope).to_string()
+        };
+        assert_eq!(
+            diff_str("Shape", "|- name: str, area: [num, num]=>num -|"),
+            ""
+        );
+        assert_eq!(
+            diff_str("Shape", "|- name: str, area: [num, str]=>num -|"),
+            "field area -> param 2: expected num, found str"
+        );
+        // named types that are different all the way through are written as they are
+        assert_eq!(diff_str("Shape", "num"), "expected Shape, found num");
+        assert_eq!(
+            diff_str("List", "<| end: emp, next: |- head: str, tail: List -| |>"),
+            "variant next -> field head: expected num, found str"
+        );
+        assert_eq!(diff_str("Unknown", "num"), "expected Unknown, found num");
+    }
+}
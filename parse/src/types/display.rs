@@ -0,0 +1,146 @@
+//! Writing types out the same way they're written in a program.
+
+use std::fmt;
+
+use crate::{
+    tokens::token::Token,
+    types::{
+        complex::Complex, function::Function, map::Map, operator::Operator,
+        primitive::PrimitiveType, tuple::Tuple, union::Union, Group, Named, Type,
+    },
+    Identifier,
+};
+
+impl fmt::Display for Type {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Type::Primitive(primitive) => write!(f, "{}", primitive.token()),
+            Type::Tuple(Tuple(element_types)) => {
+                write!(f, "[| ")?;
+                comma_separated(f, element_types)?;
+                write!(f, " |]")
+            }
+            Type::Group(Group(element_type)) => write!(f, "{}@", element_type),
+            Type::Complex(Complex(field_types)) if field_types.is_empty() => write!(f, "|- -|"),
+            Type::Complex(Complex(field_types)) => {
+                write!(f, "|- ")?;
+                for (ind, (name, field_type)) in field_types.iter().enumerate() {
+                    if ind > 0 {
+                        write!(f, ", ")?;
+                    }
+                    write!(f, "{}: {}", name, field_type)?;
+                }
+                write!(f, " -|")
+            }
+            Type::Map(Map { key, value }) => write!(f, "map<{}, {}>", key, value),
+            Type::Union(union) => match optional(union) {
+                Some(found) => write!(f, "{}?", found),
+                None => {
+                    write!(f, "<| ")?;
+                    for (ind, (name, variant_type)) in union.0.iter().enumerate() {
+                        if ind > 0 {
+                            write!(f, ", ")?;
+                        }
+                        match variant_type {
+                            Type::Primitive(PrimitiveType::Emp) => write!(f, "{}", name)?,
+                            variant_type => write!(f, "{}: {}", name, variant_type)?,
+                        }
+                    }
+                    write!(f, " |>")
+                }
+            },
+            Type::Operator(Operator::Merge(first, second)) => {
+                write!(f, "merge<{}, {}>", first, second)
+            }
+            Type::Operator(Operator::Pick(found, fields)) => {
+                write!(f, "pick<{}, ", found)?;
+                comma_separated(f, fields)?;
+                write!(f, ">")
+            }
+            Type::Operator(Operator::Omit(found, fields)) => {
+                write!(f, "omit<{}, ", found)?;
+                comma_separated(f, fields)?;
+                write!(f, ">")
+            }
+            Type::Operator(Operator::Partial(found)) => write!(f, "partial<{}>", found),
+            Type::Function(Function {
+                type_parameters,
+                parameters,
+                return_type,
+            }) => {
+                if !type_parameters.is_empty() {
+                    write!(f, "<")?;
+                    comma_separated(f, type_parameters)?;
+                    write!(f, ">")?;
+                }
+                write!(f, "[")?;
+                comma_separated(f, parameters)?;
+                write!(f, "]=>{}", return_type)
+            }
+            Type::Named(Named {
+                name, arguments, ..
+            }) => {
+                write!(f, "{}", name)?;
+                if !arguments.is_empty() {
+                    write!(f, "<")?;
+                    comma_separated(f, arguments)?;
+                    write!(f, ">")?;
+                }
+                Ok(())
+            }
+            Type::Variable(name) => write!(f, "{}", name),
+        }
+    }
+}
+
+impl fmt::Display for Identifier {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.token())
+    }
+}
+
+/// What a union carries in it's `some` variant, if it's one that can be written as `T?`.
+fn optional(union: &Union) -> Option<&Type> {
+    match union.0.get(&Identifier("some".into())) {
+        Some(found) if *union == Union::optional(found.clone()) => Some(found),
+        _ => None,
+    }
+}
+
+fn comma_separated(f: &mut fmt::Formatter<'_>, items: &[impl fmt::Display]) -> fmt::Result {
+    for (ind, item) in items.iter().enumerate() {
+        if ind > 0 {
+            write!(f, ", ")?;
+        }
+        write!(f, "{}", item)?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod test_display {
+    use crate::{pointer::Pointer, types::yaupl_type};
+
+    /// Every type is written out the same way it was parsed.
+    #[test]
+    fn test_round_trip() {
+        for written in &[
+            "num",
+            "u8",
+            "[| str, bln@ |]",
+            "|- a: num, b: [num, bln]=>___ -|",
+            "|- -|",
+            "map<str, num@>",
+            "<| err: str, ok: num, pending |>",
+            "num?",
+            "merge<pick<User, name, email>, |- id: num -|>",
+            "omit<partial<User>, password>",
+            "<T>[T@, [T]=>bln]=>T@",
+            "shapes.Point",
+            "Pair<num>",
+        ] {
+            let (_, _, found) = yaupl_type(written, Pointer::new(0, 0)).unwrap();
+            assert_eq!(found.to_string(), *written);
+        }
+    }
+}
@@ -1,20 +1,44 @@
 use crate::{
-    combinators::csv,
+    combinators::{csv, optionally},
     parse_error::ParseError,
     pointer::Pointer,
     tokens::{arrow_right_thick, brace_square_close, brace_square_open},
-    types::{yaupl_type, Type},
+    types::{type_parameters, yaupl_type, Type},
+    Identifier,
 };
 
-#[derive(Debug, Ord, PartialOrd, Eq, PartialEq)]
+#[derive(Debug, Ord, PartialOrd, Eq, PartialEq, Clone)]
 pub struct Function {
-    parameters: Vec<Type>,
-    return_type: Box<Type>,
+    /// Empty unless the function is generic.
+    pub(crate) type_parameters: Vec<Identifier>,
+    pub(crate) parameters: Vec<Type>,
+    pub(crate) return_type: Box<Type>,
+}
+
+impl Function {
+    pub fn type_parameters(&self) -> &[Identifier] {
+        &self.type_parameters
+    }
+
+    pub fn parameters(&self) -> &[Type] {
+        &self.parameters
+    }
+
+    pub fn return_type(&self) -> &Type {
+        &self.return_type
+    }
 }
 
 /// REFACTOR
 /// TODO: can't rely on the tuple function anymore since the tuples have a different syntax (`[| |]` vs `[ ]`)
+///
+/// ```yaupl
+/// [num, num]=>num
+/// <T>[T@, [T]=>bln]=>T@
+/// ```
 pub(crate) fn function(i: &str, ptr: Pointer) -> Result<(&str, Pointer, Type), ParseError> {
+    let (i, ptr, type_parameters) = optionally(i, ptr, &type_parameters);
+    let type_parameters = type_parameters.unwrap_or_default();
     let (i, ptr, _bracket_open) = brace_square_open(i, ptr)?;
     let (i, ptr, parameters) = csv(i, ptr, &yaupl_type)?;
     let (i, ptr, _bracket_close) = brace_square_close(i, ptr)?;
@@ -27,8 +51,12 @@ pub(crate) fn function(i: &str, ptr: Pointer) -> Result<(&str, Pointer, Type), P
         i,
         ptr,
         Type::Function(Function {
-            parameters,
-            return_type: Box::new(return_type),
+            parameters: parameters
+                .iter()
+                .map(|parameter| parameter.with_variables(&type_parameters))
+                .collect(),
+            return_type: Box::new(return_type.with_variables(&type_parameters)),
+            type_parameters,
         }),
     ))
 }
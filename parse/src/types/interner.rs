@@ -0,0 +1,332 @@
+//! Hash-consing types, so that each distinct type is only stored once and can be compared by id.
+//!
+//! The checker works on [`TypeId`]s, looking at one [`Node`] of a type at a time and only building
+//! a whole [`Type`] back up for errors and for what it returns, so comparing two types that are
+//! exactly the same is just comparing their ids.
+
+use std::collections::{BTreeMap, HashMap};
+
+use crate::{
+    pointer::Span,
+    types::{
+        complex::Complex, function::Function, map::Map, operator::Operator,
+        primitive::PrimitiveType, tuple::Tuple, union::Union, Group, Named, Type,
+    },
+    Identifier,
+};
+
+/// A type that's been interned. Two ids from the same [`TypeInterner`] are equal exactly when the
+/// types they were made from are.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct TypeId(u32);
+
+/// One level of a type, with the types inside it already interned. Named types don't keep their
+/// span, like they're not compared by it.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub(crate) enum Node {
+    Primitive(PrimitiveType),
+    Tuple(Vec<TypeId>),
+    Group(TypeId),
+    Complex(BTreeMap<Identifier, TypeId>),
+    Map(TypeId, TypeId),
+    Union(BTreeMap<Identifier, TypeId>),
+    Merge(TypeId, TypeId),
+    Pick(TypeId, Vec<Identifier>),
+    Omit(TypeId, Vec<Identifier>),
+    Partial(TypeId),
+    Function {
+        type_parameters: Vec<Identifier>,
+        parameters: Vec<TypeId>,
+        return_type: TypeId,
+    },
+    Named(Identifier, Vec<TypeId>),
+    Variable(Identifier),
+}
+
+impl Node {
+    /// The types directly inside this one.
+    pub(crate) fn parts(&self) -> Vec<TypeId> {
+        match self {
+            Node::Primitive(_) | Node::Variable(_) => vec![],
+            Node::Tuple(parts) | Node::Named(_, parts) => parts.clone(),
+            Node::Group(part) | Node::Pick(part, _) | Node::Omit(part, _) | Node::Partial(part) => {
+                vec![*part]
+            }
+            Node::Complex(parts) | Node::Union(parts) => parts.values().copied().collect(),
+            Node::Map(first, second) | Node::Merge(first, second) => vec![*first, *second],
+            Node::Function {
+                parameters,
+                return_type,
+                ..
+            } => parameters
+                .iter()
+                .chain(Some(return_type))
+                .copied()
+                .collect(),
+        }
+    }
+
+    /// Whether this is a type operator, which [`unfold`](crate::check::named::unfold) evaluates.
+    pub(crate) fn is_operator(&self) -> bool {
+        matches!(
+            self,
+            Node::Merge(..) | Node::Pick(..) | Node::Omit(..) | Node::Partial(_)
+        )
+    }
+
+    /// The same node, with `f` applied to each of the types directly inside it.
+    pub(crate) fn map(&self, mut f: impl FnMut(TypeId) -> TypeId) -> Node {
+        let mut map_all = |parts: &[TypeId]| parts.iter().map(|part| f(*part)).collect();
+        match self {
+            Node::Primitive(_) | Node::Variable(_) => self.clone(),
+            Node::Tuple(parts) => Node::Tuple(map_all(parts)),
+            Node::Named(name, parts) => Node::Named(name.clone(), map_all(parts)),
+            Node::Group(part) => Node::Group(f(*part)),
+            Node::Pick(part, names) => Node::Pick(f(*part), names.clone()),
+            Node::Omit(part, names) => Node::Omit(f(*part), names.clone()),
+            Node::Partial(part) => Node::Partial(f(*part)),
+            Node::Complex(parts) => Node::Complex(
+                parts
+                    .iter()
+                    .map(|(name, part)| (name.clone(), f(*part)))
+                    .collect(),
+            ),
+            Node::Union(parts) => Node::Union(
+                parts
+                    .iter()
+                    .map(|(name, part)| (name.clone(), f(*part)))
+                    .collect(),
+            ),
+            Node::Map(first, second) => Node::Map(f(*first), f(*second)),
+            Node::Merge(first, second) => Node::Merge(f(*first), f(*second)),
+            Node::Function {
+                type_parameters,
+                parameters,
+                return_type,
+            } => Node::Function {
+                type_parameters: type_parameters.clone(),
+                parameters: map_all(parameters),
+                return_type: f(*return_type),
+            },
+        }
+    }
+}
+
+/// Every type that's been interned, each stored once no matter how many times it's interned or
+/// how many other types it's part of.
+#[derive(Debug, Default)]
+pub struct TypeInterner {
+    nodes: Vec<Node>,
+    /// Whether there are any type variables in each node, so that replacing them can skip the
+    /// types that don't have any.
+    variables: Vec<bool>,
+    ids: HashMap<Node, TypeId>,
+}
+
+impl TypeInterner {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The id of `found`, which is the same id as every other type that's the same as it.
+    pub fn intern(&mut self, found: &Type) -> TypeId {
+        let node = match found {
+            Type::Primitive(primitive) => Node::Primitive(primitive.clone()),
+            Type::Tuple(Tuple(element_types)) => Node::Tuple(self.intern_all(element_types)),
+            Type::Group(Group(element_type)) => Node::Group(self.intern(element_type)),
+            Type::Complex(Complex(field_types)) => Node::Complex(
+                field_types
+                    .iter()
+                    .map(|(name, field_type)| (name.clone(), self.intern(field_type)))
+                    .collect(),
+            ),
+            Type::Map(Map { key, value }) => Node::Map(self.intern(key), self.intern(value)),
+            Type::Union(Union(variant_types)) => Node::Union(
+                variant_types
+                    .iter()
+                    .map(|(name, variant_type)| (name.clone(), self.intern(variant_type)))
+                    .collect(),
+            ),
+            Type::Operator(Operator::Merge(first, second)) => {
+                Node::Merge(self.intern(first), self.intern(second))
+            }
+            Type::Operator(Operator::Pick(found, fields)) => {
+                Node::Pick(self.intern(found), fields.clone())
+            }
+            Type::Operator(Operator::Omit(found, fields)) => {
+                Node::Omit(self.intern(found), fields.clone())
+            }
+            Type::Operator(Operator::Partial(found)) => Node::Partial(self.intern(found)),
+            Type::Function(function) => Node::Function {
+                type_parameters: function.type_parameters.clone(),
+                parameters: self.intern_all(&function.parameters),
+                return_type: self.intern(&function.return_type),
+            },
+            Type::Named(Named {
+                name, arguments, ..
+            }) => Node::Named(name.clone(), self.intern_all(arguments)),
+            Type::Variable(name) => Node::Variable(name.clone()),
+        };
+        self.insert(node)
+    }
+
+    fn intern_all(&mut self, found: &[Type]) -> Vec<TypeId> {
+        found.iter().map(|found| self.intern(found)).collect()
+    }
+
+    /// The id of the type made of `node`, like [`TypeInterner::intern`] but one level at a time.
+    pub(crate) fn insert(&mut self, node: Node) -> TypeId {
+        if let Some(id) = self.ids.get(&node) {
+            return *id;
+        }
+        let id = TypeId(self.nodes.len() as u32);
+        let variables = matches!(node, Node::Variable(_))
+            || node
+                .parts()
+                .iter()
+                .any(|part| self.variables[part.0 as usize]);
+        self.nodes.push(node.clone());
+        self.variables.push(variables);
+        self.ids.insert(node, id);
+        id
+    }
+
+    pub(crate) fn primitive(&mut self, primitive: PrimitiveType) -> TypeId {
+        self.insert(Node::Primitive(primitive))
+    }
+
+    /// The top level of the type.
+    pub(crate) fn node(&self, id: TypeId) -> &Node {
+        &self.nodes[id.0 as usize]
+    }
+
+    /// Whether there are any type variables anywhere in the type.
+    pub(crate) fn has_variables(&self, id: TypeId) -> bool {
+        self.variables[id.0 as usize]
+    }
+
+    /// Rebuilds the type with every part of it that `replace` returns something for replaced by
+    /// what it returns, like [`Type::replace`]. The replacements aren't looked inside of.
+    pub(crate) fn replace(
+        &mut self,
+        id: TypeId,
+        replace: &mut dyn FnMut(&mut Self, TypeId) -> Option<TypeId>,
+    ) -> TypeId {
+        if let Some(found) = replace(self, id) {
+            return found;
+        }
+        let node = self.node(id).clone();
+        let replaced = node.map(|part| self.replace(part, replace));
+        self.insert(replaced)
+    }
+
+    /// Builds the type that `id` was interned from back up.
+    ///
+    /// # Panics
+    ///
+    /// If `id` is from a different interner.
+    pub fn get(&self, id: TypeId) -> Type {
+        let get_all = |ids: &[TypeId]| ids.iter().map(|id| self.get(*id)).collect();
+        let get_box = |id: &TypeId| Box::new(self.get(*id));
+        match &self.nodes[id.0 as usize] {
+            Node::Primitive(primitive) => Type::Primitive(primitive.clone()),
+            Node::Tuple(element_types) => Type::Tuple(Tuple(get_all(element_types))),
+            Node::Group(element_type) => Type::Group(Group(get_box(element_type))),
+            Node::Complex(field_types) => Type::Complex(Complex(
+                field_types
+                    .iter()
+                    .map(|(name, field_type)| (name.clone(), self.get(*field_type)))
+                    .collect(),
+            )),
+            Node::Map(key, value) => Type::Map(Map {
+                key: get_box(key),
+                value: get_box(value),
+            }),
+            Node::Union(variant_types) => Type::Union(Union(
+                variant_types
+                    .iter()
+                    .map(|(name, variant_type)| (name.clone(), self.get(*variant_type)))
+                    .collect(),
+            )),
+            Node::Merge(first, second) => {
+                Type::Operator(Operator::Merge(get_box(first), get_box(second)))
+            }
+            Node::Pick(found, fields) => {
+                Type::Operator(Operator::Pick(get_box(found), fields.clone()))
+            }
+            Node::Omit(found, fields) => {
+                Type::Operator(Operator::Omit(get_box(found), fields.clone()))
+            }
+            Node::Partial(found) => Type::Operator(Operator::Partial(get_box(found))),
+            Node::Function {
+                type_parameters,
+                parameters,
+                return_type,
+            } => Type::Function(Function {
+                type_parameters: type_parameters.clone(),
+                parameters: get_all(parameters),
+                return_type: get_box(return_type),
+            }),
+            Node::Named(name, arguments) => Type::Named(Named {
+                name: name.clone(),
+                arguments: get_all(arguments),
+                span: Span::default(),
+            }),
+            Node::Variable(name) => Type::Variable(name.clone()),
+        }
+    }
+
+    /// Writes the type out the same way it would be written in a program.
+    pub fn render(&self, id: TypeId) -> String {
+        self.get(id).to_string()
+    }
+
+    /// How many distinct types, including the types inside of them, have been interned.
+    pub fn len(&self) -> usize {
+        self.nodes.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.nodes.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod test_interner {
+    use crate::types::fixtures::yaupl_type_str;
+
+    use super::*;
+
+    #[test]
+    fn test_interning() {
+        let mut interner = TypeInterner::new();
+        let first = interner.intern(&yaupl_type_str("|- a: [| num, str |], b: num@ -|"));
+        // `num` is only stored once, even though it's in two places
+        assert_eq!(interner.len(), 5);
+        let second = interner.intern(&yaupl_type_str("|- b: num@, a: [| num, str |] -|"));
+        assert_eq!(first, second);
+        assert_eq!(interner.len(), 5);
+
+        let other = interner.intern(&yaupl_type_str("|- a: [| num, str |], b: str@ -|"));
+        assert_ne!(first, other);
+        // only the parts that are different are added
+        assert_eq!(interner.len(), 7);
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let mut interner = TypeInterner::new();
+        for written in &[
+            "<| err: str, ok: map<str, num@> |>",
+            "<T>[T@, [T]=>bln]=>T@",
+            "merge<pick<User, name>, partial<Pair<num>>>",
+            "omit<shapes.Point, x>",
+            "u8?",
+        ] {
+            let found = yaupl_type_str(written);
+            let id = interner.intern(&found);
+            assert_eq!(interner.get(id), found);
+            assert_eq!(interner.render(id), *written);
+        }
+    }
+}
@@ -0,0 +1,68 @@
+use crate::{
+    ident,
+    parse_error::ParseError,
+    pointer::Pointer,
+    tokens::{angle_close, angle_open, comma},
+    Identifier,
+};
+
+use super::{primitive::PrimitiveType, yaupl_type, Type};
+
+/// A collection of values that are looked up by keys, which are all of one type, and can be added
+/// to while the program runs, unlike the fields of a [`Complex`](super::complex::Complex).
+#[derive(Debug, Ord, PartialOrd, Eq, PartialEq, Clone)]
+pub struct Map {
+    pub(crate) key: Box<Type>,
+    pub(crate) value: Box<Type>,
+}
+
+impl Map {
+    pub fn key(&self) -> &Type {
+        &self.key
+    }
+
+    pub fn value(&self) -> &Type {
+        &self.value
+    }
+
+    /// Whether values of the type can be used as keys, which have to be compared with each
+    /// other.
+    pub(crate) fn is_key(found: &Type) -> bool {
+        matches!(
+            found,
+            Type::Primitive(
+                PrimitiveType::Str
+                    | PrimitiveType::Num
+                    | PrimitiveType::Int(_)
+                    | PrimitiveType::Byte
+                    | PrimitiveType::Bln
+            )
+        )
+    }
+}
+
+/// ```yaupl
+/// map<str, num>
+/// ```
+///
+/// Like the type operators, there can't be any whitespace before the `<`, and `map` can't be
+/// used as the name of a declared type that takes type arguments.
+pub(crate) fn map(i: &str, ptr: Pointer) -> Result<(&str, Pointer, Type), ParseError> {
+    let (i, ptr, Identifier(name)) = ident(i, ptr)?;
+    if name != "map" || !i.starts_with('<') {
+        return Err(ParseError::None);
+    }
+    let (i, ptr, _angle_open) = angle_open(i, ptr)?;
+    let (i, ptr, key) = yaupl_type(i, ptr)?;
+    let (i, ptr, _comma) = comma(i, ptr)?;
+    let (i, ptr, value) = yaupl_type(i, ptr)?;
+    let (i, ptr, _angle_close) = angle_close(i, ptr)?;
+    Ok((
+        i,
+        ptr,
+        Type::Map(Map {
+            key: Box::new(key),
+            value: Box::new(value),
+        }),
+    ))
+}
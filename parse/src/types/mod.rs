@@ -1,11 +1,32 @@
-use std::collections::BTreeMap;
+use std::convert::Infallible;
 
-use crate::{Identifier, parse_error::ParseError, pointer::Pointer, types::primitive::PrimitiveType, tokens::group, types::complex::Complex, types::function::Function, types::tuple::Tuple};
+use crate::{
+    combinators::csv,
+    ident,
+    parse_error::ParseError,
+    pointer::{Pointer, Span},
+    tokens::{angle_close, angle_open, group, question},
+    types::complex::Complex,
+    types::function::Function,
+    types::map::Map,
+    types::operator::Operator,
+    types::primitive::PrimitiveType,
+    types::tuple::Tuple,
+    types::union::Union,
+    whitespace::whitespace,
+    Identifier,
+};
 
 pub(crate) mod complex;
+pub(crate) mod diff;
+mod display;
 pub(crate) mod function;
+pub(crate) mod interner;
+pub(crate) mod map;
+pub(crate) mod operator;
 pub(crate) mod primitive;
 pub(crate) mod tuple;
+pub(crate) mod union;
 
 /// TODO: deal with types in brackets `[ ]`
 /// how many levels of brackets should be allowed?
@@ -13,7 +34,11 @@ pub(crate) fn yaupl_type(i: &str, ptr: Pointer) -> Result<(&str, Pointer, Type),
     let (i, ptr, mut res) = primitive::primitive(i, ptr)
         .or(function::function(i, ptr))
         .or(tuple::tuple(i, ptr))
-        .or(complex::complex(i, ptr))?;
+        .or(complex::complex(i, ptr))
+        .or_else(|_| union::union(i, ptr))
+        .or_else(|_| map::map(i, ptr))
+        .or_else(|_| operator::operator(i, ptr))
+        .or_else(|_| named(i, ptr))?;
 
     let (mut i, mut ptr) = (i, ptr);
     loop {
@@ -21,15 +46,229 @@ pub(crate) fn yaupl_type(i: &str, ptr: Pointer) -> Result<(&str, Pointer, Type),
             i = new_i;
             ptr = new_ptr;
             res = Type::Group(Group(Box::new(res)));
+        } else if let Ok((new_i, new_ptr, _question)) = question(i, ptr) {
+            i = new_i;
+            ptr = new_ptr;
+            res = Type::Union(Union::optional(res));
         } else {
             return Ok((i, ptr, res));
         };
     }
 }
-#[derive(Debug, Ord, PartialOrd, Eq, PartialEq)]
-pub struct Group(Box<Type>);
 
-#[derive(Debug, Ord, PartialOrd, Eq, PartialEq)]
+/// The name of a declared type, or `module.Name` for one exported by an imported module, followed
+/// by any type arguments. There can't be any whitespace before the `.` or the `<`.
+///
+/// ```yaupl
+/// Point
+/// shapes.Point
+/// Pair<num>
+/// ```
+fn named(i: &str, ptr: Pointer) -> Result<(&str, Pointer, Type), ParseError> {
+    let (i, start) = whitespace(i, ptr);
+    let (i, ptr, Identifier(name)) = ident(i, start)?;
+    let (i, ptr, name) = match i.strip_prefix('.') {
+        Some(rest) if rest.starts_with(|ch: char| ch.is_ascii_alphabetic() || ch == '_') => {
+            let (rest, rest_ptr, Identifier(field)) = ident(rest, ptr.add_col(1))?;
+            (rest, rest_ptr, Identifier(format!("{}.{}", name, field)))
+        }
+        _ => (i, ptr, Identifier(name)),
+    };
+    let span = Span::new(start, ptr);
+    let (i, ptr, arguments) = if i.starts_with('<') && !i.starts_with("<-") {
+        let (i, ptr, _angle_open) = angle_open(i, ptr)?;
+        let (i, ptr, arguments) = csv(i, ptr, &yaupl_type)?;
+        let (i, ptr, _angle_close) = angle_close(i, ptr)?;
+        (i, ptr, arguments)
+    } else {
+        (i, ptr, vec![])
+    };
+    Ok((
+        i,
+        ptr,
+        Type::Named(Named {
+            name,
+            arguments,
+            span,
+        }),
+    ))
+}
+
+/// `<T, U>`, the type parameters of a generic function, lambda or type declaration.
+pub(crate) fn type_parameters(
+    i: &str,
+    ptr: Pointer,
+) -> Result<(&str, Pointer, Vec<Identifier>), ParseError> {
+    let (i, ptr, _angle_open) = angle_open(i, ptr)?;
+    let (i, ptr, parameters) = csv(i, ptr, &ident)?;
+    let (i, ptr, _angle_close) = angle_close(i, ptr)?;
+    Ok((i, ptr, parameters))
+}
+
+/// Building types without writing them out and parsing them. Each of these makes the same type
+/// as the syntax in it's example.
+impl Type {
+    /// `[| num, str |]`
+    pub fn tuple(element_types: impl IntoIterator<Item = Type>) -> Type {
+        Type::Tuple(Tuple(element_types.into_iter().collect()))
+    }
+
+    /// `|- a: num, b: str -|`. If a name is given more than once, the last type given for it is
+    /// the one it has.
+    pub fn complex(field_types: impl IntoIterator<Item = (impl Into<Identifier>, Type)>) -> Type {
+        Type::Complex(Complex(
+            field_types
+                .into_iter()
+                .map(|(name, field_type)| (name.into(), field_type))
+                .collect(),
+        ))
+    }
+
+    /// `num@`
+    pub fn group(element_type: Type) -> Type {
+        Type::Group(Group(Box::new(element_type)))
+    }
+
+    /// `[num, num]=>bln`
+    pub fn function(parameters: impl IntoIterator<Item = Type>, return_type: Type) -> Type {
+        Type::generic_function(Vec::<Identifier>::new(), parameters, return_type)
+    }
+
+    /// `<T>[T@]=>T`, where the type parameters are used in the other types as
+    /// [`Type::variable`]s.
+    pub fn generic_function(
+        type_parameters: impl IntoIterator<Item = impl Into<Identifier>>,
+        parameters: impl IntoIterator<Item = Type>,
+        return_type: Type,
+    ) -> Type {
+        Type::Function(Function {
+            type_parameters: type_parameters.into_iter().map(Into::into).collect(),
+            parameters: parameters.into_iter().collect(),
+            return_type: Box::new(return_type),
+        })
+    }
+
+    /// `map<str, num>`
+    pub fn map(key: Type, value: Type) -> Type {
+        Type::Map(Map {
+            key: Box::new(key),
+            value: Box::new(value),
+        })
+    }
+
+    /// `<| ok: num, err: str |>`, where variants that don't carry anything carry `___`.
+    pub fn union(variant_types: impl IntoIterator<Item = (impl Into<Identifier>, Type)>) -> Type {
+        Type::Union(Union(
+            variant_types
+                .into_iter()
+                .map(|(name, variant_type)| (name.into(), variant_type))
+                .collect(),
+        ))
+    }
+
+    /// `num?`
+    pub fn optional(found: Type) -> Type {
+        Type::Union(Union::optional(found))
+    }
+
+    /// `Pair<num>`, which is only a type in a program that declares it.
+    pub fn named(name: impl Into<Identifier>, arguments: impl IntoIterator<Item = Type>) -> Type {
+        Type::Named(Named {
+            name: name.into(),
+            arguments: arguments.into_iter().collect(),
+            span: Span::default(),
+        })
+    }
+
+    /// `T`, one of the type parameters of a generic function.
+    pub fn variable(name: impl Into<Identifier>) -> Type {
+        Type::Variable(name.into())
+    }
+}
+
+impl Type {
+    /// Rebuilds the type with every part of it that `replace` returns something for replaced by
+    /// what it returns. The replacements aren't looked inside of.
+    pub(crate) fn replace(&self, replace: &dyn Fn(&Type) -> Option<Type>) -> Type {
+        if let Some(found) = replace(self) {
+            return found;
+        }
+        match self {
+            Type::Primitive(_) | Type::Variable(_) => self.clone(),
+            Type::Tuple(Tuple(element_types)) => Type::Tuple(Tuple(
+                element_types
+                    .iter()
+                    .map(|element_type| element_type.replace(replace))
+                    .collect(),
+            )),
+            Type::Group(Group(element_type)) => {
+                Type::Group(Group(Box::new(element_type.replace(replace))))
+            }
+            Type::Map(Map { key, value }) => Type::Map(Map {
+                key: Box::new(key.replace(replace)),
+                value: Box::new(value.replace(replace)),
+            }),
+            Type::Complex(Complex(field_types)) => Type::Complex(Complex(
+                field_types
+                    .iter()
+                    .map(|(name, field_type)| (name.clone(), field_type.replace(replace)))
+                    .collect(),
+            )),
+            Type::Union(Union(variant_types)) => Type::Union(Union(
+                variant_types
+                    .iter()
+                    .map(|(name, variant_type)| (name.clone(), variant_type.replace(replace)))
+                    .collect(),
+            )),
+            Type::Function(function) => Type::Function(Function {
+                type_parameters: function.type_parameters.clone(),
+                parameters: function
+                    .parameters
+                    .iter()
+                    .map(|parameter| parameter.replace(replace))
+                    .collect(),
+                return_type: Box::new(function.return_type.replace(replace)),
+            }),
+            Type::Operator(operator) => Type::Operator(
+                operator
+                    .map(|operand| Ok::<_, Infallible>(operand.replace(replace)))
+                    .unwrap_or_else(|never| match never {}),
+            ),
+            Type::Named(named) => Type::Named(Named {
+                name: named.name.clone(),
+                arguments: named
+                    .arguments
+                    .iter()
+                    .map(|argument| argument.replace(replace))
+                    .collect(),
+                span: named.span,
+            }),
+        }
+    }
+
+    /// Turns the names of `parameters` into type variables, since they're parsed as named types.
+    pub(crate) fn with_variables(&self, parameters: &[Identifier]) -> Type {
+        self.replace(&|found| match found {
+            Type::Named(Named {
+                name, arguments, ..
+            }) if arguments.is_empty() && parameters.contains(name) => {
+                Some(Type::Variable(name.clone()))
+            }
+            _ => None,
+        })
+    }
+}
+
+#[derive(Debug, Ord, PartialOrd, Eq, PartialEq, Clone)]
+pub struct Group(pub(crate) Box<Type>);
+
+impl Group {
+    pub fn element_type(&self) -> &Type {
+        &self.0
+    }
+}
+
+#[derive(Debug, Ord, PartialOrd, Eq, PartialEq, Clone)]
 pub enum Type {
     /// The basic types.
     /// ### Examples
@@ -55,10 +294,195 @@ pub enum Type {
     /// [a: str, b: bln@, c: [num, bln]=>___]
     /// ```
     Complex(Complex),
+    /// A collection of values of the second type, looked up by keys of the first. Only `str`,
+    /// `num`, `bln`, `byte` and the integer types can be keys.
+    /// ### Examples
+    /// ```yaupl
+    /// map<str, num@>
+    /// ```
+    Map(Map),
+    /// A tagged union, which is a value of the type of exactly one of it's variants, along with
+    /// which one it is. `T?` is short for `<| none, some: T |>`.
+    /// ### Examples
+    /// ```yaupl
+    /// <| ok: num, err: str |>
+    /// num?
+    /// ```
+    Union(Union),
+    /// A complex type made out of other complex types, see [`Operator`].
+    /// ### Examples
+    /// ```yaupl
+    /// merge<pick<User, name>, |- id: num -|>
+    /// ```
+    Operator(Operator),
     /// A function that takes the types of the left side of the arrow and returns the right side.
     /// ### Examples
     /// ```yaupl
     /// [num, bln]=>___
     /// ```
     Function(Function),
+    /// A reference to a type declared with `type`, which is the same as the type it names. Named
+    /// types can refer to themselves, since they're only unfolded when they're used.
+    /// ### Examples
+    /// ```yaupl
+    /// List
+    /// shapes.Point
+    /// Pair<num>
+    /// ```
+    Named(Named),
+    /// One of the type parameters of a generic function or type declaration.
+    /// ### Examples
+    /// ```yaupl
+    /// <T>[T@, [T]=>bln]=>T@
+    /// ```
+    Variable(Identifier),
+}
+
+/// Named types compare by name and arguments only, so that a type written in two places is
+/// still the same type.
+#[derive(Debug, Clone)]
+pub struct Named {
+    pub(crate) name: Identifier,
+    pub(crate) arguments: Vec<Type>,
+    /// Where the name was written, or the default span for names that weren't written out.
+    pub(crate) span: Span,
+}
+
+impl Named {
+    /// The name of the declared type, which is `module.Name` for one from an imported module.
+    pub fn name(&self) -> &Identifier {
+        &self.name
+    }
+
+    pub fn arguments(&self) -> &[Type] {
+        &self.arguments
+    }
+}
+
+impl PartialEq for Named {
+    fn eq(&self, other: &Self) -> bool {
+        self.name == other.name && self.arguments == other.arguments
+    }
+}
+
+impl Eq for Named {}
+
+impl PartialOrd for Named {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Named {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        (&self.name, &self.arguments).cmp(&(&other.name, &other.arguments))
+    }
+}
+
+/// Types for tests to be written with.
+#[cfg(test)]
+pub(crate) mod fixtures {
+    use super::*;
+
+    pub(crate) fn yaupl_type_str(i: &str) -> Type {
+        yaupl_type(i, Pointer::new(0, 0)).unwrap().2
+    }
+
+    pub(crate) fn num() -> Type {
+        Type::Primitive(PrimitiveType::Num)
+    }
+
+    pub(crate) fn str() -> Type {
+        Type::Primitive(PrimitiveType::Str)
+    }
+}
+
+#[cfg(test)]
+mod test_builders {
+    use super::{
+        fixtures::{num, str, yaupl_type_str},
+        *,
+    };
+
+    #[test]
+    fn test_constructors() {
+        assert_eq!(
+            Type::complex(vec![
+                ("id", num()),
+                ("tags", Type::group(str())),
+                (
+                    "score",
+                    Type::function(vec![num(), num()], Type::optional(num()))
+                ),
+            ]),
+            yaupl_type_str("|- id: num, tags: str@, score: [num, num]=>num? -|")
+        );
+        assert_eq!(
+            Type::generic_function(
+                vec!["T"],
+                vec![Type::tuple(vec![Type::variable("T"), str()])],
+                Type::map(str(), Type::variable("T")),
+            ),
+            yaupl_type_str("<T>[[| T, str |]]=>map<str, T>")
+        );
+        assert_eq!(
+            Type::union(vec![
+                ("ok", Type::named("Pair", vec![num()])),
+                ("err", str())
+            ]),
+            yaupl_type_str("<| ok: Pair<num>, err: str |>")
+        );
+    }
+
+    #[test]
+    fn test_accessors() {
+        let found = yaupl_type_str(
+            "|- a: <T>[T@, Pair<num>]=>map<str, T>, b: <| none, some: [| num |] |> -|",
+        );
+        let complex = match &found {
+            Type::Complex(complex) => complex,
+            _ => unreachable!(),
+        };
+        assert_eq!(
+            complex
+                .fields()
+                .map(|(name, _)| name.as_str())
+                .collect::<Vec<_>>(),
+            vec!["a", "b"]
+        );
+        let function = match complex.field("a") {
+            Some(Type::Function(function)) => function,
+            _ => unreachable!(),
+        };
+        assert_eq!(function.type_parameters(), &[Identifier::from("T")]);
+        match &function.parameters()[0] {
+            Type::Group(group) => assert_eq!(group.element_type(), &Type::variable("T")),
+            _ => unreachable!(),
+        }
+        match &function.parameters()[1] {
+            Type::Named(named) => {
+                assert_eq!(named.name().as_str(), "Pair");
+                assert_eq!(named.arguments(), &[num()]);
+            }
+            _ => unreachable!(),
+        }
+        match function.return_type() {
+            Type::Map(map) => {
+                assert_eq!(map.key(), &str());
+                assert_eq!(map.value(), &Type::variable("T"));
+            }
+            _ => unreachable!(),
+        }
+        match complex.field("b") {
+            Some(Type::Union(union)) => {
+                assert_eq!(union.variants().count(), 2);
+                match union.variant("some") {
+                    Some(Type::Tuple(tuple)) => assert_eq!(tuple.elements(), &[num()]),
+                    _ => unreachable!(),
+                }
+            }
+            _ => unreachable!(),
+        }
+        assert_eq!(complex.field("c"), None);
+    }
 }
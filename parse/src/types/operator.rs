@@ -0,0 +1,109 @@
+use crate::{
+    combinators::csv,
+    ident,
+    parse_error::ParseError,
+    pointer::Pointer,
+    tokens::{angle_close, angle_open, comma},
+    Identifier,
+};
+
+use super::{yaupl_type, Type};
+
+/// An operator that makes a complex type out of other complex types. They're evaluated when the
+/// program is checked, to the complex type they make.
+#[derive(Debug, Ord, PartialOrd, Eq, PartialEq, Clone)]
+pub enum Operator {
+    /// Every field of both types. Fields that are in both have to have the same type.
+    Merge(Box<Type>, Box<Type>),
+    /// Only the named fields, which all have to be in the type.
+    Pick(Box<Type>, Vec<Identifier>),
+    /// Every field except the named ones, which all have to be in the type.
+    Omit(Box<Type>, Vec<Identifier>),
+    /// Every field, made optional if it isn't already.
+    Partial(Box<Type>),
+}
+
+impl Operator {
+    /// The same operator, with `f` applied to each of it's operands.
+    pub(crate) fn map<E>(&self, mut f: impl FnMut(&Type) -> Result<Type, E>) -> Result<Self, E> {
+        Ok(match self {
+            Operator::Merge(first, second) => {
+                Operator::Merge(Box::new(f(first)?), Box::new(f(second)?))
+            }
+            Operator::Pick(found, fields) => Operator::Pick(Box::new(f(found)?), fields.clone()),
+            Operator::Omit(found, fields) => Operator::Omit(Box::new(f(found)?), fields.clone()),
+            Operator::Partial(found) => Operator::Partial(Box::new(f(found)?)),
+        })
+    }
+}
+
+/// ```yaupl
+/// merge<User, |- id: num -|>
+/// pick<User, name, email>
+/// omit<User, password>
+/// partial<User>
+/// ```
+///
+/// There can't be any whitespace before the `<`, and the names of the operators can't be used as
+/// the names of declared types that take type arguments.
+pub(crate) fn operator(i: &str, ptr: Pointer) -> Result<(&str, Pointer, Type), ParseError> {
+    let (i, ptr, Identifier(name)) = ident(i, ptr)?;
+    // anything else is a named type, which shouldn't have it's type arguments parsed twice
+    if !matches!(name.as_str(), "merge" | "pick" | "omit" | "partial") || !i.starts_with('<') {
+        return Err(ParseError::None);
+    }
+    let (i, ptr, _angle_open) = angle_open(i, ptr)?;
+    let (i, ptr, found) = yaupl_type(i, ptr)?;
+    let found = Box::new(found);
+    let (i, ptr, operator) = match name.as_str() {
+        "merge" => {
+            let (i, ptr, _comma) = comma(i, ptr)?;
+            let (i, ptr, second) = yaupl_type(i, ptr)?;
+            (i, ptr, Operator::Merge(found, Box::new(second)))
+        }
+        "pick" | "omit" => {
+            let (i, ptr, _comma) = comma(i, ptr)?;
+            let (i, ptr, fields) = csv(i, ptr, &ident)?;
+            if name == "pick" {
+                (i, ptr, Operator::Pick(found, fields))
+            } else {
+                (i, ptr, Operator::Omit(found, fields))
+            }
+        }
+        "partial" => (i, ptr, Operator::Partial(found)),
+        _ => unreachable!(),
+    };
+    let (i, ptr, _angle_close) = angle_close(i, ptr)?;
+    Ok((i, ptr, Type::Operator(operator)))
+}
+
+#[cfg(test)]
+mod test_operator {
+    use crate::types::{primitive::PrimitiveType, Named};
+
+    use super::*;
+
+    #[test]
+    fn test_operator() {
+        assert!(matches!(
+            operator("merge<A, B>", Pointer::new(0, 0)),
+            Ok((_, _, Type::Operator(Operator::Merge(..))))
+        ));
+        assert!(matches!(
+            operator("Pair<num>", Pointer::new(0, 0)),
+            Err(ParseError::None)
+        ));
+        // named types are only parsed once however deeply they're nested
+        let depth = 64;
+        let nested = format!("{}num{}", "Pair<".repeat(depth), ">".repeat(depth));
+        let (i, _, mut found) = yaupl_type(&nested, Pointer::new(0, 0)).unwrap();
+        assert_eq!(i, "");
+        for _ in 0..depth {
+            found = match found {
+                Type::Named(Named { mut arguments, .. }) => arguments.remove(0),
+                found => panic!("expected a named type, found {:?}", found),
+            };
+        }
+        assert_eq!(found, Type::Primitive(PrimitiveType::Num));
+    }
+}
@@ -3,20 +3,27 @@ use crate::{
     parse_error::ParseError,
     pointer::Pointer,
     tokens::{
-        keyword_bln, keyword_emp, keyword_num, keyword_str, token::Token, KeywordBln, KeywordEmp,
-        KeywordNum, KeywordStr,
+        keyword_bln, keyword_byte, keyword_emp, keyword_int, keyword_num, keyword_nvr, keyword_str,
+        token::Token, KeywordBln, KeywordByte, KeywordEmp, KeywordNum, KeywordNvr, KeywordStr,
     },
 };
 
 use super::Type;
 
 // REFACTOR: make the enum variants tuple structs containing their respective tokens
-#[derive(Debug, Ord, PartialOrd, Eq, PartialEq)]
+#[derive(Debug, Ord, PartialOrd, Eq, PartialEq, Clone, Hash)]
 pub enum PrimitiveType {
     Str,
     Bln,
     Num,
+    /// A whole number with a fixed number of bits. Arithmetic that doesn't fit is an error.
+    Int(IntType),
+    /// 8 bits of binary data, which can be compared but not used in arithmetic.
+    Byte,
     Emp,
+    /// The type of expressions that never produce a value, like `return`. It can be used
+    /// anywhere any other type is expected.
+    Never,
 }
 
 impl Token for PrimitiveType {
@@ -25,7 +32,73 @@ impl Token for PrimitiveType {
             PrimitiveType::Str => KeywordStr.token(),
             PrimitiveType::Bln => KeywordBln.token(),
             PrimitiveType::Num => KeywordNum.token(),
+            PrimitiveType::Int(int) => int.token(),
+            PrimitiveType::Byte => KeywordByte.token(),
             PrimitiveType::Emp => KeywordEmp.token(),
+            PrimitiveType::Never => KeywordNvr.token(),
+        }
+    }
+}
+
+/// The signed and unsigned fixed-width integer types.
+#[derive(Debug, Ord, PartialOrd, Eq, PartialEq, Clone, Copy, Hash)]
+pub enum IntType {
+    I8,
+    I16,
+    I32,
+    I64,
+    U8,
+    U16,
+    U32,
+    U64,
+}
+
+impl IntType {
+    pub(crate) const ALL: [IntType; 8] = [
+        IntType::I8,
+        IntType::I16,
+        IntType::I32,
+        IntType::I64,
+        IntType::U8,
+        IntType::U16,
+        IntType::U32,
+        IntType::U64,
+    ];
+
+    pub(crate) fn is_signed(self) -> bool {
+        matches!(
+            self,
+            IntType::I8 | IntType::I16 | IntType::I32 | IntType::I64
+        )
+    }
+
+    /// Whether `value` is in the range of the type.
+    pub(crate) fn fits(self, value: i128) -> bool {
+        let bits = match self {
+            IntType::I8 | IntType::U8 => 8,
+            IntType::I16 | IntType::U16 => 16,
+            IntType::I32 | IntType::U32 => 32,
+            IntType::I64 | IntType::U64 => 64,
+        };
+        if self.is_signed() {
+            -(1 << (bits - 1)) <= value && value < 1 << (bits - 1)
+        } else {
+            0 <= value && value < 1 << bits
+        }
+    }
+}
+
+impl Token for IntType {
+    fn token(&self) -> &str {
+        match self {
+            IntType::I8 => "i8",
+            IntType::I16 => "i16",
+            IntType::I32 => "i32",
+            IntType::I64 => "i64",
+            IntType::U8 => "u8",
+            IntType::U16 => "u16",
+            IntType::U32 => "u32",
+            IntType::U64 => "u64",
         }
     }
 }
@@ -37,7 +110,15 @@ pub(crate) fn primitive<'a>(
     one_of(
         i,
         ptr,
-        &[&keyword_str, &keyword_num, &keyword_bln, &keyword_emp],
+        &[
+            &keyword_str,
+            &keyword_num,
+            &keyword_int,
+            &keyword_byte,
+            &keyword_bln,
+            &keyword_emp,
+            &keyword_nvr,
+        ],
     )
     .map(|res| (res.0, res.1, Type::Primitive(res.2)))
 }
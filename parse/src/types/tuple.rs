@@ -7,9 +7,15 @@ use crate::{
 
 use super::{yaupl_type, Type};
 
-#[derive(Debug, Ord, PartialOrd, Eq, PartialEq)]
+#[derive(Debug, Ord, PartialOrd, Eq, PartialEq, Clone)]
 pub struct Tuple(pub Vec<Type>);
 
+impl Tuple {
+    pub fn elements(&self) -> &[Type] {
+        &self.0
+    }
+}
+
 /// REFACTOR
 /// TODO: generalize this into a comma-seperated value function
 pub(crate) fn tuple(i: &str, ptr: Pointer) -> Result<(&str, Pointer, Type), ParseError> {
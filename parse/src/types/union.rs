@@ -0,0 +1,66 @@
+use std::collections::BTreeMap;
+
+use crate::{
+    combinators::{csv, optionally},
+    ident,
+    parse_error::ParseError,
+    pointer::Pointer,
+    tokens::{colon, variant_close, variant_open},
+    Identifier,
+};
+
+use super::{primitive::PrimitiveType, yaupl_type, Type};
+
+/// The variants of a tagged union and the type of the value each one carries.
+#[derive(Debug, Ord, PartialOrd, Eq, PartialEq, Clone)]
+pub struct Union(pub(crate) BTreeMap<Identifier, Type>);
+
+impl Union {
+    /// Every variant and the type it carries, in order of their names.
+    pub fn variants(&self) -> impl Iterator<Item = (&Identifier, &Type)> {
+        self.0.iter()
+    }
+
+    pub fn variant(&self, name: &str) -> Option<&Type> {
+        self.0.get(&Identifier::from(name))
+    }
+
+    /// `T?`, which is either `some` value of type `T` or `none`.
+    pub(crate) fn optional(found: Type) -> Union {
+        Union(
+            vec![
+                (
+                    Identifier("none".into()),
+                    Type::Primitive(PrimitiveType::Emp),
+                ),
+                (Identifier("some".into()), found),
+            ]
+            .into_iter()
+            .collect(),
+        )
+    }
+}
+
+/// A variant without a type carries `emp`. There has to be at least one variant.
+pub(crate) fn union(i: &str, ptr: Pointer) -> Result<(&str, Pointer, Type), ParseError> {
+    let (i, ptr, _bracket) = variant_open(i, ptr)?;
+    let (i, ptr, variants) = csv(i, ptr, &variant)?;
+    let (i, ptr, _bracket) = variant_close(i, ptr)?;
+    if variants.is_empty() {
+        return Err(ParseError::EmptyUnion);
+    }
+    Ok((i, ptr, Type::Union(Union(variants.into_iter().collect()))))
+}
+
+fn variant(i: &str, ptr: Pointer) -> Result<(&str, Pointer, (Identifier, Type)), ParseError> {
+    let (i, ptr, name) = ident(i, ptr)?;
+    let (i, ptr, found) = optionally(i, ptr, &|i, ptr| {
+        let (i, ptr, _colon) = colon(i, ptr)?;
+        yaupl_type(i, ptr)
+    });
+    Ok((
+        i,
+        ptr,
+        (name, found.unwrap_or(Type::Primitive(PrimitiveType::Emp))),
+    ))
+}
@@ -34,4 +34,4 @@ impl<K, V> KeyValuePair<K, V> {
     pub(crate) fn new(key: (K, Pointer), value: (V, Pointer)) -> Self {
         Self { key, value }
     }
-}
\ No newline at end of file
+}
@@ -2,25 +2,30 @@ use super::pointer::Pointer;
 
 pub(crate) fn whitespace(i: &str, ptr: Pointer) -> (&str, Pointer) {
     let mut ch_inds = i.char_indices();
-    let mut prev_ind = 0;
     let mut rows = 0;
     let mut cols = 0;
     let end_location = loop {
         match ch_inds.next() {
-            Some((ind, ch @ ('\n' | '\r' | '\t' | ' '))) => {
+            Some((_, ch @ ('\n' | '\r' | '\t' | ' '))) => {
                 cols += 1;
-                if ch == '\n' || ch == '\r' {
+                if ch == '\n' {
                     rows += 1;
                     cols = 0;
+                } else if ch == '\r' {
+                    cols = 0;
                 }
-                prev_ind = ind;
                 continue;
             }
             Some((ind, _)) => break ind,
-            None => break prev_ind,
+            None => break i.len(),
         };
     };
-    (&i[end_location..], ptr.add_col(cols).add_row(rows))
+    if rows > 0 {
+        // the column starts again on every new line
+        (&i[end_location..], Pointer::new(ptr.row + rows, cols))
+    } else {
+        (&i[end_location..], ptr.add_col(cols))
+    }
 }
 
 #[cfg(test)]
@@ -35,5 +40,10 @@ mod test_white_space {
             whitespace(" \n        hello", Pointer { row: 0, col: 0 }),
             ("hello", Pointer { row: 1, col: 8 })
         );
+        assert_eq!(
+            whitespace("\r\n  hello", Pointer { row: 3, col: 5 }),
+            ("hello", Pointer { row: 4, col: 2 })
+        );
+        assert_eq!(whitespace("  ", Pointer { row: 0, col: 0 }).0, "");
     }
 }
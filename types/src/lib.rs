@@ -1,10 +1,26 @@
-#[allow(unused_imports)]
-use std::ops::{Add, Sub};
+use std::{
+    cmp::Ordering,
+    convert::TryFrom,
+    fmt,
+    ops::{Add, Mul, Neg, Sub},
+};
+
+use self::wide::Wide;
+
+mod wide;
 
 const FRACTIONAL_WRAP: u128 = 100_000_000_000_000_000_000_000_000_000_000_000_000;
 
-#[derive(Debug, Eq, PartialEq)]
-pub(crate) enum Number {
+/// The number of decimal places a [`Number`] can represent.
+pub const FRACTIONAL_DIGITS: usize = 38;
+
+/// A fixed-point decimal with 38 digits after the decimal point.
+///
+/// The value of a `Num` is `integral + fractional / 10^38`, with `fractional` always positive, so
+/// `-1.5` is stored as `integral: -2, fractional: 0.5 * 10^38`. Anything that overflows the
+/// integral part saturates to one of the infinities.
+#[derive(Debug, Eq, PartialEq, Copy, Clone)]
+pub enum Number {
     Num { integral: i128, fractional: u128 },
     Infinity,
     NegativeInfinity,
@@ -32,6 +48,221 @@ impl Number {
             fractional: 0,
         }
     }
+
+    pub fn is_zero(&self) -> bool {
+        *self == Number::zero()
+    }
+
+    /// Returns the number as a `usize` if it's a whole number that fits in one.
+    pub fn to_usize(&self) -> Option<usize> {
+        match self {
+            Number::Num {
+                integral,
+                fractional: 0,
+            } => usize::try_from(*integral).ok(),
+            _ => None,
+        }
+    }
+
+    /// Returns the number as an `i128` if it's a whole number.
+    pub fn to_i128(&self) -> Option<i128> {
+        match self {
+            Number::Num {
+                integral,
+                fractional: 0,
+            } => Some(*integral),
+            _ => None,
+        }
+    }
+
+    fn is_negative(&self) -> bool {
+        match self {
+            Number::Num { integral, .. } => *integral < 0,
+            Number::Infinity => false,
+            Number::NegativeInfinity => true,
+        }
+    }
+
+    /// Splits a finite number into its sign and its magnitude scaled up by `10^38`.
+    fn to_scaled(self) -> Option<(bool, Wide)> {
+        match self {
+            Number::Num {
+                integral,
+                fractional,
+            } => {
+                let wrap = Wide::from_u128(FRACTIONAL_WRAP);
+                let integral_part = Wide::from_u128(integral.unsigned_abs()).mul(wrap);
+                if integral < 0 {
+                    Some((true, integral_part.sub(Wide::from_u128(fractional))))
+                } else {
+                    Some((false, integral_part.add(Wide::from_u128(fractional))))
+                }
+            }
+            Number::Infinity | Number::NegativeInfinity => None,
+        }
+    }
+
+    /// The inverse of [`Number::to_scaled`], saturating to the infinities on overflow.
+    fn from_scaled(negative: bool, magnitude: Wide) -> Self {
+        let (integral, fractional) = magnitude.div_rem(Wide::from_u128(FRACTIONAL_WRAP));
+        // the remainder is always less than the wrap, so this can't fail
+        let fractional = fractional.to_u128().unwrap();
+        let integral = match integral.to_u128() {
+            Some(integral) => integral,
+            None if negative => return Number::NegativeInfinity,
+            None => return Number::Infinity,
+        };
+
+        if !negative {
+            if integral > i128::MAX as u128 {
+                Number::Infinity
+            } else {
+                Number::from_parts(integral as i128, fractional)
+            }
+        } else if fractional == 0 {
+            // -(2^127) is still representable
+            if integral > i128::MIN.unsigned_abs() {
+                Number::NegativeInfinity
+            } else {
+                Number::from_parts((integral as i128).wrapping_neg(), 0)
+            }
+        } else if integral >= i128::MIN.unsigned_abs() {
+            Number::NegativeInfinity
+        } else {
+            // -(3 + 0.25) == -4 + 0.75
+            Number::from_parts(-(integral as i128) - 1, FRACTIONAL_WRAP - fractional)
+        }
+    }
+
+    /// Division, returning `None` if the result is undefined (`x / 0` and `oo / oo`).
+    pub fn checked_div(self, rhs: Self) -> Option<Self> {
+        if rhs.is_zero() {
+            return None;
+        }
+        let negative = self.is_negative() != rhs.is_negative();
+        match (self.to_scaled(), rhs.to_scaled()) {
+            (Some((_, lhs)), Some((_, rhs))) => Some(Number::from_scaled(
+                negative,
+                lhs.mul(Wide::from_u128(FRACTIONAL_WRAP)).div_rem(rhs).0,
+            )),
+            // x / oo = 0
+            (Some(_), None) => Some(Number::zero()),
+            // oo / x = oo
+            (None, Some(_)) if negative => Some(Number::NegativeInfinity),
+            (None, Some(_)) => Some(Number::Infinity),
+            (None, None) => None,
+        }
+    }
+
+    /// The remainder of truncating division, taking the sign of `self` (the same as `%` on the
+    /// built in integers). Returns `None` if the result is undefined (`x % 0` and `oo % x`).
+    pub fn checked_rem(self, rhs: Self) -> Option<Self> {
+        if rhs.is_zero() {
+            return None;
+        }
+        match (self.to_scaled(), rhs.to_scaled()) {
+            (Some((negative, lhs)), Some((_, rhs))) => {
+                Some(Number::from_scaled(negative, lhs.div_rem(rhs).1))
+            }
+            // x % oo = x
+            (Some(_), None) => Some(self),
+            (None, _) => None,
+        }
+    }
+}
+
+impl PartialOrd for Number {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Number {
+    fn cmp(&self, other: &Self) -> Ordering {
+        match (self, other) {
+            (
+                Number::Num {
+                    integral: lhs_integral,
+                    fractional: lhs_fractional,
+                },
+                Number::Num {
+                    integral: rhs_integral,
+                    fractional: rhs_fractional,
+                },
+            ) => lhs_integral
+                .cmp(rhs_integral)
+                .then(lhs_fractional.cmp(rhs_fractional)),
+            (Number::Infinity, Number::Infinity)
+            | (Number::NegativeInfinity, Number::NegativeInfinity) => Ordering::Equal,
+            (Number::NegativeInfinity, _) | (_, Number::Infinity) => Ordering::Less,
+            (Number::Infinity, _) | (_, Number::NegativeInfinity) => Ordering::Greater,
+        }
+    }
+}
+
+/// Written the same way as a number literal, with no trailing zeros after the decimal point, and
+/// the infinities as `oo` and `-oo`.
+impl fmt::Display for Number {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let (negative, magnitude) = match self.to_scaled() {
+            Some(scaled) => scaled,
+            None if self.is_negative() => return write!(f, "-oo"),
+            None => return write!(f, "oo"),
+        };
+        let (integral, fractional) = magnitude.div_rem(Wide::from_u128(FRACTIONAL_WRAP));
+        // both of these fit, since the magnitude came from an `i128` and a `u128` below the wrap
+        let (integral, fractional) = (integral.to_u128().unwrap(), fractional.to_u128().unwrap());
+        if negative {
+            write!(f, "-")?;
+        }
+        write!(f, "{}", integral)?;
+        if fractional != 0 {
+            let digits = format!("{:0width$}", fractional, width = FRACTIONAL_DIGITS);
+            write!(f, ".{}", digits.trim_end_matches('0'))?;
+        }
+        Ok(())
+    }
+}
+
+impl Neg for Number {
+    type Output = Number;
+
+    fn neg(self) -> Self::Output {
+        match self {
+            Number::Num { .. } => {
+                let (negative, magnitude) = self.to_scaled().unwrap();
+                Number::from_scaled(!negative, magnitude)
+            }
+            Number::Infinity => Number::NegativeInfinity,
+            Number::NegativeInfinity => Number::Infinity,
+        }
+    }
+}
+
+impl Sub for Number {
+    type Output = Number;
+
+    fn sub(self, rhs: Self) -> Self::Output {
+        self + -rhs
+    }
+}
+
+impl Mul for Number {
+    type Output = Number;
+
+    fn mul(self, rhs: Self) -> Self::Output {
+        let negative = self.is_negative() != rhs.is_negative();
+        match (self.to_scaled(), rhs.to_scaled()) {
+            (Some((_, lhs)), Some((_, rhs))) => Number::from_scaled(
+                negative,
+                lhs.mul(rhs).div_rem(Wide::from_u128(FRACTIONAL_WRAP)).0,
+            ),
+            // 0 * oo = 0, the same as oo + -oo
+            _ if self.is_zero() || rhs.is_zero() => Number::zero(),
+            _ if negative => Number::NegativeInfinity,
+            _ => Number::Infinity,
+        }
+    }
 }
 
 impl Add for Number {
@@ -120,25 +351,96 @@ mod test_number {
             Number::Infinity
         );
     }
-}
 
-// impl Sub for Number {
-//     type Output = Number;
-
-//     fn sub(self, rhs: Self) -> Self::Output {
-//         let frac = self.fractional - rhs.fractional;
-//         let int = self.integral - rhs.integral;
-//         let wrap = frac > 0;
-//         if wrap {
-//             Number {
-//                 integral: int + 1,
-//                 fractional: frac - FRACTIONAL_WRAP,
-//             }
-//         } else {
-//             Number {
-//                 integral: int,
-//                 fractional: frac,
-//             }
-//         }
-//     }
-// }
+    const HALF: u128 = FRACTIONAL_WRAP / 2;
+
+    #[test]
+    fn test_neg() {
+        assert_eq!(-Number::from_parts(3, HALF), Number::from_parts(-4, HALF));
+        assert_eq!(-Number::from_parts(-4, HALF), Number::from_parts(3, HALF));
+        assert_eq!(-Number::from_parts(2, 0), Number::from_parts(-2, 0));
+        assert_eq!(-Number::zero(), Number::zero());
+        assert_eq!(-Number::from_parts(i128::MIN, 0), Number::Infinity);
+        assert_eq!(-Number::Infinity, Number::NegativeInfinity);
+    }
+
+    #[test]
+    fn test_sub() {
+        assert_eq!(
+            Number::from_parts(1, 0) - Number::from_parts(2, HALF),
+            Number::from_parts(-2, HALF)
+        );
+    }
+
+    #[test]
+    fn test_mul() {
+        assert_eq!(
+            Number::from_parts(3, HALF) * Number::from_parts(-2, 0),
+            Number::from_parts(-7, 0)
+        );
+        assert_eq!(
+            Number::from_parts(i128::MAX, 0) * Number::from_parts(2, 0),
+            Number::Infinity
+        );
+        assert_eq!(Number::Infinity * Number::zero(), Number::zero());
+    }
+
+    #[test]
+    fn test_checked_div() {
+        assert_eq!(
+            Number::from_parts(7, 0).checked_div(Number::from_parts(2, 0)),
+            Some(Number::from_parts(3, HALF))
+        );
+        assert_eq!(Number::one().checked_div(Number::zero()), None);
+        assert_eq!(
+            Number::one().checked_div(Number::NegativeInfinity),
+            Some(Number::zero())
+        );
+    }
+
+    #[test]
+    fn test_checked_rem() {
+        assert_eq!(
+            Number::from_parts(7, 0).checked_rem(Number::from_parts(3, 0)),
+            Some(Number::one())
+        );
+        // -7 % 3 == -1
+        assert_eq!(
+            Number::from_parts(-7, 0).checked_rem(Number::from_parts(3, 0)),
+            Some(Number::from_parts(-1, 0))
+        );
+        // 5.5 % 2 == 1.5
+        assert_eq!(
+            Number::from_parts(5, HALF).checked_rem(Number::from_parts(2, 0)),
+            Some(Number::from_parts(1, HALF))
+        );
+        assert_eq!(Number::one().checked_rem(Number::zero()), None);
+        assert_eq!(Number::Infinity.checked_rem(Number::one()), None);
+    }
+
+    #[test]
+    fn test_ord() {
+        assert!(Number::from_parts(-2, HALF) < Number::from_parts(-1, 0));
+        assert!(Number::NegativeInfinity < Number::from_parts(i128::MIN, 0));
+        assert!(Number::Infinity > Number::from_parts(i128::MAX, 0));
+    }
+
+    #[test]
+    fn test_to_usize() {
+        assert_eq!(Number::from_parts(3, 0).to_usize(), Some(3));
+        assert_eq!(Number::from_parts(-3, 0).to_usize(), None);
+        assert_eq!(Number::from_parts(3, HALF).to_usize(), None);
+        assert_eq!(Number::Infinity.to_usize(), None);
+    }
+
+    #[test]
+    fn test_display() {
+        assert_eq!(Number::from_parts(-2, HALF).to_string(), "-1.5");
+        assert_eq!(Number::from_parts(12, 0).to_string(), "12");
+        assert_eq!(
+            Number::from_parts(0, 1).to_string(),
+            format!("0.{}1", "0".repeat(37))
+        );
+        assert_eq!(Number::NegativeInfinity.to_string(), "-oo");
+    }
+}
@@ -0,0 +1,134 @@
+use std::cmp::Ordering;
+
+const LIMBS: usize = 8;
+
+/// A fixed width, 512 bit unsigned integer. Only used as scratch space for the intermediate
+/// results of [`Number`](crate::Number) arithmetic, so it only implements what that needs.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub(crate) struct Wide([u64; LIMBS]);
+
+impl Wide {
+    pub(crate) fn zero() -> Self {
+        Wide([0; LIMBS])
+    }
+
+    pub(crate) fn from_u128(val: u128) -> Self {
+        let mut limbs = [0; LIMBS];
+        limbs[0] = val as u64;
+        limbs[1] = (val >> 64) as u64;
+        Wide(limbs)
+    }
+
+    /// Returns the value as a `u128`, or `None` if it doesn't fit.
+    pub(crate) fn to_u128(self) -> Option<u128> {
+        if self.0[2..].iter().any(|limb| *limb != 0) {
+            None
+        } else {
+            Some(self.0[0] as u128 | (self.0[1] as u128) << 64)
+        }
+    }
+
+    pub(crate) fn is_zero(&self) -> bool {
+        self.0.iter().all(|limb| *limb == 0)
+    }
+
+    /// Wrapping addition, the callers never get anywhere near 512 bits.
+    pub(crate) fn add(self, rhs: Self) -> Self {
+        let mut out = [0; LIMBS];
+        let mut carry = 0;
+        for (ind, limb) in out.iter_mut().enumerate() {
+            let sum = self.0[ind] as u128 + rhs.0[ind] as u128 + carry;
+            *limb = sum as u64;
+            carry = sum >> 64;
+        }
+        Wide(out)
+    }
+
+    /// Wrapping subtraction, `self` must be greater than or equal to `rhs`.
+    pub(crate) fn sub(self, rhs: Self) -> Self {
+        let mut out = [0; LIMBS];
+        let mut borrow = false;
+        for (ind, limb) in out.iter_mut().enumerate() {
+            let (diff, overflow_a) = self.0[ind].overflowing_sub(rhs.0[ind]);
+            let (diff, overflow_b) = diff.overflowing_sub(borrow as u64);
+            *limb = diff;
+            borrow = overflow_a || overflow_b;
+        }
+        Wide(out)
+    }
+
+    /// Wrapping multiplication.
+    pub(crate) fn mul(self, rhs: Self) -> Self {
+        let mut out = [0; LIMBS];
+        for i in 0..LIMBS {
+            let mut carry = 0;
+            for j in 0..(LIMBS - i) {
+                let product =
+                    self.0[i] as u128 * rhs.0[j] as u128 + out[i + j] as u128 + carry;
+                out[i + j] = product as u64;
+                carry = product >> 64;
+            }
+        }
+        Wide(out)
+    }
+
+    /// Shift-subtract long division, returning the quotient and the remainder.
+    ///
+    /// Panics if `rhs` is zero.
+    pub(crate) fn div_rem(self, rhs: Self) -> (Self, Self) {
+        assert!(!rhs.is_zero(), "attempted to divide by zero");
+        let mut quotient = Wide::zero();
+        let mut remainder = Wide::zero();
+        for bit in (0..LIMBS * 64).rev() {
+            remainder = remainder.shl1();
+            remainder.0[0] |= (self.0[bit / 64] >> (bit % 64)) & 1;
+            if remainder >= rhs {
+                remainder = remainder.sub(rhs);
+                quotient.0[bit / 64] |= 1 << (bit % 64);
+            }
+        }
+        (quotient, remainder)
+    }
+
+    fn shl1(self) -> Self {
+        let mut out = [0; LIMBS];
+        let mut carry = 0;
+        for (ind, limb) in out.iter_mut().enumerate() {
+            *limb = (self.0[ind] << 1) | carry;
+            carry = self.0[ind] >> 63;
+        }
+        Wide(out)
+    }
+}
+
+impl PartialOrd for Wide {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Wide {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.0.iter().rev().cmp(other.0.iter().rev())
+    }
+}
+
+#[cfg(test)]
+mod test_wide {
+    use super::*;
+
+    #[test]
+    fn test_mul_div_rem() {
+        let a = Wide::from_u128(u128::MAX);
+        let b = Wide::from_u128(1_000_000_007);
+        let (quotient, remainder) = a.mul(b).add(Wide::from_u128(5)).div_rem(b);
+        assert_eq!(quotient, a);
+        assert_eq!(remainder.to_u128(), Some(5));
+    }
+
+    #[test]
+    fn test_to_u128_overflow() {
+        let a = Wide::from_u128(u128::MAX);
+        assert_eq!(a.add(Wide::from_u128(1)).to_u128(), None);
+    }
+}
@@ -1,11 +1,95 @@
-use std::fs::read_to_string;
+use std::{
+    path::{Path, PathBuf},
+    process,
+};
 
-use parse::parse;
+use parse::{Loader, ModuleError, Value};
 
+/// ```sh
+/// yaupl main.yaupl -I lib -I ../shared
+/// yaupl main.yaupl --emit types
+/// ```
+///
+/// Loads `main.yaupl` and everything it imports, type checks it, runs it and prints the value of
+/// it's last statement. Each `-I` adds a directory to look for imports in. Names that are never
+/// used are warned about first, and names that aren't defined or are defined twice are errors.
+///
+/// With `--emit types` it prints the type of each name `main.yaupl` binds at the top level instead
+/// of running it, including any that were inferred.
 fn main() {
-    let args: Vec<String> = std::env::args().collect();
-    dbg!(&args);
-    let s = read_to_string("$data/test.y").unwrap();
-    let res = parse(&s);
-    println!("{:#?}", res);
+    let mut args = std::env::args().skip(1);
+    let mut file = None;
+    let mut emit_types = false;
+    let mut loader = Loader::new();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "-I" => match args.next() {
+                Some(dir) => {
+                    loader.add_search_path(dir);
+                }
+                None => usage(),
+            },
+            "--emit" => match args.next().as_deref() {
+                Some("types") => emit_types = true,
+                _ => usage(),
+            },
+            _ if file.is_none() => file = Some(PathBuf::from(arg)),
+            _ => usage(),
+        }
+    }
+    let file = file.unwrap_or_else(|| usage());
+
+    if emit_types {
+        match types(&mut loader, &file) {
+            Ok(types) => {
+                for (name, found) in types {
+                    println!("{}: {}", name, found);
+                }
+            }
+            Err(error) => {
+                eprintln!("error: {}", error);
+                process::exit(1);
+            }
+        }
+        return;
+    }
+
+    match run(&mut loader, &file) {
+        Ok(value) => println!("{:#?}", value),
+        Err(error) => {
+            eprintln!("error: {}", error);
+            process::exit(1);
+        }
+    }
+}
+
+fn run(loader: &mut Loader, file: &Path) -> Result<Value, ModuleError> {
+    let root = loader.load(file)?;
+    warn(loader, &root);
+    loader.check(&root)?;
+    loader.eval(&root)
+}
+
+/// The type of each top level binding, written out.
+fn types(loader: &mut Loader, file: &Path) -> Result<Vec<(String, String)>, ModuleError> {
+    let root = loader.load(file)?;
+    warn(loader, &root);
+    Ok(loader
+        .binding_types(&root)?
+        .into_iter()
+        .map(|(name, found)| (name.to_string(), found.to_string()))
+        .collect())
+}
+
+/// Prints every unused name in the module and everything it imports. Errors in how names are
+/// used are found when the module is checked.
+fn warn(loader: &Loader, root: &Path) {
+    for (path, warning) in loader.warnings(root) {
+        eprintln!("warning: {}:{}", path.display(), warning);
+    }
+}
+
+fn usage() -> ! {
+    eprintln!("usage: yaupl <file> [-I <dir>]... [--emit types]");
+    process::exit(2);
 }